@@ -0,0 +1,83 @@
+use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::prelude::*;
+
+mod json;
+pub use json::{JsonFields, JsonFormat};
+
+/// Output style of the global subscriber, chosen with `LOG_FORMAT`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Multi-field human-readable lines (default)
+    Pretty,
+    /// Single-line condensed output
+    Compact,
+    /// One JSON object per line, for log aggregators
+    Json,
+}
+
+impl LogFormat {
+    /// Read `LOG_FORMAT`; see `parse`
+    pub fn from_env() -> Self {
+        Self::parse(std::env::var("LOG_FORMAT").ok().as_deref())
+    }
+
+    /// Case-insensitive `"compact"` or `"json"`; anything else, or no value, is `Pretty`
+    pub fn parse(value: Option<&str>) -> Self {
+        match value.map(str::to_lowercase).as_deref() {
+            Some("compact") => LogFormat::Compact,
+            Some("json") => LogFormat::Json,
+            _ => LogFormat::Pretty,
+        }
+    }
+}
+
+/// Level filter from `RUST_LOG`, then `LOG_LEVEL`, defaulting to INFO
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_from_env("LOG_LEVEL"))
+        .unwrap_or_else(|_| EnvFilter::new("info"))
+}
+
+/// Install the global subscriber shared by every binary.
+///
+/// Safe to call more than once: later calls leave the first subscriber in place.
+pub fn init() {
+    let registry = tracing_subscriber::registry().with(env_filter());
+
+    let result = match LogFormat::from_env() {
+        LogFormat::Pretty => registry
+            .with(fmt::layer().with_target(true).with_thread_ids(false).with_file(false))
+            .try_init(),
+        LogFormat::Compact => registry
+            .with(fmt::layer().compact().with_target(false))
+            .try_init(),
+        LogFormat::Json => registry
+            .with(fmt::layer().event_format(JsonFormat).fmt_fields(JsonFields))
+            .try_init(),
+    };
+
+    if result.is_err() {
+        tracing::debug!("global logger already initialized");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_can_be_called_twice() {
+        init();
+        init();
+        tracing::info!("still logging after a second init");
+    }
+
+    #[test]
+    fn parse_picks_the_format_case_insensitively_and_defaults_to_pretty() {
+        assert_eq!(LogFormat::parse(Some("JSON")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some("json")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some("compact")), LogFormat::Compact);
+        assert_eq!(LogFormat::parse(Some("yaml")), LogFormat::Pretty);
+        assert_eq!(LogFormat::parse(None), LogFormat::Pretty);
+    }
+}
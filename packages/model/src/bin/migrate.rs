@@ -0,0 +1,10 @@
+// Standalone migration runner: `cargo run -p model --bin migrate -- up|down|status|fresh`
+// Reads DATABASE_URL (from .env when present) like the server does
+use model::migration::Migrator;
+use sea_orm_migration::cli;
+
+#[tokio::main]
+async fn main() {
+    let _ = dotenvy::dotenv();
+    cli::run_cli(Migrator).await;
+}
@@ -0,0 +1,34 @@
+//! Periodic purges of rows that are past their useful life. Each runs as a single bulk
+//! statement and returns how many rows it touched.
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+use crate::models::{session, user};
+
+/// How long a password reset code stays usable after it's sent
+pub const RESET_CODE_LIFETIME: Duration = Duration::days(7);
+
+/// Delete sessions (revoked or not) whose refresh token expired before `now`
+pub async fn delete_expired_sessions(db: &DatabaseConnection, now: DateTime<Utc>) -> Result<u64, DbErr> {
+    let result = session::entity::Entity::delete_many()
+        .filter(session::entity::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Clear reset codes sent more than `RESET_CODE_LIFETIME` before `now`, along with their attempt counters.
+/// Only rows holding a code are touched: bans record their time in the same `peripheral_timeout` column
+pub async fn clear_expired_reset_codes(db: &DatabaseConnection, now: DateTime<Utc>) -> Result<u64, DbErr> {
+    let result = user::entity::Entity::update_many()
+        .col_expr(user::entity::Column::PeripheralAuthenticationCode, Expr::value(Option::<String>::None))
+        .col_expr(user::entity::Column::PeripheralTimeout, Expr::value(Option::<DateTime<Utc>>::None))
+        .col_expr(user::entity::Column::PeripheralFailedCodeAttempts, Expr::value(0))
+        .filter(user::entity::Column::PeripheralAuthenticationCode.is_not_null())
+        .filter(user::entity::Column::PeripheralTimeout.lt(now - RESET_CODE_LIFETIME))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
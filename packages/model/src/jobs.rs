@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// A payload that can travel through the queue inside a `JobEnvelope`
+pub trait Job: Serialize + DeserializeOwned {
+    /// Identifies the payload type on the wire
+    const KIND: &'static str;
+    /// Bumped whenever the payload shape changes incompatibly
+    const VERSION: u8;
+}
+
+#[derive(Debug)]
+pub enum JobError {
+    Malformed(String),
+    UnknownKind(String),
+    UnsupportedVersion { kind: String, version: u8 },
+}
+
+impl std::fmt::Display for JobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            JobError::Malformed(msg) => write!(f, "Malformed job: {}", msg),
+            JobError::UnknownKind(kind) => write!(f, "Unknown job kind: {}", kind),
+            JobError::UnsupportedVersion { kind, version } => {
+                write!(f, "Unsupported version {} of job kind {}", version, kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JobError {}
+
+/// Versioned wrapper for every message published to a job queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobEnvelope<T> {
+    pub version: u8,
+    pub kind: String,
+    pub payload: T,
+    /// `request_id` of the HTTP request that enqueued the job, so worker logs can be tied back to it
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    pub enqueued_at: DateTime<Utc>,
+}
+
+/// Envelope fields checked before the payload is parsed
+#[derive(Deserialize)]
+struct EnvelopeHeader {
+    version: u8,
+    kind: String,
+}
+
+impl<T: Job> JobEnvelope<T> {
+    pub fn new(payload: T, correlation_id: Option<String>) -> Self {
+        Self {
+            version: T::VERSION,
+            kind: T::KIND.to_string(),
+            payload,
+            correlation_id,
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JobError> {
+        serde_json::to_vec(self).map_err(|e| JobError::Malformed(e.to_string()))
+    }
+
+    /// Parse an envelope of `T`, rejecting other kinds and versions before touching the payload
+    pub fn from_bytes(data: &[u8]) -> Result<Self, JobError> {
+        let header: EnvelopeHeader = serde_json::from_slice(data).map_err(|e| JobError::Malformed(e.to_string()))?;
+        if header.kind != T::KIND {
+            return Err(JobError::UnknownKind(header.kind));
+        }
+        if header.version != T::VERSION {
+            return Err(JobError::UnsupportedVersion { kind: header.kind, version: header.version });
+        }
+
+        serde_json::from_slice(data).map_err(|e| JobError::Malformed(e.to_string()))
+    }
+}
+
+/// Email for the worker to render and deliver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailJob {
+    pub to: String,
+    /// Template name understood by the mailer (e.g. "reset_code")
+    pub template: String,
+    pub context: HashMap<String, String>,
+}
+
+impl Job for EmailJob {
+    const KIND: &'static str = "email";
+    const VERSION: u8 = 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job() -> EmailJob {
+        EmailJob {
+            to: "ada@example.com".to_string(),
+            template: "reset_code".to_string(),
+            context: HashMap::from([("code".to_string(), "482913".to_string())]),
+        }
+    }
+
+    #[test]
+    fn email_job_round_trips_through_its_envelope() {
+        let envelope = JobEnvelope::new(job(), Some("req-42".to_string()));
+        let decoded = JobEnvelope::<EmailJob>::from_bytes(&envelope.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(decoded.version, EmailJob::VERSION);
+        assert_eq!(decoded.kind, "email");
+        assert_eq!(decoded.correlation_id.as_deref(), Some("req-42"));
+        assert_eq!(decoded.enqueued_at, envelope.enqueued_at);
+        assert_eq!(decoded.payload.to, "ada@example.com");
+        assert_eq!(decoded.payload.context["code"], "482913");
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut value = serde_json::to_value(JobEnvelope::new(job(), None)).unwrap();
+        value["version"] = (EmailJob::VERSION + 1).into();
+
+        let result = JobEnvelope::<EmailJob>::from_bytes(&serde_json::to_vec(&value).unwrap());
+        assert!(matches!(result, Err(JobError::UnsupportedVersion { version, .. }) if version == EmailJob::VERSION + 1));
+    }
+
+    #[test]
+    fn unknown_kind_and_garbage_are_rejected() {
+        let mut value = serde_json::to_value(JobEnvelope::new(job(), None)).unwrap();
+        value["kind"] = "sms".into();
+        let result = JobEnvelope::<EmailJob>::from_bytes(&serde_json::to_vec(&value).unwrap());
+        assert!(matches!(result, Err(JobError::UnknownKind(kind)) if kind == "sms"));
+
+        assert!(matches!(JobEnvelope::<EmailJob>::from_bytes(b"not json"), Err(JobError::Malformed(_))));
+    }
+}
@@ -1,5 +1,9 @@
 // Expose the root models module (directory: packages/model/src/models)
 pub mod models;
+// Versioned job envelopes shared by queue publishers and the worker
+pub mod jobs;
+// Bulk purges run by the app's cleanup scheduler
+pub mod cleanup;
 // Shared pagination and compatibility module lives in `shared.rs`
 pub mod migration;
 pub mod shared;
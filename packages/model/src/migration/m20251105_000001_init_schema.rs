@@ -1,5 +1,5 @@
 use sea_orm_migration::prelude::*;
-use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use sea_orm::ConnectionTrait;
 
 #[derive(DeriveMigrationName)]
 pub struct Migration;
@@ -8,10 +8,11 @@ pub struct Migration;
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
 
         // admins
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS admins (
                 id uuid PRIMARY KEY,
                 email_address text NOT NULL,
@@ -23,8 +24,8 @@ impl MigrationTrait for Migration {
         )).await?;
 
         // users
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS users (
                 id uuid PRIMARY KEY,
                 personal_first_name text NOT NULL,
@@ -55,8 +56,8 @@ impl MigrationTrait for Migration {
         )).await?;
 
         // organizations
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS organizations (
                 id uuid PRIMARY KEY,
                 name text NOT NULL,
@@ -75,8 +76,8 @@ impl MigrationTrait for Migration {
         )).await?;
 
         // organization_users
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS organization_users (
                 id uuid PRIMARY KEY,
                 user_id uuid NOT NULL REFERENCES users(id),
@@ -90,8 +91,8 @@ impl MigrationTrait for Migration {
         )).await?;
 
         // projects
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS projects (
                 id uuid PRIMARY KEY,
                 organization_id uuid NOT NULL REFERENCES organizations(id),
@@ -110,8 +111,8 @@ impl MigrationTrait for Migration {
         )).await?;
 
         // project_users
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS project_users (
                 id uuid PRIMARY KEY,
                 organization_id uuid NOT NULL REFERENCES organizations(id),
@@ -126,8 +127,8 @@ impl MigrationTrait for Migration {
         )).await?;
 
         // integrations
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS integrations (
                 id uuid PRIMARY KEY,
                 type text NOT NULL,
@@ -149,8 +150,8 @@ impl MigrationTrait for Migration {
         )).await?;
 
         // billings
-        conn.execute(Statement::from_string(
-            DatabaseBackend::Postgres,
+        conn.execute(super::statement(
+            backend,
             r#"CREATE TABLE IF NOT EXISTS billings (
                 id uuid PRIMARY KEY,
                 organization_id uuid NOT NULL REFERENCES organizations(id),
@@ -169,7 +170,9 @@ impl MigrationTrait for Migration {
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        super::ensure_destructive_allowed()?;
         let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
 
         // Drop in order to satisfy FKs
         for stmt in [
@@ -182,7 +185,7 @@ impl MigrationTrait for Migration {
             r#"DROP TABLE IF EXISTS users CASCADE;"#,
             r#"DROP TABLE IF EXISTS admins CASCADE;"#,
         ] {
-            conn.execute(Statement::from_string(DatabaseBackend::Postgres, stmt)).await?;
+            conn.execute(super::statement(backend, stmt)).await?;
         }
 
         Ok(())
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // wallets
+        conn.execute(super::statement(
+            backend,
+            r#"CREATE TABLE IF NOT EXISTS wallets (
+                id uuid PRIMARY KEY,
+                user_id uuid NOT NULL REFERENCES users(id),
+                address text NOT NULL UNIQUE,
+                encrypted_private_key text NOT NULL,
+                encrypted_seed_phrase text NOT NULL,
+                created_at timestamptz NOT NULL,
+                updated_at timestamptz NOT NULL,
+                deleted_at timestamptz
+            );"#,
+        )).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        super::ensure_destructive_allowed()?;
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        conn.execute(super::statement(
+            backend,
+            r#"DROP TABLE IF EXISTS wallets CASCADE;"#,
+        )).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // users: failed reset-code attempts since the last code was issued
+        conn.execute(super::statement(
+            backend,
+            r#"ALTER TABLE users
+                ADD COLUMN IF NOT EXISTS peripheral_failed_code_attempts integer NOT NULL DEFAULT 0;"#,
+        )).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        super::ensure_destructive_allowed()?;
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        conn.execute(super::statement(
+            backend,
+            r#"ALTER TABLE users DROP COLUMN IF EXISTS peripheral_failed_code_attempts;"#,
+        )).await?;
+
+        Ok(())
+    }
+}
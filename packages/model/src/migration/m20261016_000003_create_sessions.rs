@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // sessions: one row per issued refresh token, keyed by its jti
+        for stmt in [
+            r#"CREATE TABLE IF NOT EXISTS sessions (
+                jti uuid PRIMARY KEY,
+                user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                user_agent text,
+                ip text,
+                created_at timestamptz NOT NULL,
+                expires_at timestamptz NOT NULL,
+                revoked_at timestamptz
+            );"#,
+            r#"CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id);"#,
+        ] {
+            conn.execute(super::statement(backend, stmt)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        super::ensure_destructive_allowed()?;
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        conn.execute(super::statement(
+            backend,
+            r#"DROP TABLE IF EXISTS sessions CASCADE;"#,
+        )).await?;
+
+        Ok(())
+    }
+}
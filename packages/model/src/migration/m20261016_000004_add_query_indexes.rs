@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // Listing by creation date, soft-delete filters and organization foreign keys
+        for stmt in [
+            r#"CREATE INDEX IF NOT EXISTS idx_users_created_at ON users(created_at);"#,
+            r#"CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at);"#,
+            r#"CREATE INDEX IF NOT EXISTS idx_projects_organization_id ON projects(organization_id);"#,
+            r#"CREATE INDEX IF NOT EXISTS idx_organization_users_user_id ON organization_users(user_id);"#,
+            r#"CREATE INDEX IF NOT EXISTS idx_organization_users_organization_id ON organization_users(organization_id);"#,
+        ] {
+            conn.execute(super::statement(backend, stmt)).await?;
+        }
+
+        Ok(())
+    }
+
+    // Dropping indexes loses no data, so this one isn't behind ALLOW_DESTRUCTIVE_MIGRATIONS
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        for stmt in [
+            r#"DROP INDEX IF EXISTS idx_organization_users_organization_id;"#,
+            r#"DROP INDEX IF EXISTS idx_organization_users_user_id;"#,
+            r#"DROP INDEX IF EXISTS idx_projects_organization_id;"#,
+            r#"DROP INDEX IF EXISTS idx_users_deleted_at;"#,
+            r#"DROP INDEX IF EXISTS idx_users_created_at;"#,
+        ] {
+            conn.execute(super::statement(backend, stmt)).await?;
+        }
+
+        Ok(())
+    }
+}
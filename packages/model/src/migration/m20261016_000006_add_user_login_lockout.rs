@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // users: consecutive wrong passwords and the temporary lock they trigger
+        for stmt in [
+            r#"ALTER TABLE users
+                ADD COLUMN IF NOT EXISTS failed_login_attempts integer NOT NULL DEFAULT 0;"#,
+            r#"ALTER TABLE users
+                ADD COLUMN IF NOT EXISTS locked_until timestamptz;"#,
+        ] {
+            conn.execute(super::statement(backend, stmt)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        super::ensure_destructive_allowed()?;
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        for stmt in [
+            r#"ALTER TABLE users DROP COLUMN IF EXISTS locked_until;"#,
+            r#"ALTER TABLE users DROP COLUMN IF EXISTS failed_login_attempts;"#,
+        ] {
+            conn.execute(super::statement(backend, stmt)).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::{ConnectionTrait, DatabaseBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // users.personal_user_roles isn't mapped by the entity, so inserts need a default for it.
+        // SQLite can't alter a column's default, so the (always empty) column is re-created there
+        let stmts: &[&str] = match backend {
+            DatabaseBackend::Sqlite => &[
+                r#"ALTER TABLE users DROP COLUMN personal_user_roles;"#,
+                r#"ALTER TABLE users ADD COLUMN personal_user_roles text NOT NULL DEFAULT '[]';"#,
+            ],
+            _ => &[r#"ALTER TABLE users ALTER COLUMN personal_user_roles SET DEFAULT '{}';"#],
+        };
+        for stmt in stmts {
+            conn.execute(super::statement(backend, stmt)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // Keeping the default on SQLite is harmless and avoids another column rebuild
+        if backend != DatabaseBackend::Sqlite {
+            conn.execute(super::statement(
+                backend,
+                r#"ALTER TABLE users ALTER COLUMN personal_user_roles DROP DEFAULT;"#,
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,59 @@
 pub use sea_orm_migration::prelude::*;
+use sea_orm::{DatabaseBackend, Statement};
 
 mod m20251105_000001_init_schema;
+mod m20261016_000001_create_wallets;
+mod m20261016_000002_add_user_code_attempts;
+mod m20261016_000003_create_sessions;
+mod m20261016_000004_add_query_indexes;
+mod m20261016_000005_add_user_last_login;
+mod m20261016_000006_add_user_login_lockout;
+mod m20261016_000007_default_user_roles;
+
+/// Refuse to run a `down` migration (they drop tables and columns along with their data)
+/// unless `ALLOW_DESTRUCTIVE_MIGRATIONS=true` is set; `up` is never guarded
+pub fn ensure_destructive_allowed() -> Result<(), DbErr> {
+    match std::env::var("ALLOW_DESTRUCTIVE_MIGRATIONS").as_deref() {
+        Ok("true") => Ok(()),
+        _ => Err(DbErr::Custom(
+            "down migrations drop data; set ALLOW_DESTRUCTIVE_MIGRATIONS=true to run them".to_string(),
+        )),
+    }
+}
+
+/// Build a migration statement for `backend`. Migrations are written in Postgres DDL; on SQLite
+/// it's rewritten to equivalents: `text[]`/`jsonb` become JSON text, `timestamptz` becomes text,
+/// and the Postgres-only `CASCADE` on drops and `IF [NOT] EXISTS` on added/dropped columns are removed
+pub fn statement(backend: DatabaseBackend, sql: &str) -> Statement {
+    match backend {
+        DatabaseBackend::Sqlite => Statement::from_string(backend, sqlite_ddl(sql)),
+        _ => Statement::from_string(backend, sql),
+    }
+}
+
+fn sqlite_ddl(sql: &str) -> String {
+    sql.replace("text[]", "text")
+        .replace("jsonb", "text")
+        .replace("timestamptz", "text")
+        .replace("ADD COLUMN IF NOT EXISTS", "ADD COLUMN")
+        .replace("DROP COLUMN IF EXISTS", "DROP COLUMN")
+        .replace(" CASCADE;", ";")
+}
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20251105_000001_init_schema::Migration)]
+        vec![
+            Box::new(m20251105_000001_init_schema::Migration),
+            Box::new(m20261016_000001_create_wallets::Migration),
+            Box::new(m20261016_000002_add_user_code_attempts::Migration),
+            Box::new(m20261016_000003_create_sessions::Migration),
+            Box::new(m20261016_000004_add_query_indexes::Migration),
+            Box::new(m20261016_000005_add_user_last_login::Migration),
+            Box::new(m20261016_000006_add_user_login_lockout::Migration),
+            Box::new(m20261016_000007_default_user_roles::Migration),
+        ]
     }
 }
@@ -43,9 +43,9 @@ impl From<Admin> for ActiveModel {
             id: Set(admin.id),
             email_address: Set(admin.email_address),
             password: Set(admin.password),
-            created_at: Set(Utc::now().into()),
+            created_at: Set(admin.created_at.into()),
             updated_at: Set(Utc::now().into()),
-            deleted_at: Set(None),
+            deleted_at: Set(admin.deleted_at.map(|dt| dt.into())),
         }
     }
 }
\ No newline at end of file
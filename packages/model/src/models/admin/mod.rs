@@ -16,6 +16,37 @@ pub struct Admin {
     pub deleted_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminLoginRequest {
+    pub email_address: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminAuthResponse {
+    pub id: String,
+    pub access_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+    pub confirm_password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminChangePasswordResponse {
+    pub id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserListQuery {
+    /// Case-insensitive substring matched against the email address
+    pub q: Option<String>,
+}
+
 // Unified paginated response alias
 pub type AdminsPage = PaginatedResponse<Admin>;
 
@@ -1,7 +1,9 @@
-use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, ActiveModelTrait};
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait};
 use async_trait::async_trait;
 use uuid::Uuid;
 use crate::models::admin::{self, entity::Entity as AdminEntity, entity::Model as AdminModel};
+use crate::models::base::BaseRepository;
+use crate::models::ModelError;
 
 #[derive(Debug)]
 pub enum AdminRepositoryError {
@@ -22,6 +24,16 @@ impl std::fmt::Display for AdminRepositoryError {
 
 impl std::error::Error for AdminRepositoryError {}
 
+impl From<ModelError> for AdminRepositoryError {
+    fn from(e: ModelError) -> Self {
+        match e {
+            ModelError::NotFound(msg) => AdminRepositoryError::NotFound(msg),
+            ModelError::Duplicate(_) => AdminRepositoryError::Duplicate("Admin with this email already exists".to_string()),
+            ModelError::DatabaseError(msg) => AdminRepositoryError::DatabaseError(msg),
+        }
+    }
+}
+
 #[async_trait]
 pub trait AdminRepositoryTrait {
     async fn create(&self, admin: AdminModel) -> Result<AdminModel, AdminRepositoryError>;
@@ -34,45 +46,29 @@ pub trait AdminRepositoryTrait {
 
 #[derive(Clone)]
 pub struct AdminRepository {
-    db: DatabaseConnection,
+    base: BaseRepository<AdminEntity>,
 }
 
 impl AdminRepository {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { base: BaseRepository::new(db, "Admin", admin::entity::Column::UpdatedAt) }
     }
 }
 
 #[async_trait]
 impl AdminRepositoryTrait for AdminRepository {
     async fn create(&self, admin: AdminModel) -> Result<AdminModel, AdminRepositoryError> {
-        let active_model: admin::entity::ActiveModel = admin.clone().into();
-
-        match active_model.insert(&self.db).await {
-            Ok(inserted) => Ok(inserted),
-            Err(e) => {
-                let error_msg = e.to_string();
-                if error_msg.contains("duplicate") || error_msg.contains("unique") {
-                    Err(AdminRepositoryError::Duplicate("Admin with this email already exists".to_string()))
-                } else {
-                    Err(AdminRepositoryError::DatabaseError(error_msg))
-                }
-            }
-        }
+        Ok(self.base.create(admin).await?)
     }
 
     async fn get_by_id(&self, id: Uuid) -> Result<AdminModel, AdminRepositoryError> {
-        match AdminEntity::find_by_id(id).one(&self.db).await {
-            Ok(Some(admin)) => Ok(admin),
-            Ok(None) => Err(AdminRepositoryError::NotFound(format!("Admin with id {} not found", id))),
-            Err(e) => Err(AdminRepositoryError::DatabaseError(e.to_string())),
-        }
+        Ok(self.base.get_by_id(id).await?)
     }
 
     async fn get_by_email(&self, email: &str) -> Result<AdminModel, AdminRepositoryError> {
         match AdminEntity::find()
             .filter(admin::entity::Column::EmailAddress.eq(email))
-            .one(&self.db)
+            .one(self.base.db())
             .await
         {
             Ok(Some(admin)) => Ok(admin),
@@ -82,23 +78,15 @@ impl AdminRepositoryTrait for AdminRepository {
     }
 
     async fn update(&self, admin: AdminModel) -> Result<AdminModel, AdminRepositoryError> {
-        let active_model: admin::entity::ActiveModel = admin.clone().into();
-
-        match active_model.update(&self.db).await {
-            Ok(updated) => Ok(updated),
-            Err(e) => Err(AdminRepositoryError::DatabaseError(e.to_string())),
-        }
+        Ok(self.base.update(admin).await?)
     }
 
     async fn delete(&self, id: Uuid) -> Result<(), AdminRepositoryError> {
-        match AdminEntity::delete_by_id(id).exec(&self.db).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(AdminRepositoryError::DatabaseError(e.to_string())),
-        }
+        Ok(self.base.delete(id).await?)
     }
 
     async fn list_all(&self) -> Result<Vec<AdminModel>, AdminRepositoryError> {
-        match AdminEntity::find().all(&self.db).await {
+        match AdminEntity::find().all(self.base.db()).await {
             Ok(admins) => Ok(admins),
             Err(e) => Err(AdminRepositoryError::DatabaseError(e.to_string())),
         }
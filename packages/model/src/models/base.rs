@@ -0,0 +1,92 @@
+use std::marker::PhantomData;
+
+use sea_orm::{
+    sea_query::Value, ActiveModelBehavior, ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel,
+    PrimaryKeyTrait, SqlErr,
+};
+use uuid::Uuid;
+
+use crate::models::ModelError;
+
+/// Create/get/update/delete by id for any uuid-keyed entity, with the shared error mapping:
+/// unique violations become `Duplicate`, missing rows `NotFound`, everything else `DatabaseError`.
+/// Concrete repositories wrap one and keep their own finders
+pub struct BaseRepository<E: EntityTrait> {
+    db: DatabaseConnection,
+    /// Entity name used in error messages, e.g. "User"
+    name: &'static str,
+    /// Column stamped with the current time on every update
+    updated_at: E::Column,
+    _entity: PhantomData<fn() -> E>,
+}
+
+impl<E: EntityTrait> Clone for BaseRepository<E> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            name: self.name,
+            updated_at: self.updated_at,
+            _entity: PhantomData,
+        }
+    }
+}
+
+impl<E> BaseRepository<E>
+where
+    E: EntityTrait,
+    E::Model: IntoActiveModel<E::ActiveModel> + Send + Sync,
+    E::ActiveModel: ActiveModelBehavior + Send,
+    Uuid: Into<<E::PrimaryKey as PrimaryKeyTrait>::ValueType>,
+{
+    pub fn new(db: DatabaseConnection, name: &'static str, updated_at: E::Column) -> Self {
+        Self { db, name, updated_at, _entity: PhantomData }
+    }
+
+    pub fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    pub async fn create(&self, model: E::Model) -> Result<E::Model, ModelError> {
+        model
+            .into_active_model()
+            .insert(&self.db)
+            .await
+            .map_err(|e| self.map_write_error(e))
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<E::Model, ModelError> {
+        match E::find_by_id(id).one(&self.db).await {
+            Ok(Some(model)) => Ok(model),
+            Ok(None) => Err(ModelError::NotFound(format!("{} with id {} not found", self.name, id))),
+            Err(e) => Err(ModelError::DatabaseError(e.to_string())),
+        }
+    }
+
+    pub async fn update(&self, model: E::Model) -> Result<E::Model, ModelError> {
+        // A model converts into an all-Unchanged active model, which sea-orm treats as a no-op;
+        // mark every column as set so the caller's edits are written.
+        let mut active_model = model.into_active_model().reset_all();
+        let now: sea_orm::prelude::DateTimeWithTimeZone = chrono::Utc::now().into();
+        active_model.set(self.updated_at, Value::from(now));
+        active_model.update(&self.db).await.map_err(|e| self.map_write_error(e))
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), ModelError> {
+        match E::delete_by_id(id).exec(&self.db).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ModelError::DatabaseError(e.to_string())),
+        }
+    }
+
+    fn map_write_error(&self, e: DbErr) -> ModelError {
+        if let Some(SqlErr::UniqueConstraintViolation(_)) = e.sql_err() {
+            return ModelError::Duplicate(format!("{} already exists", self.name));
+        }
+        let error_msg = e.to_string();
+        if error_msg.contains("duplicate") || error_msg.contains("unique") {
+            ModelError::Duplicate(format!("{} already exists", self.name))
+        } else {
+            ModelError::DatabaseError(error_msg)
+        }
+    }
+}
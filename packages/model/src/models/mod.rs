@@ -3,8 +3,13 @@ use sea_orm::{DatabaseConnection, Database, DbErr};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub mod base;
 pub mod user;
 pub mod admin;
+pub mod wallet;
+pub mod session;
+pub mod organization;
+pub mod organization_user;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Timestamps {
@@ -56,16 +61,29 @@ pub struct Models {
     pub db: DatabaseConnection,
     pub user: user::repo::UserRepository,
     pub admin: admin::repo::AdminRepository,
+    pub wallet: wallet::repo::WalletRepository,
+    pub session: session::repo::SessionRepository,
+    pub organization: organization::repo::OrganizationRepository,
+    pub organization_user: organization_user::repo::OrganizationUserRepository,
 }
 
 impl Models {
     pub async fn new(database_url: &str) -> Result<Self, DbErr> {
         let db = Database::connect(database_url).await?;
-        Ok(Self {
+        Ok(Self::from_connection(db))
+    }
+
+    /// Build every repository on an already-open connection, e.g. one shared with migrations
+    pub fn from_connection(db: DatabaseConnection) -> Self {
+        Self {
             user: user::repo::UserRepository::new(db.clone()),
             admin: admin::repo::AdminRepository::new(db.clone()),
+            wallet: wallet::repo::WalletRepository::new(db.clone()),
+            session: session::repo::SessionRepository::new(db.clone()),
+            organization: organization::repo::OrganizationRepository::new(db.clone()),
+            organization_user: organization_user::repo::OrganizationUserRepository::new(db.clone()),
             db,
-        })
+        }
     }
 }
 
@@ -0,0 +1,84 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::Utc;
+
+use super::Organization;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "organizations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+
+    // jsonb documents
+    #[sea_orm(column_type = "JsonBinary")]
+    pub template: Json,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub project_template: Json,
+
+    pub stage: String,
+    pub status: String,
+    pub members: i32,
+    pub creator_id: Uuid,
+
+    #[sea_orm(column_type = "JsonBinary")]
+    pub settings: Json,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::models::user::entity::Entity",
+        from = "Column::CreatorId",
+        to = "crate::models::user::entity::Column::Id"
+    )]
+    Creator,
+    #[sea_orm(has_many = "crate::models::organization_user::entity::Entity")]
+    OrganizationUsers,
+}
+
+impl Related<crate::models::organization_user::entity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OrganizationUsers.def()
+    }
+}
+
+/// Members of the organization, joined through `organization_users`
+impl Related<crate::models::user::entity::Entity> for Entity {
+    fn to() -> RelationDef {
+        crate::models::organization_user::entity::Relation::User.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(crate::models::organization_user::entity::Relation::Organization.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for Organization {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            description: model.description,
+            template: model.template,
+            project_template: model.project_template,
+            stage: model.stage,
+            status: model.status,
+            members: model.members,
+            creator_id: model.creator_id,
+            settings: model.settings,
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+            deleted_at: model.deleted_at.map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+}
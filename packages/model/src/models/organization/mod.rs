@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod entity;
+pub mod repo;
+pub mod schema;
+
+/// A tenant: owns projects, billing and integrations, and has members through `organization_users`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub template: serde_json::Value,
+    pub project_template: serde_json::Value,
+    pub stage: String,
+    pub status: String,
+    pub members: i32,
+    pub creator_id: Uuid,
+    pub settings: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
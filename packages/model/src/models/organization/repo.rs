@@ -0,0 +1,149 @@
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, ColumnTrait, ActiveModelTrait, Order, PaginatorTrait};
+use sea_orm::ActiveValue::Set;
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::models::organization_user;
+use crate::models::organization::{entity, schema, entity::Entity as OrganizationEntity, entity::Model as OrganizationModel};
+use crate::shared::{PaginationOptions, PaginatedResponse};
+
+#[derive(Debug)]
+pub enum OrganizationRepositoryError {
+    NotFound(String),
+    Duplicate(String),
+    /// A jsonb column doesn't match its struct in `organization::schema`
+    InvalidDocument(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for OrganizationRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OrganizationRepositoryError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            OrganizationRepositoryError::Duplicate(msg) => write!(f, "Duplicate: {}", msg),
+            OrganizationRepositoryError::InvalidDocument(msg) => write!(f, "Invalid document: {}", msg),
+            OrganizationRepositoryError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for OrganizationRepositoryError {}
+
+#[async_trait]
+pub trait OrganizationRepositoryTrait {
+    async fn create(&self, organization: OrganizationModel) -> Result<OrganizationModel, OrganizationRepositoryError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<OrganizationModel, OrganizationRepositoryError>;
+    async fn update(&self, organization: OrganizationModel) -> Result<OrganizationModel, OrganizationRepositoryError>;
+    /// Organizations that aren't soft-deleted, newest first unless `options` say otherwise
+    async fn list(&self, options: &PaginationOptions) -> Result<PaginatedResponse<OrganizationModel>, OrganizationRepositoryError>;
+    async fn list_by_creator(&self, creator_id: Uuid) -> Result<Vec<OrganizationModel>, OrganizationRepositoryError>;
+    /// Organizations the user is an active member of, in a single joined query
+    async fn list_by_member(&self, user_id: Uuid) -> Result<Vec<OrganizationModel>, OrganizationRepositoryError>;
+}
+
+#[derive(Clone)]
+pub struct OrganizationRepository {
+    db: DatabaseConnection,
+}
+
+impl OrganizationRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OrganizationRepositoryTrait for OrganizationRepository {
+    async fn create(&self, organization: OrganizationModel) -> Result<OrganizationModel, OrganizationRepositoryError> {
+        schema::validate(&organization).map_err(OrganizationRepositoryError::InvalidDocument)?;
+        let active_model: entity::ActiveModel = organization.into();
+        match active_model.insert(&self.db).await {
+            Ok(inserted) => Ok(inserted),
+            Err(e) => {
+                let error_msg = e.to_string();
+                if error_msg.contains("duplicate") || error_msg.contains("unique") {
+                    Err(OrganizationRepositoryError::Duplicate("Organization already exists".to_string()))
+                } else {
+                    Err(OrganizationRepositoryError::DatabaseError(error_msg))
+                }
+            }
+        }
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<OrganizationModel, OrganizationRepositoryError> {
+        match OrganizationEntity::find_by_id(id)
+            .filter(entity::Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+        {
+            Ok(Some(organization)) => Ok(organization),
+            Ok(None) => Err(OrganizationRepositoryError::NotFound(format!("Organization with id {} not found", id))),
+            Err(e) => Err(OrganizationRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn update(&self, organization: OrganizationModel) -> Result<OrganizationModel, OrganizationRepositoryError> {
+        schema::validate(&organization).map_err(OrganizationRepositoryError::InvalidDocument)?;
+        // Same as users: mark every column as set so the caller's edits are written
+        let mut active_model = entity::ActiveModel::from(organization).reset_all();
+        active_model.updated_at = Set(chrono::Utc::now().into());
+        match active_model.update(&self.db).await {
+            Ok(updated) => Ok(updated),
+            Err(e) => Err(OrganizationRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list(&self, options: &PaginationOptions) -> Result<PaginatedResponse<OrganizationModel>, OrganizationRepositoryError> {
+        let (page, limit) = (options.page(), options.limit());
+
+        // Only whitelisted columns can be sorted on; anything else falls back to creation time
+        let sort_column = match options.sort_by.as_deref() {
+            Some("name") => entity::Column::Name,
+            Some("updated_at") => entity::Column::UpdatedAt,
+            _ => entity::Column::CreatedAt,
+        };
+        // Newest first unless a sort order is given
+        let order = if options.sort_order.is_some() && options.is_ascending() { Order::Asc } else { Order::Desc };
+        let paginator = OrganizationEntity::find()
+            .filter(entity::Column::DeletedAt.is_null())
+            .order_by(sort_column, order)
+            .order_by_asc(entity::Column::Id)
+            .paginate(&self.db, limit as u64);
+
+        let total = match paginator.num_items().await {
+            Ok(total) => total as i64,
+            Err(e) => return Err(OrganizationRepositoryError::DatabaseError(e.to_string())),
+        };
+        match paginator.fetch_page((page - 1) as u64).await {
+            Ok(items) => Ok(PaginatedResponse::new(items, total, page, limit)),
+            Err(e) => Err(OrganizationRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list_by_creator(&self, creator_id: Uuid) -> Result<Vec<OrganizationModel>, OrganizationRepositoryError> {
+        match OrganizationEntity::find()
+            .filter(entity::Column::CreatorId.eq(creator_id))
+            .filter(entity::Column::DeletedAt.is_null())
+            .order_by_desc(entity::Column::CreatedAt)
+            .all(&self.db)
+            .await
+        {
+            Ok(organizations) => Ok(organizations),
+            Err(e) => Err(OrganizationRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list_by_member(&self, user_id: Uuid) -> Result<Vec<OrganizationModel>, OrganizationRepositoryError> {
+        match OrganizationEntity::find()
+            .inner_join(organization_user::entity::Entity)
+            .filter(organization_user::entity::Column::UserId.eq(user_id))
+            .filter(organization_user::entity::Column::DeletedAt.is_null())
+            .filter(entity::Column::DeletedAt.is_null())
+            .order_by_asc(entity::Column::Name)
+            .all(&self.db)
+            .await
+        {
+            Ok(organizations) => Ok(organizations),
+            Err(e) => Err(OrganizationRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::entity::Model;
+
+/// Shape of `organizations.template` and `organizations.project_template`: the sections and
+/// fields shown when filling in an organization or one of its projects
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    pub version: u32,
+    #[serde(default)]
+    pub sections: Vec<TemplateSection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateSection {
+    pub key: String,
+    pub label: String,
+    #[serde(default)]
+    pub fields: Vec<TemplateField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateField {
+    pub key: String,
+    pub label: String,
+    pub kind: FieldKind,
+    #[serde(default)]
+    pub required: bool,
+    /// Choices for `select` fields
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    Text,
+    Number,
+    Boolean,
+    Date,
+    Select,
+}
+
+/// Shape of `organizations.settings`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OrganizationSettings {
+    /// Role given to members added without one
+    #[serde(default)]
+    pub default_role: Option<String>,
+    #[serde(default)]
+    pub allow_member_invites: bool,
+    #[serde(default)]
+    pub theme: Option<String>,
+}
+
+/// Check the jsonb columns of an organization against their structs before it's written
+pub fn validate(model: &Model) -> Result<(), String> {
+    let template: Template = parse("template", &model.template)?;
+    check_template("template", &template)?;
+    let project_template: Template = parse("project_template", &model.project_template)?;
+    check_template("project_template", &project_template)?;
+    parse::<OrganizationSettings>("settings", &model.settings)?;
+    Ok(())
+}
+
+fn parse<T: DeserializeOwned>(column: &str, value: &serde_json::Value) -> Result<T, String> {
+    T::deserialize(value).map_err(|e| format!("{} is invalid: {}", column, e))
+}
+
+/// Rules serde can't express: keys are unique and select fields have choices
+fn check_template(column: &str, template: &Template) -> Result<(), String> {
+    let mut section_keys = std::collections::HashSet::new();
+    for section in &template.sections {
+        if !section_keys.insert(section.key.as_str()) {
+            return Err(format!("{} is invalid: duplicate section key `{}`", column, section.key));
+        }
+        let mut field_keys = std::collections::HashSet::new();
+        for field in &section.fields {
+            if !field_keys.insert(field.key.as_str()) {
+                return Err(format!(
+                    "{} is invalid: duplicate field key `{}` in section `{}`",
+                    column, field.key, section.key
+                ));
+            }
+            if field.kind == FieldKind::Select && field.options.is_empty() {
+                return Err(format!("{} is invalid: select field `{}` has no options", column, field.key));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::Utc;
+
+use super::OrganizationUser;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "organization_users")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Uuid,
+
+    #[sea_orm(column_type = "JsonBinary")]
+    pub dashboards: Json,
+
+    pub role: String,
+
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::models::user::entity::Entity",
+        from = "Column::UserId",
+        to = "crate::models::user::entity::Column::Id"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "crate::models::organization::entity::Entity",
+        from = "Column::OrganizationId",
+        to = "crate::models::organization::entity::Column::Id"
+    )]
+    Organization,
+}
+
+impl Related<crate::models::user::entity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<crate::models::organization::entity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Organization.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for OrganizationUser {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            user_id: model.user_id,
+            organization_id: model.organization_id,
+            dashboards: model.dashboards,
+            role: model.role,
+            created_at: model.created_at.with_timezone(&Utc),
+            updated_at: model.updated_at.with_timezone(&Utc),
+            deleted_at: model.deleted_at.map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+}
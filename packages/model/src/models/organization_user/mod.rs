@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod entity;
+pub mod repo;
+
+/// A user's membership of an organization and their role in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationUser {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Uuid,
+    pub dashboards: serde_json::Value,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
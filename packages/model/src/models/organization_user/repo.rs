@@ -0,0 +1,104 @@
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, ColumnTrait, ActiveModelTrait};
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::models::organization_user::{entity, entity::Entity as OrganizationUserEntity, entity::Model as OrganizationUserModel};
+
+#[derive(Debug)]
+pub enum OrganizationUserRepositoryError {
+    NotFound(String),
+    Duplicate(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for OrganizationUserRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OrganizationUserRepositoryError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            OrganizationUserRepositoryError::Duplicate(msg) => write!(f, "Duplicate: {}", msg),
+            OrganizationUserRepositoryError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for OrganizationUserRepositoryError {}
+
+#[async_trait]
+pub trait OrganizationUserRepositoryTrait {
+    async fn create(&self, membership: OrganizationUserModel) -> Result<OrganizationUserModel, OrganizationUserRepositoryError>;
+    /// The user's active membership of the organization
+    async fn get_membership(&self, organization_id: Uuid, user_id: Uuid) -> Result<OrganizationUserModel, OrganizationUserRepositoryError>;
+    async fn list_by_organization(&self, organization_id: Uuid) -> Result<Vec<OrganizationUserModel>, OrganizationUserRepositoryError>;
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<OrganizationUserModel>, OrganizationUserRepositoryError>;
+}
+
+#[derive(Clone)]
+pub struct OrganizationUserRepository {
+    db: DatabaseConnection,
+}
+
+impl OrganizationUserRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OrganizationUserRepositoryTrait for OrganizationUserRepository {
+    async fn create(&self, membership: OrganizationUserModel) -> Result<OrganizationUserModel, OrganizationUserRepositoryError> {
+        let active_model: entity::ActiveModel = membership.into();
+        match active_model.insert(&self.db).await {
+            Ok(inserted) => Ok(inserted),
+            Err(e) => {
+                let error_msg = e.to_string();
+                if error_msg.contains("duplicate") || error_msg.contains("unique") {
+                    Err(OrganizationUserRepositoryError::Duplicate("Membership already exists".to_string()))
+                } else {
+                    Err(OrganizationUserRepositoryError::DatabaseError(error_msg))
+                }
+            }
+        }
+    }
+
+    async fn get_membership(&self, organization_id: Uuid, user_id: Uuid) -> Result<OrganizationUserModel, OrganizationUserRepositoryError> {
+        match OrganizationUserEntity::find()
+            .filter(entity::Column::OrganizationId.eq(organization_id))
+            .filter(entity::Column::UserId.eq(user_id))
+            .filter(entity::Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+        {
+            Ok(Some(membership)) => Ok(membership),
+            Ok(None) => Err(OrganizationUserRepositoryError::NotFound(format!(
+                "User {} is not a member of organization {}",
+                user_id, organization_id
+            ))),
+            Err(e) => Err(OrganizationUserRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list_by_organization(&self, organization_id: Uuid) -> Result<Vec<OrganizationUserModel>, OrganizationUserRepositoryError> {
+        match OrganizationUserEntity::find()
+            .filter(entity::Column::OrganizationId.eq(organization_id))
+            .filter(entity::Column::DeletedAt.is_null())
+            .order_by_asc(entity::Column::CreatedAt)
+            .all(&self.db)
+            .await
+        {
+            Ok(memberships) => Ok(memberships),
+            Err(e) => Err(OrganizationUserRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<OrganizationUserModel>, OrganizationUserRepositoryError> {
+        match OrganizationUserEntity::find()
+            .filter(entity::Column::UserId.eq(user_id))
+            .filter(entity::Column::DeletedAt.is_null())
+            .order_by_asc(entity::Column::CreatedAt)
+            .all(&self.db)
+            .await
+        {
+            Ok(memberships) => Ok(memberships),
+            Err(e) => Err(OrganizationUserRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+}
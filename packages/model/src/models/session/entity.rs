@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::Utc;
+
+use super::Session;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "sessions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub jti: Uuid,
+    pub user_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub expires_at: DateTimeWithTimeZone,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Not revoked and not past its expiry
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at.with_timezone(&Utc) > Utc::now()
+    }
+}
+
+impl From<Model> for Session {
+    fn from(model: Model) -> Self {
+        Self {
+            jti: model.jti,
+            user_id: model.user_id,
+            user_agent: model.user_agent,
+            ip: model.ip,
+            created_at: model.created_at.with_timezone(&Utc),
+            expires_at: model.expires_at.with_timezone(&Utc),
+            revoked_at: model.revoked_at.map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod entity;
+pub mod repo;
+
+/// A refresh token issued to one device, identified by the token's `jti`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub jti: Uuid,
+    pub user_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+// Response DTOs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionResponse {
+    pub jti: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<Session> for SessionResponse {
+    fn from(session: Session) -> Self {
+        Self {
+            jti: session.jti.to_string(),
+            user_agent: session.user_agent,
+            ip: session.ip,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
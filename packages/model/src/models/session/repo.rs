@@ -0,0 +1,124 @@
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, ColumnTrait, ActiveModelTrait};
+use sea_orm::sea_query::Expr;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use uuid::Uuid;
+use crate::cleanup;
+use crate::models::session::{entity, entity::Entity as SessionEntity, entity::Model as SessionModel};
+
+#[derive(Debug)]
+pub enum SessionRepositoryError {
+    NotFound(String),
+    Duplicate(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for SessionRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SessionRepositoryError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            SessionRepositoryError::Duplicate(msg) => write!(f, "Duplicate: {}", msg),
+            SessionRepositoryError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SessionRepositoryError {}
+
+#[async_trait]
+pub trait SessionRepositoryTrait {
+    async fn create(&self, session: SessionModel) -> Result<SessionModel, SessionRepositoryError>;
+    async fn get_by_jti(&self, jti: Uuid) -> Result<SessionModel, SessionRepositoryError>;
+    /// Every session of the user, revoked and expired ones included, newest first
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<SessionModel>, SessionRepositoryError>;
+    /// Sessions that are neither revoked nor expired, newest first
+    async fn list_active_by_user(&self, user_id: Uuid) -> Result<Vec<SessionModel>, SessionRepositoryError>;
+    /// Revoke a live session in a single conditional `UPDATE`. Only one caller can win: a session
+    /// that is missing or already revoked is `NotFound`, which is how refresh-token reuse shows up
+    async fn revoke(&self, jti: Uuid) -> Result<(), SessionRepositoryError>;
+    /// Delete sessions whose refresh token expired before `now`, returning how many were removed
+    async fn delete_expired(&self, now: DateTime<Utc>) -> Result<u64, SessionRepositoryError>;
+}
+
+#[derive(Clone)]
+pub struct SessionRepository {
+    db: DatabaseConnection,
+}
+
+impl SessionRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SessionRepositoryTrait for SessionRepository {
+    async fn create(&self, session: SessionModel) -> Result<SessionModel, SessionRepositoryError> {
+        let active_model: entity::ActiveModel = session.into();
+        match active_model.insert(&self.db).await {
+            Ok(inserted) => Ok(inserted),
+            Err(e) => {
+                let error_msg = e.to_string();
+                if error_msg.contains("duplicate") || error_msg.contains("unique") {
+                    Err(SessionRepositoryError::Duplicate("Session already exists".to_string()))
+                } else {
+                    Err(SessionRepositoryError::DatabaseError(error_msg))
+                }
+            }
+        }
+    }
+
+    async fn get_by_jti(&self, jti: Uuid) -> Result<SessionModel, SessionRepositoryError> {
+        match SessionEntity::find_by_id(jti).one(&self.db).await {
+            Ok(Some(session)) => Ok(session),
+            Ok(None) => Err(SessionRepositoryError::NotFound(format!("Session {} not found", jti))),
+            Err(e) => Err(SessionRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<SessionModel>, SessionRepositoryError> {
+        match SessionEntity::find()
+            .filter(entity::Column::UserId.eq(user_id))
+            .order_by_desc(entity::Column::CreatedAt)
+            .all(&self.db)
+            .await
+        {
+            Ok(sessions) => Ok(sessions),
+            Err(e) => Err(SessionRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list_active_by_user(&self, user_id: Uuid) -> Result<Vec<SessionModel>, SessionRepositoryError> {
+        match SessionEntity::find()
+            .filter(entity::Column::UserId.eq(user_id))
+            .filter(entity::Column::RevokedAt.is_null())
+            .filter(entity::Column::ExpiresAt.gt(Utc::now()))
+            .order_by_desc(entity::Column::CreatedAt)
+            .all(&self.db)
+            .await
+        {
+            Ok(sessions) => Ok(sessions),
+            Err(e) => Err(SessionRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn revoke(&self, jti: Uuid) -> Result<(), SessionRepositoryError> {
+        let result = SessionEntity::update_many()
+            .col_expr(entity::Column::RevokedAt, Expr::value(Some(DateTime::<FixedOffset>::from(Utc::now()))))
+            .filter(entity::Column::Jti.eq(jti))
+            .filter(entity::Column::RevokedAt.is_null())
+            .exec(&self.db)
+            .await
+            .map_err(|e| SessionRepositoryError::DatabaseError(e.to_string()))?;
+        if result.rows_affected == 0 {
+            return Err(SessionRepositoryError::NotFound(format!("Session {} not found or already revoked", jti)));
+        }
+        Ok(())
+    }
+
+    async fn delete_expired(&self, now: DateTime<Utc>) -> Result<u64, SessionRepositoryError> {
+        cleanup::delete_expired_sessions(&self.db, now)
+            .await
+            .map_err(|e| SessionRepositoryError::DatabaseError(e.to_string()))
+    }
+}
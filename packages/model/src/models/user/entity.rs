@@ -3,7 +3,7 @@ use sea_orm::ActiveValue::Set;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use crate::models::Timestamps;
+use crate::models::{organization, organization_user, Timestamps};
 use super::model::{User, Personal, Peripheral, Verification, Setting, CustomSetting, Subscription, SubscriptionStatus};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
@@ -29,6 +29,9 @@ pub struct Model {
     pub peripheral_timeout: Option<DateTimeWithTimeZone>,
     pub peripheral_is_banned: bool,
     pub peripheral_is_verified: bool,
+    pub peripheral_failed_code_attempts: i32,
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<DateTimeWithTimeZone>,
     
     // Verification
     pub verification_code: String,
@@ -42,6 +45,9 @@ pub struct Model {
     pub setting_subscription_status: String,
     pub setting_subscription_start_date: Option<DateTimeWithTimeZone>,
     pub setting_subscription_end_date: Option<DateTimeWithTimeZone>,
+
+    // Activity
+    pub last_login_at: Option<DateTimeWithTimeZone>,
     
     // Timestamps
     pub created_at: DateTimeWithTimeZone,
@@ -49,11 +55,97 @@ pub struct Model {
     pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
-#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    /// Organizations this user created (`organizations.creator_id`)
+    CreatedOrganizations,
+    /// This user's memberships (`organization_users.user_id`)
+    OrganizationUsers,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::CreatedOrganizations => organization::entity::Relation::Creator.def().rev(),
+            Self::OrganizationUsers => organization_user::entity::Relation::User.def().rev(),
+        }
+    }
+}
+
+impl Related<organization_user::entity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OrganizationUsers.def()
+    }
+}
+
+/// Organizations the user is a member of, joined through `organization_users`
+impl Related<organization::entity::Entity> for Entity {
+    fn to() -> RelationDef {
+        organization_user::entity::Relation::Organization.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(organization_user::entity::Relation::User.def().rev())
+    }
+}
+
+/// Organizations the user created, for `find_with_linked`/`find_also_linked`
+pub struct CreatedOrganizations;
+
+impl Linked for CreatedOrganizations {
+    type FromEntity = Entity;
+    type ToEntity = organization::entity::Entity;
+
+    fn link(&self) -> Vec<RelationDef> {
+        vec![Relation::CreatedOrganizations.def()]
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl Model {
+    /// A freshly registered user: unverified, not banned, on the BASIC subscription, with no codes or
+    /// settings yet. `email_address` should already be normalized and `password_hash` hashed
+    pub fn new_registration(
+        first_name: impl Into<String>,
+        second_name: impl Into<String>,
+        email_address: impl Into<String>,
+        password_hash: impl Into<String>,
+    ) -> Self {
+        let now: DateTimeWithTimeZone = Utc::now().into();
+        Self {
+            id: Uuid::new_v4(),
+            personal_first_name: first_name.into(),
+            personal_second_name: second_name.into(),
+            personal_email_address: email_address.into(),
+            personal_profile_image: None,
+            personal_username: None,
+            password: password_hash.into(),
+            peripheral_authentication_code: None,
+            peripheral_authentication_token: None,
+            peripheral_timeout: None,
+            peripheral_is_banned: false,
+            peripheral_is_verified: false,
+            peripheral_failed_code_attempts: 0,
+            failed_login_attempts: 0,
+            locked_until: None,
+            verification_code: String::new(),
+            verification_timeout: None,
+            setting_custom_setting_default_theme: None,
+            setting_custom_setting_is_accepting_request: false,
+            setting_subscription_price_id: None,
+            setting_subscription_product_id: None,
+            setting_subscription_status: "BASIC".to_string(),
+            setting_subscription_start_date: None,
+            setting_subscription_end_date: None,
+            last_login_at: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+}
+
 impl From<Model> for User {
     fn from(model: Model) -> Self {
         let timestamps = Timestamps {
@@ -78,6 +170,9 @@ impl From<Model> for User {
                 timeout: model.peripheral_timeout.map(DateTime::<Utc>::from),
                 is_banned: model.peripheral_is_banned,
                 is_verified: model.peripheral_is_verified,
+                failed_code_attempts: model.peripheral_failed_code_attempts,
+                failed_login_attempts: model.failed_login_attempts,
+                locked_until: model.locked_until.map(DateTime::<Utc>::from),
             },
             verification: Verification {
                 code: model.verification_code,
@@ -99,6 +194,7 @@ impl From<Model> for User {
                     end_date: model.setting_subscription_end_date.map(DateTime::<Utc>::from),
                 },
             },
+            last_login_at: model.last_login_at.map(DateTime::<Utc>::from),
             timestamps,
         }
     }
@@ -119,6 +215,9 @@ impl From<User> for ActiveModel {
             peripheral_timeout: Set(user.peripheral.timeout.map(|t| t.into())),
             peripheral_is_banned: Set(user.peripheral.is_banned),
             peripheral_is_verified: Set(user.peripheral.is_verified),
+            peripheral_failed_code_attempts: Set(user.peripheral.failed_code_attempts),
+            failed_login_attempts: Set(user.peripheral.failed_login_attempts),
+            locked_until: Set(user.peripheral.locked_until.map(|t| t.into())),
             verification_code: Set(user.verification.code),
             verification_timeout: Set(user.verification.timeout.map(|t| t.timestamp())),
             setting_custom_setting_default_theme: Set(user.setting.custom_setting.default_theme),
@@ -128,6 +227,7 @@ impl From<User> for ActiveModel {
             setting_subscription_status: Set(serde_json::to_string(&user.setting.subscription.status).unwrap()),
             setting_subscription_start_date: Set(user.setting.subscription.start_date.map(|t| t.into())),
             setting_subscription_end_date: Set(user.setting.subscription.end_date.map(|t| t.into())),
+            last_login_at: Set(user.last_login_at.map(|t| t.into())),
             created_at: Set(user.timestamps.created_at.into()),
             updated_at: Set(user.timestamps.updated_at.into()),
             deleted_at: Set(user.timestamps.deleted_at.map(|t| t.into())),
@@ -135,3 +235,28 @@ impl From<User> for ActiveModel {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_registration_fills_the_signup_defaults() {
+        let user = Model::new_registration("Ada", "Lovelace", "ada@example.com", "hash");
+
+        assert_eq!(user.personal_first_name, "Ada");
+        assert_eq!(user.personal_second_name, "Lovelace");
+        assert_eq!(user.personal_email_address, "ada@example.com");
+        assert_eq!(user.password, "hash");
+        assert_eq!(user.setting_subscription_status, "BASIC");
+        assert!(!user.peripheral_is_verified);
+        assert!(!user.peripheral_is_banned);
+        assert_eq!((user.failed_login_attempts, user.peripheral_failed_code_attempts), (0, 0));
+        assert!(user.locked_until.is_none() && user.last_login_at.is_none() && user.deleted_at.is_none());
+        assert!(user.peripheral_authentication_code.is_none() && user.verification_code.is_empty());
+        assert_eq!(user.created_at, user.updated_at);
+
+        // Every registration gets its own id
+        assert_ne!(user.id, Model::new_registration("Ada", "Lovelace", "ada@example.com", "hash").id);
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::user::Model as UserModel;
+use crate::models::user::repo::{UserRepositoryError, UserRepositoryTrait};
+use crate::shared::{PaginatedResponse, PaginationOptions};
+
+/// `UserRepositoryTrait` over a `HashMap`, so services can be exercised without a database.
+/// Mirrors the real repository's errors (duplicate email, not found) but not its sorting
+#[derive(Debug, Default)]
+pub struct InMemoryUserRepository {
+    users: Mutex<HashMap<Uuid, UserModel>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start with `users` already stored
+    pub fn with_users(users: impl IntoIterator<Item = UserModel>) -> Self {
+        let users = users.into_iter().map(|user| (user.id, user)).collect();
+        Self { users: Mutex::new(users) }
+    }
+}
+
+#[async_trait]
+impl UserRepositoryTrait for InMemoryUserRepository {
+    async fn create(&self, user: UserModel) -> Result<UserModel, UserRepositoryError> {
+        let mut users = self.users.lock().unwrap();
+        if users.values().any(|u| u.personal_email_address == user.personal_email_address) {
+            return Err(UserRepositoryError::Duplicate("Email address already exists".to_string()));
+        }
+        users.insert(user.id, user.clone());
+        Ok(user)
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<UserModel, UserRepositoryError> {
+        self.users
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| UserRepositoryError::NotFound(format!("User with id {} not found", id)))
+    }
+
+    async fn get_by_email(&self, email: &str) -> Result<UserModel, UserRepositoryError> {
+        self.users
+            .lock()
+            .unwrap()
+            .values()
+            .find(|u| u.personal_email_address == email)
+            .cloned()
+            .ok_or_else(|| UserRepositoryError::NotFound(format!("User with email {} not found", email)))
+    }
+
+    async fn update(&self, mut user: UserModel) -> Result<UserModel, UserRepositoryError> {
+        let mut users = self.users.lock().unwrap();
+        if !users.contains_key(&user.id) {
+            return Err(UserRepositoryError::NotFound(format!("User with id {} not found", user.id)));
+        }
+        user.updated_at = chrono::Utc::now().into();
+        users.insert(user.id, user.clone());
+        Ok(user)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), UserRepositoryError> {
+        self.users.lock().unwrap().remove(&id);
+        Ok(())
+    }
+
+    async fn list(&self, options: &PaginationOptions, email_filter: Option<&str>) -> Result<PaginatedResponse<UserModel>, UserRepositoryError> {
+        let (page, limit) = (options.page(), options.limit());
+        let filter = email_filter.map(|f| f.trim().to_lowercase()).filter(|f| !f.is_empty());
+
+        let mut matching: Vec<UserModel> = self
+            .users
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|u| u.deleted_at.is_none())
+            .filter(|u| filter.as_ref().is_none_or(|f| u.personal_email_address.contains(f.as_str())))
+            .cloned()
+            .collect();
+        matching.sort_by_key(|u| (u.created_at, u.id));
+
+        let total = matching.len() as i64;
+        let items = matching
+            .into_iter()
+            .skip(((page - 1) * limit) as usize)
+            .take(limit as usize)
+            .collect();
+        Ok(PaginatedResponse::new(items, total, page, limit))
+    }
+
+    async fn increment_failed_logins(&self, id: Uuid) -> Result<i32, UserRepositoryError> {
+        let mut users = self.users.lock().unwrap();
+        let user = users
+            .get_mut(&id)
+            .ok_or_else(|| UserRepositoryError::NotFound(format!("User with id {} not found", id)))?;
+        user.failed_login_attempts += 1;
+        Ok(user.failed_login_attempts)
+    }
+
+    async fn set_locked_until(&self, id: Uuid, until: Option<DateTime<Utc>>) -> Result<(), UserRepositoryError> {
+        if let Some(user) = self.users.lock().unwrap().get_mut(&id) {
+            user.locked_until = until.map(Into::into);
+        }
+        Ok(())
+    }
+
+    async fn record_login(&self, id: Uuid, at: DateTime<Utc>) -> Result<(), UserRepositoryError> {
+        let mut users = self.users.lock().unwrap();
+        let user = users
+            .get_mut(&id)
+            .ok_or_else(|| UserRepositoryError::NotFound(format!("User with id {} not found", id)))?;
+        user.last_login_at = Some(at.into());
+        user.failed_login_attempts = 0;
+        user.locked_until = None;
+        Ok(())
+    }
+
+    async fn take_code_attempt(&self, id: Uuid, max: i32) -> Result<Option<i32>, UserRepositoryError> {
+        let mut users = self.users.lock().unwrap();
+        let Some(user) = users.get_mut(&id).filter(|u| u.peripheral_failed_code_attempts < max) else {
+            return Ok(None);
+        };
+        user.peripheral_failed_code_attempts += 1;
+        Ok(Some(user.peripheral_failed_code_attempts))
+    }
+
+    async fn reset_code_attempts(&self, id: Uuid) -> Result<(), UserRepositoryError> {
+        if let Some(user) = self.users.lock().unwrap().get_mut(&id) {
+            user.peripheral_failed_code_attempts = 0;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
 pub mod entity;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 pub mod model;
 pub mod repo;
 
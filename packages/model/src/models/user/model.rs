@@ -12,7 +12,7 @@ pub enum SubscriptionStatus {
     ENTERPRISE,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Verification {
     pub code: String,
     pub timeout: Option<DateTime<Utc>>,
@@ -37,15 +37,21 @@ pub struct Personal {
     pub username: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Peripheral {
-    #[serde(skip_serializing)]
     pub authentication_code: Option<String>,
-    #[serde(skip_serializing)]
     pub authentication_token: Option<String>,
     pub timeout: Option<DateTime<Utc>>,
     pub is_banned: bool,
     pub is_verified: bool,
+    #[serde(default)]
+    pub failed_code_attempts: i32,
+    /// Wrong passwords since the last successful sign-in
+    #[serde(default)]
+    pub failed_login_attempts: i32,
+    /// Sign-in is refused until this time
+    #[serde(default)]
+    pub locked_until: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,17 +66,17 @@ pub struct Setting {
     pub subscription: Subscription,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Domain user, secrets included; deliberately not `Serialize`, responses go through `PublicUser`
+#[derive(Debug, Clone, Deserialize)]
 pub struct User {
     pub id: Uuid,
     pub personal: Personal,
-    #[serde(skip_serializing)]
     pub password: String,
-    #[serde(skip_serializing)]
     pub peripheral: Peripheral,
-    #[serde(skip_serializing)]
     pub verification: Verification,
     pub setting: Setting,
+    /// Last successful sign-in, `None` until the first one
+    pub last_login_at: Option<DateTime<Utc>>,
     pub timestamps: Timestamps,
 }
 
@@ -90,7 +96,7 @@ impl Model for User {
 
 impl SoftDelete for User {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct MultipleUser {
     pub total_users: i64,
     pub users: Vec<User>,
@@ -122,6 +128,11 @@ pub struct UserIdRequest {
     pub user_id: Uuid,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendResetCodeRequest {
     pub email_address: String,
@@ -153,6 +164,54 @@ pub struct UpdatePersonal {
     pub username: Option<String>,
 }
 
+impl RegisterRequest {
+    pub fn new(
+        first_name: impl Into<String>,
+        second_name: impl Into<String>,
+        email_address: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            first_name: first_name.into(),
+            second_name: second_name.into(),
+            email_address: email_address.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl LoginRequest {
+    pub fn new(email_address: impl Into<String>, password: impl Into<String>) -> Self {
+        Self { email_address: email_address.into(), password: password.into() }
+    }
+}
+
+impl RefreshTokenRequest {
+    pub fn new(refresh_token: impl Into<String>) -> Self {
+        Self { refresh_token: refresh_token.into() }
+    }
+}
+
+impl SendResetCodeRequest {
+    pub fn new(email_address: impl Into<String>) -> Self {
+        Self { email_address: email_address.into() }
+    }
+}
+
+impl VerifyResetCodeRequest {
+    pub fn new(email_address: impl Into<String>, auth_code: impl Into<String>) -> Self {
+        Self { email_address: email_address.into(), auth_code: auth_code.into() }
+    }
+}
+
+impl ResetPasswordRequest {
+    /// A request whose confirmation matches the password
+    pub fn new(password: impl Into<String>) -> Self {
+        let password = password.into();
+        Self { confirm_password: password.clone(), password }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserCodeInjection {
     pub authentication_code: String,
@@ -168,6 +227,19 @@ pub struct AuthUserResponse {
     pub refresh_token: String,
 }
 
+/// `AuthUserResponse` without the refresh token, for when it's delivered as a cookie instead
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessTokenResponse {
+    pub id: String,
+    pub access_token: String,
+}
+
+impl From<AuthUserResponse> for AccessTokenResponse {
+    fn from(response: AuthUserResponse) -> Self {
+        Self { id: response.id, access_token: response.access_token }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyCodeResponse {
     pub token: String,
@@ -180,27 +252,40 @@ pub struct PasswordAuthResponse {
     pub message: String,
 }
 
+/// The only projection of a `User` that leaves the server; every outbound user shape is built
+/// from it, and `User` itself can't be serialized
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SecureUserResponse {
+pub struct PublicUser {
     pub id: String,
     pub personal: Personal,
     pub timestamps: Timestamps,
-    pub verification: Verification,
+    /// Only whether the email is verified; the code and its timeout never leave the server
+    pub is_verified: bool,
     pub setting: Setting,
+    pub last_login_at: Option<DateTime<Utc>>,
 }
 
-impl From<User> for SecureUserResponse {
+// Adding a field to `PublicUser` fails to compile here; check it isn't a secret before listing it
+const _: fn(PublicUser) = |PublicUser { id: _, personal: _, timestamps: _, is_verified: _, setting: _, last_login_at: _ }| {};
+
+impl From<User> for PublicUser {
     fn from(user: User) -> Self {
+        // Exhaustive, so a new `User` field has to be placed on one side of the boundary
+        let User { id, personal, password: _, peripheral, verification: _, setting, last_login_at, timestamps } = user;
         Self {
-            id: user.id.to_string(),
-            personal: user.personal,
-            timestamps: user.timestamps,
-            verification: user.verification,
-            setting: user.setting
+            id: id.to_string(),
+            personal,
+            timestamps,
+            is_verified: peripheral.is_verified,
+            setting,
+            last_login_at,
         }
     }
 }
 
+/// Full profile as returned to its owner and to admins
+pub type SecureUserResponse = PublicUser;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneralUserResponse {
     pub id: String,
@@ -209,10 +294,10 @@ pub struct GeneralUserResponse {
     pub updated_at: DateTime<Utc>,
 }
 
-impl From<User> for GeneralUserResponse {
-    fn from(user: User) -> Self {
+impl From<PublicUser> for GeneralUserResponse {
+    fn from(user: PublicUser) -> Self {
         Self {
-            id: user.id.to_string(),
+            id: user.id,
             personal: user.personal,
             created_at: user.timestamps.created_at,
             updated_at: user.timestamps.updated_at,
@@ -237,3 +322,58 @@ pub struct MultipleGeneralResponse {
 // Unified paginated response aliases
 pub type SecureUsersPage = PaginatedResponse<SecureUserResponse>;
 pub type GeneralUsersPage = PaginatedResponse<GeneralUserResponse>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn request_constructors_serialize_to_the_payloads_handlers_read() {
+        assert_eq!(
+            serde_json::to_value(RegisterRequest::new("Ada", "Lovelace", "ada@example.com", "pw")).unwrap(),
+            json!({ "first_name": "Ada", "second_name": "Lovelace", "email_address": "ada@example.com", "password": "pw" })
+        );
+        assert_eq!(
+            serde_json::to_value(LoginRequest::new("ada@example.com", "pw")).unwrap(),
+            json!({ "email_address": "ada@example.com", "password": "pw" })
+        );
+        assert_eq!(serde_json::to_value(RefreshTokenRequest::new("rt")).unwrap(), json!({ "refresh_token": "rt" }));
+        assert_eq!(
+            serde_json::to_value(SendResetCodeRequest::new("ada@example.com")).unwrap(),
+            json!({ "email_address": "ada@example.com" })
+        );
+        assert_eq!(
+            serde_json::to_value(VerifyResetCodeRequest::new("ada@example.com", "123456")).unwrap(),
+            json!({ "email_address": "ada@example.com", "auth_code": "123456" })
+        );
+    }
+
+    #[test]
+    fn reset_password_request_confirms_its_own_password() {
+        let request = ResetPasswordRequest::new("Correct-Horse-42");
+        assert_eq!(request.password, "Correct-Horse-42");
+        assert_eq!(request.confirm_password, request.password);
+    }
+
+    #[test]
+    fn public_user_serializes_no_credentials_or_codes() {
+        let mut row = crate::models::user::entity::Model::new_registration("Ada", "Lovelace", "ada@example.com", "argon-hash");
+        row.peripheral_authentication_code = Some("reset-code-1".to_string());
+        row.peripheral_authentication_token = Some("reset-token-1".to_string());
+        row.verification_code = "verify-code-1".to_string();
+        row.peripheral_is_verified = true;
+
+        let public = serde_json::to_value(PublicUser::from(User::from(row))).unwrap();
+        assert_eq!(public["is_verified"], true);
+        assert_eq!(public["personal"]["email_address"], "ada@example.com");
+
+        let raw = public.to_string();
+        for secret in ["argon-hash", "reset-code-1", "reset-token-1", "verify-code-1"] {
+            assert!(!raw.contains(secret), "{} leaked in {}", secret, raw);
+        }
+        for field in ["password", "peripheral", "verification", "authentication_code", "authentication_token"] {
+            assert!(!raw.contains(&format!("\"{}\"", field)), "{} serialized in {}", field, raw);
+        }
+    }
+}
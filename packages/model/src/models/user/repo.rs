@@ -1,7 +1,12 @@
-use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, ActiveModelTrait};
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, ColumnTrait, PaginatorTrait, Order};
+use sea_orm::sea_query::{Expr, LikeExpr};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use crate::models::base::BaseRepository;
 use crate::models::user::{self, Entity as UserEntity, Model as UserModel};
+use crate::models::ModelError;
+use crate::shared::{PaginatedResponse, PaginationOptions};
 
 #[derive(Debug)]
 pub enum UserRepositoryError {
@@ -22,55 +27,76 @@ impl std::fmt::Display for UserRepositoryError {
 
 impl std::error::Error for UserRepositoryError {}
 
+impl From<ModelError> for UserRepositoryError {
+    fn from(e: ModelError) -> Self {
+        match e {
+            ModelError::NotFound(msg) => UserRepositoryError::NotFound(msg),
+            ModelError::Duplicate(_) => UserRepositoryError::Duplicate("Email address already exists".to_string()),
+            ModelError::DatabaseError(msg) => UserRepositoryError::DatabaseError(msg),
+        }
+    }
+}
+
 #[async_trait]
-pub trait UserRepositoryTrait {
+pub trait UserRepositoryTrait: Send + Sync {
     async fn create(&self, user: UserModel) -> Result<UserModel, UserRepositoryError>;
     async fn get_by_id(&self, id: Uuid) -> Result<UserModel, UserRepositoryError>;
     async fn get_by_email(&self, email: &str) -> Result<UserModel, UserRepositoryError>;
     async fn update(&self, user: UserModel) -> Result<UserModel, UserRepositoryError>;
     async fn delete(&self, id: Uuid) -> Result<(), UserRepositoryError>;
+    async fn list(&self, options: &PaginationOptions, email_filter: Option<&str>) -> Result<PaginatedResponse<UserModel>, UserRepositoryError>;
+    /// Count one more wrong password in a single `UPDATE`, so concurrent failures aren't lost, and
+    /// return the new count
+    async fn increment_failed_logins(&self, id: Uuid) -> Result<i32, UserRepositoryError>;
+    /// Set or clear `locked_until` without rewriting the rest of the row
+    async fn set_locked_until(&self, id: Uuid, until: Option<DateTime<Utc>>) -> Result<(), UserRepositoryError>;
+    /// Stamp `last_login_at` and clear the failed-login counter and lock, leaving every other column
+    /// as it is in the database
+    async fn record_login(&self, id: Uuid, at: DateTime<Utc>) -> Result<(), UserRepositoryError>;
+    /// Use up one reset-code attempt in a single `UPDATE ... RETURNING`, so parallel guesses can't
+    /// share an attempt. Returns the new count, or `None` once `max` attempts have been used
+    async fn take_code_attempt(&self, id: Uuid, max: i32) -> Result<Option<i32>, UserRepositoryError>;
+    /// Zero the reset-code attempt counter without rewriting the rest of the row
+    async fn reset_code_attempts(&self, id: Uuid) -> Result<(), UserRepositoryError>;
 }
 
 #[derive(Clone)]
 pub struct UserRepository {
-    db: DatabaseConnection,
+    base: BaseRepository<UserEntity>,
 }
 
 impl UserRepository {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { base: BaseRepository::new(db, "User", user::entity::Column::UpdatedAt) }
+    }
+}
+
+/// Escape `\`, `%` and `_` so user input only matches itself inside a `LIKE ... ESCAPE '\'` pattern
+fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
     }
+    escaped
 }
 
 #[async_trait]
 impl UserRepositoryTrait for UserRepository {
     async fn create(&self, user: UserModel) -> Result<UserModel, UserRepositoryError> {
-        let active_model: user::entity::ActiveModel = user.clone().into();
-        match active_model.insert(&self.db).await {
-            Ok(inserted) => Ok(inserted),
-            Err(e) => {
-                let error_msg = e.to_string();
-                if error_msg.contains("duplicate") || error_msg.contains("unique") {
-                    Err(UserRepositoryError::Duplicate("Email address already exists".to_string()))
-                } else {
-                    Err(UserRepositoryError::DatabaseError(error_msg))
-                }
-            }
-        }
+        Ok(self.base.create(user).await?)
     }
 
     async fn get_by_id(&self, id: Uuid) -> Result<UserModel, UserRepositoryError> {
-        match UserEntity::find_by_id(id).one(&self.db).await {
-            Ok(Some(user)) => Ok(user),
-            Ok(None) => Err(UserRepositoryError::NotFound(format!("User with id {} not found", id))),
-            Err(e) => Err(UserRepositoryError::DatabaseError(e.to_string())),
-        }
+        Ok(self.base.get_by_id(id).await?)
     }
 
     async fn get_by_email(&self, email: &str) -> Result<UserModel, UserRepositoryError> {
         match UserEntity::find()
             .filter(user::entity::Column::PersonalEmailAddress.eq(email))
-            .one(&self.db)
+            .one(self.base.db())
             .await
         {
             Ok(Some(user)) => Ok(user),
@@ -80,18 +106,103 @@ impl UserRepositoryTrait for UserRepository {
     }
 
     async fn update(&self, user: UserModel) -> Result<UserModel, UserRepositoryError> {
-        let active_model: user::entity::ActiveModel = user.clone().into();
-        match active_model.update(&self.db).await {
-            Ok(updated) => Ok(updated),
-            Err(e) => Err(UserRepositoryError::DatabaseError(e.to_string())),
-        }
+        Ok(self.base.update(user).await?)
     }
 
     async fn delete(&self, id: Uuid) -> Result<(), UserRepositoryError> {
-        match UserEntity::delete_by_id(id).exec(&self.db).await {
-            Ok(_) => Ok(()),
+        Ok(self.base.delete(id).await?)
+    }
+
+    async fn list(&self, options: &PaginationOptions, email_filter: Option<&str>) -> Result<PaginatedResponse<UserModel>, UserRepositoryError> {
+        let (page, limit) = (options.page(), options.limit());
+
+        let mut query = UserEntity::find().filter(user::entity::Column::DeletedAt.is_null());
+        if let Some(filter) = email_filter.map(str::trim).filter(|f| !f.is_empty()) {
+            let pattern = format!("%{}%", escape_like(&filter.to_lowercase()));
+            query = query.filter(user::entity::Column::PersonalEmailAddress.like(LikeExpr::new(pattern).escape('\\')));
+        }
+
+        // Only whitelisted columns can be sorted on; anything else falls back to creation time
+        let sort_column = match options.sort_by.as_deref() {
+            Some("email_address") => user::entity::Column::PersonalEmailAddress,
+            Some("first_name") => user::entity::Column::PersonalFirstName,
+            Some("updated_at") => user::entity::Column::UpdatedAt,
+            _ => user::entity::Column::CreatedAt,
+        };
+        let order = if options.is_ascending() { Order::Asc } else { Order::Desc };
+        let paginator = query
+            .order_by(sort_column, order)
+            .order_by_asc(user::entity::Column::Id)
+            .paginate(self.base.db(), limit as u64);
+
+        let total = match paginator.num_items().await {
+            Ok(total) => total as i64,
+            Err(e) => return Err(UserRepositoryError::DatabaseError(e.to_string())),
+        };
+        match paginator.fetch_page((page - 1) as u64).await {
+            Ok(items) => Ok(PaginatedResponse::new(items, total, page, limit)),
             Err(e) => Err(UserRepositoryError::DatabaseError(e.to_string())),
         }
     }
-}
 
+    async fn increment_failed_logins(&self, id: Uuid) -> Result<i32, UserRepositoryError> {
+        let column = user::entity::Column::FailedLoginAttempts;
+        let updated = UserEntity::update_many()
+            .col_expr(column, Expr::col(column).add(1))
+            .filter(user::entity::Column::Id.eq(id))
+            .exec_with_returning(self.base.db())
+            .await
+            .map_err(|e| UserRepositoryError::DatabaseError(e.to_string()))?;
+        updated
+            .first()
+            .map(|user| user.failed_login_attempts)
+            .ok_or_else(|| UserRepositoryError::NotFound(format!("User with id {} not found", id)))
+    }
+
+    async fn set_locked_until(&self, id: Uuid, until: Option<DateTime<Utc>>) -> Result<(), UserRepositoryError> {
+        UserEntity::update_many()
+            .col_expr(user::entity::Column::LockedUntil, Expr::value(until))
+            .filter(user::entity::Column::Id.eq(id))
+            .exec(self.base.db())
+            .await
+            .map_err(|e| UserRepositoryError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn record_login(&self, id: Uuid, at: DateTime<Utc>) -> Result<(), UserRepositoryError> {
+        let result = UserEntity::update_many()
+            .col_expr(user::entity::Column::LastLoginAt, Expr::value(Some(at)))
+            .col_expr(user::entity::Column::FailedLoginAttempts, Expr::value(0))
+            .col_expr(user::entity::Column::LockedUntil, Expr::value(Option::<DateTime<Utc>>::None))
+            .filter(user::entity::Column::Id.eq(id))
+            .exec(self.base.db())
+            .await
+            .map_err(|e| UserRepositoryError::DatabaseError(e.to_string()))?;
+        if result.rows_affected == 0 {
+            return Err(UserRepositoryError::NotFound(format!("User with id {} not found", id)));
+        }
+        Ok(())
+    }
+
+    async fn take_code_attempt(&self, id: Uuid, max: i32) -> Result<Option<i32>, UserRepositoryError> {
+        let column = user::entity::Column::PeripheralFailedCodeAttempts;
+        let updated = UserEntity::update_many()
+            .col_expr(column, Expr::col(column).add(1))
+            .filter(user::entity::Column::Id.eq(id))
+            .filter(column.lt(max))
+            .exec_with_returning(self.base.db())
+            .await
+            .map_err(|e| UserRepositoryError::DatabaseError(e.to_string()))?;
+        Ok(updated.first().map(|user| user.peripheral_failed_code_attempts))
+    }
+
+    async fn reset_code_attempts(&self, id: Uuid) -> Result<(), UserRepositoryError> {
+        UserEntity::update_many()
+            .col_expr(user::entity::Column::PeripheralFailedCodeAttempts, Expr::value(0))
+            .filter(user::entity::Column::Id.eq(id))
+            .exec(self.base.db())
+            .await
+            .map_err(|e| UserRepositoryError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub mod entity;
+pub mod repo;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Wallet {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub address: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+// Response DTOs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWalletResponse {
+    pub id: String,
+    pub address: String,
+    /// Only ever returned at creation; the stored copy is encrypted
+    pub seed_phrase: String,
+    pub warning: String,
+}
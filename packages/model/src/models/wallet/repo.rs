@@ -0,0 +1,79 @@
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, ActiveModelTrait};
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::models::wallet::{entity, entity::Entity as WalletEntity, entity::Model as WalletModel};
+
+#[derive(Debug)]
+pub enum WalletRepositoryError {
+    NotFound(String),
+    Duplicate(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for WalletRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WalletRepositoryError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            WalletRepositoryError::Duplicate(msg) => write!(f, "Duplicate: {}", msg),
+            WalletRepositoryError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WalletRepositoryError {}
+
+#[async_trait]
+pub trait WalletRepositoryTrait {
+    async fn create(&self, wallet: WalletModel) -> Result<WalletModel, WalletRepositoryError>;
+    async fn get_by_id(&self, id: Uuid) -> Result<WalletModel, WalletRepositoryError>;
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<WalletModel>, WalletRepositoryError>;
+}
+
+#[derive(Clone)]
+pub struct WalletRepository {
+    db: DatabaseConnection,
+}
+
+impl WalletRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl WalletRepositoryTrait for WalletRepository {
+    async fn create(&self, wallet: WalletModel) -> Result<WalletModel, WalletRepositoryError> {
+        let active_model: entity::ActiveModel = wallet.into();
+        match active_model.insert(&self.db).await {
+            Ok(inserted) => Ok(inserted),
+            Err(e) => {
+                let error_msg = e.to_string();
+                if error_msg.contains("duplicate") || error_msg.contains("unique") {
+                    Err(WalletRepositoryError::Duplicate("Wallet address already exists".to_string()))
+                } else {
+                    Err(WalletRepositoryError::DatabaseError(error_msg))
+                }
+            }
+        }
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> Result<WalletModel, WalletRepositoryError> {
+        match WalletEntity::find_by_id(id).one(&self.db).await {
+            Ok(Some(wallet)) => Ok(wallet),
+            Ok(None) => Err(WalletRepositoryError::NotFound(format!("Wallet with id {} not found", id))),
+            Err(e) => Err(WalletRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+
+    async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<WalletModel>, WalletRepositoryError> {
+        match WalletEntity::find()
+            .filter(entity::Column::UserId.eq(user_id))
+            .filter(entity::Column::DeletedAt.is_null())
+            .all(&self.db)
+            .await
+        {
+            Ok(wallets) => Ok(wallets),
+            Err(e) => Err(WalletRepositoryError::DatabaseError(e.to_string())),
+        }
+    }
+}
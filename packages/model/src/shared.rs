@@ -19,6 +19,34 @@ impl Default for PaginationOptions {
     }
 }
 
+impl PaginationOptions {
+    pub const MAX_LIMIT: i32 = 100;
+
+    /// Clamp `limit` to 1..=MAX_LIMIT and `page` to at least 1, filling in defaults
+    pub fn clamped(self) -> Self {
+        let defaults = Self::default();
+        Self {
+            page: Some(self.page.or(defaults.page).unwrap_or(1).max(1)),
+            limit: Some(self.limit.or(defaults.limit).unwrap_or(10).clamp(1, Self::MAX_LIMIT)),
+            sort_by: self.sort_by,
+            sort_order: self.sort_order.map(|order| order.to_lowercase()),
+        }
+    }
+
+    pub fn page(&self) -> i32 {
+        self.page.unwrap_or(1)
+    }
+
+    pub fn limit(&self) -> i32 {
+        self.limit.unwrap_or(10)
+    }
+
+    /// True unless `sort_order` is explicitly "desc"
+    pub fn is_ascending(&self) -> bool {
+        self.sort_order.as_deref() != Some("desc")
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PaginatedResponse<T> {
     pub items: Vec<T>,
@@ -33,6 +61,17 @@ impl<T> PaginatedResponse<T> {
         let has_next = i64::from(page) * i64::from(limit) < total;
         Self { items, total, page, limit, has_next }
     }
+
+    /// Convert the items while keeping the paging metadata
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> PaginatedResponse<U> {
+        PaginatedResponse {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            limit: self.limit,
+            has_next: self.has_next,
+        }
+    }
 }
 
 // Back-compat: re-export legacy path `crate::shared::data::models` to `crate::models`
@@ -0,0 +1,88 @@
+//! The shared `BaseRepository` CRUD, directly and through the user and admin repositories that
+//! delegate to it, which must keep the errors their hand-written versions returned
+
+use chrono::Utc;
+use model::migration::{Migrator, MigratorTrait};
+use model::models::admin::entity::{Column as AdminColumn, Entity as AdminEntity, Model as Admin};
+use model::models::admin::repo::{AdminRepositoryError, AdminRepositoryTrait};
+use model::models::base::BaseRepository;
+use model::models::user::entity::Model as User;
+use model::models::user::repo::{UserRepositoryError, UserRepositoryTrait};
+use model::models::{ModelError, Models};
+use sea_orm::Database;
+use uuid::Uuid;
+
+async fn migrated_models() -> Models {
+    let db = Database::connect("sqlite::memory:").await.expect("open sqlite");
+    Migrator::up(&db, None).await.expect("migrate sqlite");
+    Models::from_connection(db)
+}
+
+fn admin(email: &str) -> Admin {
+    let now = Utc::now().into();
+    Admin {
+        id: Uuid::new_v4(),
+        email_address: email.to_string(),
+        password: "hash".to_string(),
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    }
+}
+
+#[tokio::test]
+async fn generic_crud_maps_missing_and_duplicate_rows() {
+    let models = migrated_models().await;
+    let base = BaseRepository::<AdminEntity>::new(models.db.clone(), "Admin", AdminColumn::UpdatedAt);
+
+    let created = base.create(admin("root@example.com")).await.unwrap();
+    assert_eq!(base.get_by_id(created.id).await.unwrap(), created);
+    // Admin emails aren't unique in the schema, so collide on the primary key
+    assert!(matches!(base.create(created.clone()).await, Err(ModelError::Duplicate(msg)) if msg == "Admin already exists"));
+
+    base.delete(created.id).await.unwrap();
+    match base.get_by_id(created.id).await {
+        Err(ModelError::NotFound(msg)) => assert_eq!(msg, format!("Admin with id {} not found", created.id)),
+        other => panic!("expected NotFound, got {:?}", other.map(|a| a.id)),
+    }
+    // Deleting a missing row is not an error
+    base.delete(created.id).await.unwrap();
+}
+
+#[tokio::test]
+async fn user_repository_keeps_its_crud_errors() {
+    let models = migrated_models().await;
+    let user = User::new_registration("Ada", "Lovelace", "ada@example.com", "hash");
+    let created = models.user.create(user.clone()).await.unwrap();
+    assert_eq!(models.user.get_by_id(created.id).await.unwrap().personal_email_address, "ada@example.com");
+
+    let duplicate = User::new_registration("Ada", "Lovelace", "ada@example.com", "hash");
+    assert!(matches!(
+        models.user.create(duplicate).await,
+        Err(UserRepositoryError::Duplicate(msg)) if msg == "Email address already exists"
+    ));
+
+    models.user.delete(created.id).await.unwrap();
+    assert!(matches!(
+        models.user.get_by_id(created.id).await,
+        Err(UserRepositoryError::NotFound(msg)) if msg == format!("User with id {} not found", created.id)
+    ));
+}
+
+#[tokio::test]
+async fn admin_repository_keeps_its_crud_errors() {
+    let models = migrated_models().await;
+    let created = models.admin.create(admin("root@example.com")).await.unwrap();
+    assert_eq!(models.admin.get_by_id(created.id).await.unwrap(), created);
+
+    assert!(matches!(
+        models.admin.create(created.clone()).await,
+        Err(AdminRepositoryError::Duplicate(msg)) if msg == "Admin with this email already exists"
+    ));
+
+    models.admin.delete(created.id).await.unwrap();
+    assert!(matches!(
+        models.admin.get_by_id(created.id).await,
+        Err(AdminRepositoryError::NotFound(msg)) if msg == format!("Admin with id {} not found", created.id)
+    ));
+}
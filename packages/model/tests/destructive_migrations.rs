@@ -0,0 +1,29 @@
+//! Its own test binary: it flips ALLOW_DESTRUCTIVE_MIGRATIONS, which the other suites set for good
+
+use model::migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+
+async fn applied(db: &DatabaseConnection) -> Vec<String> {
+    Migrator::get_applied_migrations(db).await.unwrap().iter().map(|m| m.name().to_string()).collect()
+}
+
+#[tokio::test]
+async fn down_needs_the_explicit_flag() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+
+    for flag in [None, Some("yes")] {
+        match flag {
+            Some(value) => std::env::set_var("ALLOW_DESTRUCTIVE_MIGRATIONS", value),
+            None => std::env::remove_var("ALLOW_DESTRUCTIVE_MIGRATIONS"),
+        }
+        let err = Migrator::down(&db, None).await.unwrap_err();
+        assert!(err.to_string().contains("ALLOW_DESTRUCTIVE_MIGRATIONS"), "{}", err);
+        // Stopped at the first migration that drops data, so the schema is still there
+        assert!(applied(&db).await.contains(&"m20251105_000001_init_schema".to_string()));
+    }
+
+    std::env::set_var("ALLOW_DESTRUCTIVE_MIGRATIONS", "true");
+    Migrator::down(&db, None).await.unwrap();
+    assert!(applied(&db).await.is_empty());
+}
@@ -0,0 +1,169 @@
+//! Organization and membership repositories against a fresh in-memory SQLite database
+
+use chrono::{Duration, Utc};
+use model::migration::{Migrator, MigratorTrait};
+use model::models::organization::entity::Model as Organization;
+use model::models::organization::repo::{OrganizationRepositoryError, OrganizationRepositoryTrait};
+use model::models::organization_user::entity::Model as Membership;
+use model::models::organization_user::repo::OrganizationUserRepositoryTrait;
+use model::models::user::entity::{CreatedOrganizations, Entity as UserEntity};
+use model::models::user::{entity::Model as User, repo::UserRepositoryTrait};
+use model::models::Models;
+use model::shared::{PaginatedResponse, PaginationOptions};
+use sea_orm::{ColumnTrait, Database, EntityTrait, QueryFilter};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn migrated_models() -> Models {
+    let db = Database::connect("sqlite::memory:").await.expect("open sqlite");
+    Migrator::up(&db, None).await.expect("migrate sqlite");
+    Models::from_connection(db)
+}
+
+async fn create_user(models: &Models, email: &str) -> User {
+    models
+        .user
+        .create(User::new_registration("Ada", "Lovelace", email, "hash"))
+        .await
+        .expect("create user")
+}
+
+fn organization(name: &str, creator_id: Uuid) -> Organization {
+    let now = Utc::now();
+    Organization {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        description: format!("{} description", name),
+        template: json!({ "version": 1, "sections": [] }),
+        project_template: json!({ "version": 1 }),
+        stage: "draft".to_string(),
+        status: "active".to_string(),
+        members: 1,
+        creator_id,
+        settings: json!({}),
+        created_at: now.into(),
+        updated_at: now.into(),
+        deleted_at: None,
+    }
+}
+
+fn membership(organization_id: Uuid, user_id: Uuid) -> Membership {
+    let now = Utc::now();
+    Membership {
+        id: Uuid::new_v4(),
+        user_id,
+        organization_id,
+        dashboards: json!([]),
+        role: "member".to_string(),
+        created_at: now.into(),
+        updated_at: now.into(),
+        deleted_at: None,
+    }
+}
+
+#[tokio::test]
+async fn organization_create_get_and_list() {
+    let models = migrated_models().await;
+    let ada = create_user(&models, "ada@example.com").await;
+
+    let acme = models.organization.create(organization("Acme", ada.id)).await.unwrap();
+    models.organization.create(organization("Globex", ada.id)).await.unwrap();
+
+    let fetched = models.organization.get_by_id(acme.id).await.unwrap();
+    assert_eq!(fetched.name, "Acme");
+    assert_eq!(fetched.template, json!({ "version": 1, "sections": [] }));
+
+    let options = PaginationOptions { sort_by: Some("name".to_string()), sort_order: Some("asc".to_string()), ..Default::default() };
+    let page = models.organization.list(&options).await.unwrap();
+    let names: Vec<&str> = page.items.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, ["Acme", "Globex"]);
+    assert_eq!(page.total, 2);
+
+    assert_eq!(models.organization.list_by_creator(ada.id).await.unwrap().len(), 2);
+    assert!(matches!(
+        models.organization.get_by_id(Uuid::new_v4()).await,
+        Err(OrganizationRepositoryError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn organizations_are_listed_newest_first_by_default() {
+    let models = migrated_models().await;
+    let ada = create_user(&models, "ada@example.com").await;
+    for (name, age_minutes) in [("Oldest", 20), ("Newest", 0), ("Middle", 10)] {
+        let mut org = organization(name, ada.id);
+        org.created_at = (Utc::now() - Duration::minutes(age_minutes)).into();
+        models.organization.create(org).await.unwrap();
+    }
+
+    let names = |page: PaginatedResponse<Organization>| page.items.into_iter().map(|o| o.name).collect::<Vec<_>>();
+    let page = models.organization.list(&PaginationOptions::default()).await.unwrap();
+    assert_eq!(names(page), ["Newest", "Middle", "Oldest"]);
+
+    let oldest_first = PaginationOptions { sort_order: Some("asc".to_string()), ..Default::default() };
+    let page = models.organization.list(&oldest_first).await.unwrap();
+    assert_eq!(names(page), ["Oldest", "Middle", "Newest"]);
+}
+
+#[tokio::test]
+async fn users_reach_their_organizations_through_relations() {
+    let models = migrated_models().await;
+    let ada = create_user(&models, "ada@example.com").await;
+    let grace = create_user(&models, "grace@example.com").await;
+    let acme = models.organization.create(organization("Acme", ada.id)).await.unwrap();
+    models.organization_user.create(membership(acme.id, grace.id)).await.unwrap();
+
+    // Memberships, joined in a single query
+    let rows = UserEntity::find()
+        .filter(model::models::user::entity::Column::Id.eq(grace.id))
+        .find_with_related(model::models::organization::entity::Entity)
+        .all(&models.db)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    let (user, organizations) = &rows[0];
+    assert_eq!(user.id, grace.id);
+    assert_eq!(organizations.iter().map(|o| o.id).collect::<Vec<_>>(), [acme.id]);
+
+    let member_of = models.organization.list_by_member(grace.id).await.unwrap();
+    assert_eq!(member_of.iter().map(|o| o.id).collect::<Vec<_>>(), [acme.id]);
+    assert!(models.organization.list_by_member(ada.id).await.unwrap().is_empty());
+
+    // Created organizations, through the creator_id link
+    let created = UserEntity::find_by_id(ada.id)
+        .find_with_linked(CreatedOrganizations)
+        .all(&models.db)
+        .await
+        .unwrap();
+    assert_eq!(created[0].1.iter().map(|o| o.name.as_str()).collect::<Vec<_>>(), ["Acme"]);
+}
+
+#[tokio::test]
+async fn templates_are_validated_on_write() {
+    let models = migrated_models().await;
+    let ada = create_user(&models, "ada@example.com").await;
+
+    let mut invalid = organization("Acme", ada.id);
+    invalid.template = json!({ "version": 1, "sections": [{ "key": "risk", "title": "Risk" }] });
+    match models.organization.create(invalid).await {
+        Err(OrganizationRepositoryError::InvalidDocument(msg)) => assert!(msg.starts_with("template is invalid"), "{}", msg),
+        other => panic!("expected InvalidDocument, got {:?}", other.map(|o| o.id)),
+    }
+    assert_eq!(models.organization.list(&PaginationOptions::default()).await.unwrap().total, 0);
+
+    let template = json!({
+        "version": 2,
+        "sections": [{
+            "key": "risk",
+            "label": "Risk",
+            "fields": [
+                { "key": "appetite", "label": "Appetite", "kind": "select", "required": true, "options": ["low", "high"] },
+                { "key": "notes", "label": "Notes", "kind": "text", "required": false }
+            ]
+        }]
+    });
+    let mut valid = organization("Acme", ada.id);
+    valid.template = template.clone();
+    let created = models.organization.create(valid).await.unwrap();
+    assert_eq!(models.organization.get_by_id(created.id).await.unwrap().template, template);
+}
@@ -0,0 +1,199 @@
+//! Repositories against a fresh in-memory SQLite database, migrated the same way as Postgres
+
+use chrono::{Duration, Utc};
+use model::cleanup;
+use model::migration::{Migrator, MigratorTrait, SchemaManager};
+use model::models::session::{entity::Model as Session, repo::{SessionRepositoryError, SessionRepositoryTrait}};
+use model::models::user::{entity::Model as User, repo::{UserRepositoryError, UserRepositoryTrait}};
+use model::models::Models;
+use sea_orm::{Database, EntityTrait};
+use uuid::Uuid;
+
+async fn migrated_models() -> Models {
+    let db = Database::connect("sqlite::memory:").await.expect("open sqlite");
+    Migrator::up(&db, None).await.expect("migrate sqlite");
+    Models::from_connection(db)
+}
+
+async fn create_user(models: &Models, email: &str) -> User {
+    models
+        .user
+        .create(User::new_registration("Ada", "Lovelace", email, "hash"))
+        .await
+        .expect("create user")
+}
+
+fn session(user_id: Uuid, expires_in: Duration) -> Session {
+    let now = Utc::now();
+    Session {
+        jti: Uuid::new_v4(),
+        user_id,
+        user_agent: Some("test".to_string()),
+        ip: None,
+        created_at: now.into(),
+        expires_at: (now + expires_in).into(),
+        revoked_at: None,
+    }
+}
+
+#[tokio::test]
+async fn migrations_apply_down_and_up_again() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+
+    std::env::set_var("ALLOW_DESTRUCTIVE_MIGRATIONS", "true");
+    Migrator::down(&db, None).await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn models_share_a_pre_opened_connection() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    let models = Models::from_connection(db.clone());
+
+    // Rows written through the repositories are visible on the caller's own handle
+    let created = create_user(&models, "shared@example.com").await;
+    let found = model::models::user::Entity::find_by_id(created.id).one(&db).await.unwrap();
+    assert_eq!(found.map(|u| u.personal_email_address).as_deref(), Some("shared@example.com"));
+}
+
+#[tokio::test]
+async fn user_create_get_update() {
+    let models = migrated_models().await;
+    let created = create_user(&models, "ada@example.com").await;
+
+    let fetched = models.user.get_by_id(created.id).await.unwrap();
+    assert_eq!(fetched.personal_email_address, "ada@example.com");
+
+    let mut by_email = models.user.get_by_email("ada@example.com").await.unwrap();
+    assert_eq!(by_email.id, created.id);
+
+    by_email.personal_first_name = "Augusta".to_string();
+    let updated = models.user.update(by_email).await.unwrap();
+    assert_eq!(updated.personal_first_name, "Augusta");
+    assert!(updated.updated_at >= created.updated_at);
+}
+
+#[tokio::test]
+async fn duplicate_email_is_reported_as_duplicate() {
+    let models = migrated_models().await;
+    create_user(&models, "ada@example.com").await;
+
+    let err = models
+        .user
+        .create(User::new_registration("Ada", "Byron", "ada@example.com", "hash"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, UserRepositoryError::Duplicate(_)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn session_create_get_revoke() {
+    let models = migrated_models().await;
+    let user = create_user(&models, "ada@example.com").await;
+
+    let created = models.session.create(session(user.id, Duration::days(1))).await.unwrap();
+    let fetched = models.session.get_by_jti(created.jti).await.unwrap();
+    assert!(fetched.is_active());
+
+    models.session.revoke(created.jti).await.unwrap();
+    let revoked = models.session.get_by_jti(created.jti).await.unwrap();
+    assert!(!revoked.is_active());
+
+    // The second revoke loses: that's how a replayed refresh token is detected
+    let again = models.session.revoke(created.jti).await.unwrap_err();
+    assert!(matches!(again, SessionRepositoryError::NotFound(_)));
+
+    let missing = models.session.revoke(Uuid::new_v4()).await.unwrap_err();
+    assert!(matches!(missing, SessionRepositoryError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn session_listing_and_expiry_purge() {
+    let models = migrated_models().await;
+    let user = create_user(&models, "ada@example.com").await;
+
+    let active = models.session.create(session(user.id, Duration::days(1))).await.unwrap();
+    let revoked = models.session.create(session(user.id, Duration::days(1))).await.unwrap();
+    models.session.revoke(revoked.jti).await.unwrap();
+    let expired = models.session.create(session(user.id, -Duration::hours(1))).await.unwrap();
+
+    let all = models.session.list_by_user(user.id).await.unwrap();
+    assert_eq!(all.len(), 3);
+
+    let live: Vec<Uuid> = models.session.list_active_by_user(user.id).await.unwrap().iter().map(|s| s.jti).collect();
+    assert_eq!(live, vec![active.jti]);
+
+    assert_eq!(models.session.delete_expired(Utc::now()).await.unwrap(), 1);
+    assert!(matches!(
+        models.session.get_by_jti(expired.jti).await,
+        Err(SessionRepositoryError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn cleanup_clears_stale_reset_codes_but_not_bans() {
+    let models = migrated_models().await;
+    let long_ago = Utc::now() - cleanup::RESET_CODE_LIFETIME - Duration::days(1);
+
+    let mut stale = create_user(&models, "stale@example.com").await;
+    stale.peripheral_authentication_code = Some("123456".to_string());
+    stale.peripheral_timeout = Some(long_ago.into());
+    stale.peripheral_failed_code_attempts = 2;
+    models.user.update(stale.clone()).await.unwrap();
+
+    let mut fresh = create_user(&models, "fresh@example.com").await;
+    fresh.peripheral_authentication_code = Some("654321".to_string());
+    fresh.peripheral_timeout = Some(Utc::now().into());
+    models.user.update(fresh.clone()).await.unwrap();
+
+    // Banned long ago: the ban time lives in peripheral_timeout with no code alongside it
+    let mut banned = create_user(&models, "banned@example.com").await;
+    banned.peripheral_is_banned = true;
+    banned.peripheral_timeout = Some(long_ago.into());
+    models.user.update(banned.clone()).await.unwrap();
+
+    assert_eq!(cleanup::clear_expired_reset_codes(&models.db, Utc::now()).await.unwrap(), 1);
+
+    let stale = models.user.get_by_id(stale.id).await.unwrap();
+    assert_eq!(stale.peripheral_authentication_code, None);
+    assert_eq!(stale.peripheral_timeout, None);
+    assert_eq!(stale.peripheral_failed_code_attempts, 0);
+
+    let fresh = models.user.get_by_id(fresh.id).await.unwrap();
+    assert_eq!(fresh.peripheral_authentication_code.as_deref(), Some("654321"));
+
+    let banned = models.user.get_by_id(banned.id).await.unwrap();
+    assert!(banned.peripheral_is_banned);
+    assert!(banned.peripheral_timeout.is_some());
+}
+
+#[tokio::test]
+async fn cleanup_deletes_only_expired_sessions() {
+    let models = migrated_models().await;
+    let user = create_user(&models, "ada@example.com").await;
+
+    let live = models.session.create(session(user.id, Duration::days(1))).await.unwrap();
+    models.session.create(session(user.id, -Duration::hours(1))).await.unwrap();
+
+    assert_eq!(cleanup::delete_expired_sessions(&models.db, Utc::now()).await.unwrap(), 1);
+    let left: Vec<Uuid> = models.session.list_by_user(user.id).await.unwrap().iter().map(|s| s.jti).collect();
+    assert_eq!(left, vec![live.jti]);
+}
+
+#[tokio::test]
+async fn query_indexes_exist_after_up() {
+    let models = migrated_models().await;
+    let manager = SchemaManager::new(&models.db);
+
+    for (table, index) in [
+        ("users", "idx_users_created_at"),
+        ("users", "idx_users_deleted_at"),
+        ("projects", "idx_projects_organization_id"),
+        ("organization_users", "idx_organization_users_user_id"),
+        ("organization_users", "idx_organization_users_organization_id"),
+    ] {
+        assert!(manager.has_index(table, index).await.unwrap(), "missing {} on {}", index, table);
+    }
+}
@@ -0,0 +1,93 @@
+//! `UserRepository` against an in-memory SQLite `users` table built from the entity
+
+use model::models::user::entity::{Entity as UserEntity, Model as User};
+use model::models::user::repo::{UserRepository, UserRepositoryError, UserRepositoryTrait};
+use model::shared::PaginationOptions;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, Schema};
+
+async fn users_table() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.expect("open sqlite");
+    let backend = db.get_database_backend();
+    let create = backend.build(&Schema::new(backend).create_table_from_entity(UserEntity));
+    // The entity doesn't opt its uuid key out of auto-increment, which SQLite only allows on integers
+    let create = create.to_string().replace(" AUTOINCREMENT", "");
+    db.execute_unprepared(&create).await.expect("create users table");
+    db
+}
+
+#[tokio::test]
+async fn update_persists_edits_and_stamps_updated_at() {
+    let repo = UserRepository::new(users_table().await);
+    let mut created = User::new_registration("Ada", "Lovelace", "ada@example.com", "hash");
+    created.updated_at = "2000-01-01T00:00:00+00:00".parse().unwrap();
+    let created = repo.create(created).await.unwrap();
+
+    let mut edited = created.clone();
+    edited.personal_first_name = "Augusta".to_string();
+    edited.peripheral_is_banned = true;
+    repo.update(edited).await.unwrap();
+
+    let stored = repo.get_by_id(created.id).await.unwrap();
+    assert_eq!(stored.personal_first_name, "Augusta");
+    assert!(stored.peripheral_is_banned);
+    assert_eq!(stored.created_at, created.created_at);
+    assert!(stored.updated_at > created.updated_at);
+}
+
+#[tokio::test]
+async fn record_login_leaves_other_columns_alone() {
+    let repo = UserRepository::new(users_table().await);
+    let created = repo.create(User::new_registration("Ada", "Lovelace", "ada@example.com", "hash")).await.unwrap();
+    repo.increment_failed_logins(created.id).await.unwrap();
+
+    // A ban that lands between sign-in's read and its bookkeeping write must survive the write
+    let mut banned = repo.get_by_id(created.id).await.unwrap();
+    banned.peripheral_is_banned = true;
+    repo.update(banned).await.unwrap();
+
+    let at = chrono::Utc::now();
+    repo.record_login(created.id, at).await.unwrap();
+
+    let stored = repo.get_by_id(created.id).await.unwrap();
+    assert!(stored.peripheral_is_banned);
+    assert_eq!(stored.failed_login_attempts, 0);
+    assert!(stored.locked_until.is_none());
+    assert_eq!(stored.last_login_at.map(|t| t.timestamp_millis()), Some(at.timestamp_millis()));
+}
+
+#[tokio::test]
+async fn increment_failed_logins_returns_the_count_it_wrote() {
+    let repo = UserRepository::new(users_table().await);
+    let created = repo.create(User::new_registration("Ada", "Lovelace", "ada@example.com", "hash")).await.unwrap();
+
+    for expected in 1..=3 {
+        assert_eq!(repo.increment_failed_logins(created.id).await.unwrap(), expected);
+    }
+    assert_eq!(repo.get_by_id(created.id).await.unwrap().failed_login_attempts, 3);
+
+    let missing = repo.increment_failed_logins(uuid::Uuid::new_v4()).await;
+    assert!(matches!(missing, Err(UserRepositoryError::NotFound(_))), "{:?}", missing.map(|_| ()));
+}
+
+#[tokio::test]
+async fn email_filter_matches_like_wildcards_literally() {
+    let repo = UserRepository::new(users_table().await);
+    for email in ["ada_l@example.com", "adaxl@example.com", "100%@example.com", "back\\slash@example.com"] {
+        repo.create(User::new_registration("Ada", "Lovelace", email, "hash")).await.unwrap();
+    }
+
+    let emails = |filter: &'static str| {
+        let repo = repo.clone();
+        async move {
+            let page = repo.list(&PaginationOptions::default(), Some(filter)).await.unwrap();
+            let mut emails: Vec<String> = page.items.into_iter().map(|u| u.personal_email_address).collect();
+            emails.sort();
+            emails
+        }
+    };
+
+    assert_eq!(emails("ada_l").await, ["ada_l@example.com"]);
+    assert_eq!(emails("%").await, ["100%@example.com"]);
+    assert_eq!(emails("k\\s").await, ["back\\slash@example.com"]);
+    assert_eq!(emails("ADA").await, ["ada_l@example.com", "adaxl@example.com"]);
+}
@@ -1,10 +1,14 @@
 use ethers::{
+    abi::Tokenizable,
+    contract::{Multicall, MULTICALL_ADDRESS},
     prelude::*,
     providers::{Http, Provider},
-    types::{Address, U256},
+    types::{transaction::eip2718::TypedTransaction, Address, U256},
 };
 use std::sync::Arc;
 
+use super::router::SwapCall;
+
 // ERC20 Token ABI (minimal)
 abigen!(
     ERC20,
@@ -14,6 +18,8 @@ abigen!(
         function decimals() external view returns (uint8)
         function totalSupply() external view returns (uint256)
         function balanceOf(address account) external view returns (uint256)
+        function allowance(address owner, address spender) external view returns (uint256)
+        function approve(address spender, uint256 amount) external returns (bool)
     ]"#
 );
 
@@ -35,6 +41,26 @@ abigen!(
     ]"#
 );
 
+// Uniswap V2 Router ABI (for PancakeSwap)
+abigen!(
+    UniswapV2Router,
+    r#"[
+        function swapExactTokensForTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline) external returns (uint256[] amounts)
+    ]"#
+);
+
+// Seconds a signed swap stays valid before the router rejects it
+pub(crate) const SWAP_DEADLINE_SECONDS: i64 = 20 * 60;
+
+/// Token address callers use to ask for the chain's native coin instead of an ERC20
+pub const NATIVE_TOKEN_SENTINEL: &str = "native";
+
+/// Whether `token_address` means the native coin: the `native` sentinel or the zero address
+pub fn is_native_token(token_address: &str) -> bool {
+    token_address.eq_ignore_ascii_case(NATIVE_TOKEN_SENTINEL)
+        || token_address.parse::<Address>().map(|a| a.is_zero()).unwrap_or(false)
+}
+
 pub struct BlockchainClient {
     provider: Arc<Provider<Http>>,
 }
@@ -47,6 +73,31 @@ impl BlockchainClient {
         })
     }
 
+    /// Whether `address` has contract code deployed; plain accounts have none
+    pub async fn is_contract(&self, address: Address) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(!self.provider.get_code(address, None).await?.is_empty())
+    }
+
+    /// Latest block number; a cheap call for checking that the RPC endpoint answers
+    pub async fn get_block_number(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.provider.get_block_number().await?.as_u64())
+    }
+
+    /// Native coin balance of `owner`, in wei
+    pub async fn get_native_balance(&self, owner: Address) -> Result<U256, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.provider.get_balance(owner, None).await?)
+    }
+
+    /// ERC20 `balanceOf(owner)`, in the token's base units
+    pub async fn get_token_balance(
+        &self,
+        token: Address,
+        owner: Address,
+    ) -> Result<U256, Box<dyn std::error::Error + Send + Sync>> {
+        let contract = ERC20::new(token, self.provider.clone());
+        Ok(contract.balance_of(owner).call().await?)
+    }
+
     /// Fetch token metadata (name, symbol, decimals)
     pub async fn get_token_metadata(
         &self,
@@ -120,39 +171,289 @@ impl BlockchainClient {
         })
     }
 
-    /// Calculate token price in USD
+    /// Get pair reserves and token metadata in a single Multicall3 round trip
+    pub async fn get_pair_data_with_metadata(
+        &self,
+        pair_address: Address,
+        token_address: &str,
+    ) -> Result<(PairData, TokenMetadata), Box<dyn std::error::Error + Send + Sync>> {
+        let token: Address = token_address.parse()?;
+        let pair_contract = UniswapV2Pair::new(pair_address, self.provider.clone());
+        let token_contract = ERC20::new(token, self.provider.clone());
+
+        // Reserves and token0 are required; metadata calls may revert on non-standard tokens
+        let mut multicall = Multicall::new(self.provider.clone(), Some(MULTICALL_ADDRESS)).await?;
+        multicall
+            .add_call(pair_contract.get_reserves(), false)
+            .add_call(pair_contract.token_0(), false)
+            .add_call(token_contract.name(), true)
+            .add_call(token_contract.symbol(), true)
+            .add_call(token_contract.decimals(), true)
+            .add_call(token_contract.total_supply(), true);
+
+        let mut results = multicall.call_raw().await?.into_iter();
+        let mut next_token = || results.next().and_then(|r| r.ok());
+
+        let (reserve0, reserve1, _) = next_token()
+            .and_then(|t| <(u128, u128, u32)>::from_token(t).ok())
+            .ok_or("Failed to decode pair reserves")?;
+        let token0 = next_token()
+            .and_then(|t| Address::from_token(t).ok())
+            .ok_or("Failed to decode pair token0")?;
+
+        let name = next_token()
+            .and_then(|t| String::from_token(t).ok())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let symbol = next_token()
+            .and_then(|t| String::from_token(t).ok())
+            .unwrap_or_else(|| "???".to_string());
+        let decimals = next_token()
+            .and_then(|t| u8::from_token(t).ok())
+            .unwrap_or(18);
+        let total_supply = next_token()
+            .and_then(|t| U256::from_token(t).ok())
+            .unwrap_or(U256::zero());
+
+        // Determine which reserve is our token
+        let (token_reserve, quote_reserve) = if token0 == token {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+
+        Ok((
+            PairData {
+                token_reserve: U256::from(token_reserve),
+                quote_reserve: U256::from(quote_reserve),
+                pair_address,
+            },
+            TokenMetadata {
+                name,
+                symbol,
+                decimals,
+                total_supply,
+            },
+        ))
+    }
+
+    /// Batched pair + metadata fetch, falling back to the sequential calls if multicall fails.
+    /// With `cached_metadata` only the reserves are read
+    async fn get_pair_snapshot(
+        &self,
+        pair_address: Address,
+        token_address: &str,
+        cached_metadata: Option<&TokenMetadata>,
+    ) -> Result<(PairData, TokenMetadata), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(metadata) = cached_metadata {
+            let pair_data = self.get_pair_data(pair_address, token_address).await?;
+            return Ok((pair_data, metadata.clone()));
+        }
+
+        match self.get_pair_data_with_metadata(pair_address, token_address).await {
+            Ok(snapshot) => Ok(snapshot),
+            Err(e) => {
+                tracing::warn!("Multicall batch failed, falling back to sequential calls: {}", e);
+                let pair_data = self.get_pair_data(pair_address, token_address).await?;
+                let token_metadata = self.get_token_metadata(token_address).await?;
+                Ok((pair_data, token_metadata))
+            }
+        }
+    }
+
+    /// Quote an exact-input swap along `path` using the V2 pair reserves of each hop
+    pub async fn quote_exact_input(
+        &self,
+        factory_address: &str,
+        path: &[Address],
+        amount_in: U256,
+    ) -> Result<RouteQuote, Box<dyn std::error::Error + Send + Sync>> {
+        if path.len() < 2 {
+            return Err("Swap path needs at least two tokens".into());
+        }
+
+        let factory: Address = factory_address.parse()?;
+        let factory_contract = UniswapV2Factory::new(factory, self.provider.clone());
+
+        let mut amount_out = amount_in;
+        // Output at mid price (no fee, no impact) used to derive price impact
+        let mut mid_amount_out = amount_in.as_u128() as f64;
+
+        for hop in path.windows(2) {
+            let pair_address = factory_contract.get_pair(hop[0], hop[1]).call().await?;
+            if pair_address == Address::zero() {
+                return Err("No liquidity pair found".into());
+            }
+
+            let pair_data = self.get_pair_data(pair_address, &format!("{:?}", hop[0])).await?;
+            if pair_data.token_reserve.is_zero() || pair_data.quote_reserve.is_zero() {
+                return Err("Insufficient liquidity".into());
+            }
+
+            mid_amount_out *= pair_data.quote_reserve.as_u128() as f64
+                / pair_data.token_reserve.as_u128() as f64;
+            amount_out = get_amount_out(amount_out, pair_data.token_reserve, pair_data.quote_reserve);
+        }
+
+        let price_impact = if mid_amount_out > 0.0 {
+            ((1.0 - amount_out.as_u128() as f64 / mid_amount_out) * 100.0).max(0.0)
+        } else {
+            0.0
+        };
+
+        Ok(RouteQuote {
+            path: path.to_vec(),
+            amount_out,
+            price_impact,
+        })
+    }
+
+    /// Quote the direct pair, falling back to routing through the wrapped native token
+    pub async fn quote_best_route(
+        &self,
+        factory_address: &str,
+        token_in: Address,
+        token_out: Address,
+        wrapped_native: Address,
+        amount_in: U256,
+    ) -> Result<RouteQuote, Box<dyn std::error::Error + Send + Sync>> {
+        match self.quote_exact_input(factory_address, &[token_in, token_out], amount_in).await {
+            Ok(quote) => Ok(quote),
+            Err(_) if token_in != wrapped_native && token_out != wrapped_native => {
+                self.quote_exact_input(factory_address, &[token_in, wrapped_native, token_out], amount_in)
+                    .await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Estimate gas for a swap call from `owner` and price it at the current gas price. The node
+    /// simulates the call, so a swap that would revert (most often a missing approval) fails here
+    /// with a message saying why
+    pub async fn estimate_gas(
+        &self,
+        owner: Address,
+        token_in: Address,
+        call: &SwapCall,
+        amount_in: U256,
+    ) -> Result<GasEstimate, Box<dyn std::error::Error + Send + Sync>> {
+        // Name the usual revert causes instead of surfacing an opaque "execution reverted"
+        let token = ERC20::new(token_in, self.provider.clone());
+        if token.balance_of(owner).call().await? < amount_in {
+            return Err("Insufficient balance of the input token".into());
+        }
+        if token.allowance(owner, call.to).call().await? < amount_in {
+            return Err("Insufficient allowance: the router must be approved to spend the input token".into());
+        }
+
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(owner)
+            .to(call.to)
+            .data(call.data.clone())
+            .value(call.value)
+            .into();
+        let gas = self
+            .provider
+            .estimate_gas(&tx, None)
+            .await
+            .map_err(|e| format!("Swap would revert: {}", e))?;
+        let gas_price = self.provider.get_gas_price().await?;
+
+        Ok(GasEstimate { gas, gas_price, fee: gas * gas_price })
+    }
+
+    /// Sign and broadcast a swap call, approving `call.to` to spend the input token first if needed
+    pub async fn send_swap(
+        &self,
+        private_key: &str,
+        token_in: Address,
+        call: &SwapCall,
+        amount_in: U256,
+        wait_for_receipt: bool,
+    ) -> Result<SwapTransaction, Box<dyn std::error::Error + Send + Sync>> {
+        let chain_id = self.provider.get_chainid().await?.as_u64();
+        let signer = private_key
+            .trim_start_matches("0x")
+            .parse::<LocalWallet>()?
+            .with_chain_id(chain_id);
+        let owner = signer.address();
+        let client = Arc::new(SignerMiddleware::new((*self.provider).clone(), signer));
+
+        // Approve the router to spend the input token when the allowance is short
+        let token = ERC20::new(token_in, client.clone());
+        let allowance = token.allowance(owner, call.to).call().await?;
+        if allowance < amount_in {
+            let approve_call = token.approve(call.to, amount_in);
+            let approval = approve_call.send().await?.await?;
+            if approval.and_then(|receipt| receipt.status) != Some(U64::from(1)) {
+                return Err("Token approval failed".into());
+            }
+        }
+
+        let tx = TransactionRequest::new()
+            .from(owner)
+            .to(call.to)
+            .data(call.data.clone())
+            .value(call.value);
+        let pending = client.send_transaction(tx, None).await?;
+        let tx_hash = pending.tx_hash();
+        let receipt = if wait_for_receipt { pending.await? } else { None };
+
+        Ok(SwapTransaction { tx_hash, receipt })
+    }
+
+    /// Look up a transaction's receipt, telling not-yet-mined apart from unknown hashes
+    pub async fn get_transaction_receipt(
+        &self,
+        hash: H256,
+    ) -> Result<TransactionLookup, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(receipt) = self.provider.get_transaction_receipt(hash).await? {
+            return Ok(TransactionLookup::Mined(Box::new(receipt)));
+        }
+
+        // No receipt yet: the node either still holds it in the mempool or never saw it
+        match self.provider.get_transaction(hash).await? {
+            Some(_) => Ok(TransactionLookup::Pending),
+            None => Ok(TransactionLookup::NotFound),
+        }
+    }
+
+    /// Calculate token price in USD. Pass `cached_metadata` when the token's metadata is already
+    /// known to skip re-reading it from the contract
     pub async fn calculate_token_price(
         &self,
         token_address: &str,
         factory_address: &str,
         wbnb_address: &str,
         busd_address: &str,
+        busd_decimals: u8,
+        cached_metadata: Option<&TokenMetadata>,
     ) -> Result<TokenPrice, Box<dyn std::error::Error + Send + Sync>> {
         // First, try to find token/BUSD pair (direct USD price)
         if let Some(pair_address) = self
             .find_pair(token_address, busd_address, factory_address)
             .await?
         {
-            let pair_data = self.get_pair_data(pair_address, token_address).await?;
-            let token_metadata = self.get_token_metadata(token_address).await?;
+            let (pair_data, token_metadata) =
+                self.get_pair_snapshot(pair_address, token_address, cached_metadata).await?;
 
             // Price = quote_reserve / token_reserve
             let price = calculate_price(
                 pair_data.token_reserve,
                 pair_data.quote_reserve,
                 token_metadata.decimals,
-                18, // BUSD decimals
+                busd_decimals,
             );
 
             let liquidity_usd = calculate_liquidity(
                 pair_data.quote_reserve,
-                18, // BUSD decimals
+                busd_decimals,
             );
 
             return Ok(TokenPrice {
                 price_usd: price,
                 liquidity_usd,
                 pair_address: Some(pair_address),
+                metadata: token_metadata,
             });
         }
 
@@ -161,11 +462,13 @@ impl BlockchainClient {
             .find_pair(token_address, wbnb_address, factory_address)
             .await?
         {
-            let pair_data = self.get_pair_data(pair_address, token_address).await?;
-            let token_metadata = self.get_token_metadata(token_address).await?;
+            let (pair_data, token_metadata) =
+                self.get_pair_snapshot(pair_address, token_address, cached_metadata).await?;
 
             // Get BNB price in BUSD
-            let bnb_price = self.get_bnb_price(factory_address, wbnb_address, busd_address).await?;
+            let bnb_price = self
+                .get_bnb_price(factory_address, wbnb_address, busd_address, busd_decimals)
+                .await?;
 
             // Price in BNB
             let price_in_bnb = calculate_price(
@@ -188,6 +491,7 @@ impl BlockchainClient {
                 price_usd,
                 liquidity_usd,
                 pair_address: Some(pair_address),
+                metadata: token_metadata,
             });
         }
 
@@ -195,12 +499,51 @@ impl BlockchainClient {
         Err("No liquidity pair found".into())
     }
 
+    /// Price the native coin through the wrapped native/BUSD pair. The native coin has no contract,
+    /// so the metadata is fixed (18 decimals, `symbol`) and total supply is reported as zero
+    pub async fn calculate_native_price(
+        &self,
+        factory_address: &str,
+        wbnb_address: &str,
+        busd_address: &str,
+        busd_decimals: u8,
+        symbol: &str,
+    ) -> Result<TokenPrice, Box<dyn std::error::Error + Send + Sync>> {
+        let pair_address = self
+            .find_pair(wbnb_address, busd_address, factory_address)
+            .await?
+            .ok_or("No liquidity pair found")?;
+
+        let pair_data = self.get_pair_data(pair_address, wbnb_address).await?;
+
+        let price_usd = calculate_price(
+            pair_data.token_reserve,
+            pair_data.quote_reserve,
+            18, // WBNB decimals
+            busd_decimals,
+        );
+        let liquidity_usd = calculate_liquidity(pair_data.quote_reserve, busd_decimals);
+
+        Ok(TokenPrice {
+            price_usd,
+            liquidity_usd,
+            pair_address: Some(pair_address),
+            metadata: TokenMetadata {
+                name: symbol.to_string(),
+                symbol: symbol.to_string(),
+                decimals: 18,
+                total_supply: U256::zero(),
+            },
+        })
+    }
+
     /// Get BNB price in USD from WBNB/BUSD pair
     async fn get_bnb_price(
         &self,
         factory_address: &str,
         wbnb_address: &str,
         busd_address: &str,
+        busd_decimals: u8,
     ) -> Result<f64, Box<dyn std::error::Error + Send + Sync>> {
         let pair_address = self
             .find_pair(wbnb_address, busd_address, factory_address)
@@ -214,7 +557,7 @@ impl BlockchainClient {
             pair_data.token_reserve,
             pair_data.quote_reserve,
             18, // WBNB decimals
-            18, // BUSD decimals
+            busd_decimals,
         );
 
         Ok(price)
@@ -238,13 +581,24 @@ fn calculate_price(
     quote_reserve_f64 / token_reserve_f64
 }
 
+// PancakeSwap V2 charges 0.25% per hop
+const PANCAKESWAP_V2_FEE_BPS: u64 = 25;
+
+// Helper implementing the V2 router's getAmountOut (constant product minus fee)
+fn get_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    let amount_in_with_fee = amount_in * U256::from(10_000 - PANCAKESWAP_V2_FEE_BPS);
+    let numerator = amount_in_with_fee * reserve_out;
+    let denominator = reserve_in * U256::from(10_000) + amount_in_with_fee;
+    numerator / denominator
+}
+
 // Helper function to calculate liquidity (2x quote reserve)
 fn calculate_liquidity(quote_reserve: U256, quote_decimals: u8) -> f64 {
     let quote_reserve_f64 = quote_reserve.as_u128() as f64 / 10f64.powi(quote_decimals as i32);
     quote_reserve_f64 * 2.0 // Total liquidity is 2x one side
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TokenMetadata {
     pub name: String,
     pub symbol: String,
@@ -264,4 +618,36 @@ pub struct TokenPrice {
     pub price_usd: f64,
     pub liquidity_usd: f64,
     pub pair_address: Option<Address>,
+    pub metadata: TokenMetadata,
+}
+
+#[derive(Debug)]
+pub struct RouteQuote {
+    pub path: Vec<Address>,
+    pub amount_out: U256,
+    /// Price impact as a percentage of the mid-price output
+    pub price_impact: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GasEstimate {
+    pub gas: U256,
+    /// Wei per gas unit at the time of the estimate
+    pub gas_price: U256,
+    /// `gas * gas_price`, in wei
+    pub fee: U256,
+}
+
+#[derive(Debug)]
+pub struct SwapTransaction {
+    pub tx_hash: H256,
+    /// Present only when the caller waited for the transaction to be mined
+    pub receipt: Option<TransactionReceipt>,
+}
+
+#[derive(Debug)]
+pub enum TransactionLookup {
+    Mined(Box<TransactionReceipt>),
+    Pending,
+    NotFound,
 }
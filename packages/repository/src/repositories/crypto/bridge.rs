@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+use super::data::{format_token_amount, parse_token_amount, CryptoError, MultiChainSwap};
+
+/// Cost and duration of moving a swap's input across chains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeQuote {
+    /// Bridge fee, in the input token
+    pub fee: String,
+    /// Expected time until funds arrive on the destination chain
+    pub estimated_seconds: u64,
+}
+
+/// Prices a cross-chain route; implemented per bridge protocol (Stargate, LayerZero, ...)
+#[async_trait]
+pub trait BridgeQuoter: Send + Sync {
+    async fn quote(&self, swap: &MultiChainSwap) -> Result<BridgeQuote, CryptoError>;
+}
+
+/// Fixed fee model: `flat_fee + amount * fee_bps / 10_000` and a constant transfer time
+#[derive(Debug, Clone)]
+pub struct StaticBridgeQuoter {
+    pub fee_bps: u32,
+    /// Added to every transfer, in the input token
+    pub flat_fee: String,
+    pub estimated_seconds: u64,
+}
+
+impl StaticBridgeQuoter {
+    /// `BRIDGE_FEE_BPS` (default 10), `BRIDGE_FLAT_FEE` (default 0), `BRIDGE_ESTIMATED_SECONDS` (default 600)
+    pub fn from_env() -> Self {
+        Self {
+            fee_bps: std::env::var("BRIDGE_FEE_BPS").ok().and_then(|v| v.parse().ok()).unwrap_or(10),
+            flat_fee: std::env::var("BRIDGE_FLAT_FEE").unwrap_or_else(|_| "0".to_string()),
+            estimated_seconds: std::env::var("BRIDGE_ESTIMATED_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(600),
+        }
+    }
+}
+
+#[async_trait]
+impl BridgeQuoter for StaticBridgeQuoter {
+    async fn quote(&self, swap: &MultiChainSwap) -> Result<BridgeQuote, CryptoError> {
+        let decimals = swap.from_token.decimals;
+        let amount = parse_token_amount(&swap.amount, decimals)?;
+        let flat_fee: U256 = ethers::utils::parse_units(&self.flat_fee, decimals as u32)
+            .map_err(|_| CryptoError::SwapError("Invalid bridge flat fee".to_string()))?
+            .into();
+
+        Ok(BridgeQuote {
+            fee: format_token_amount(flat_fee + amount * U256::from(self.fee_bps) / U256::from(10_000), decimals)?,
+            estimated_seconds: self.estimated_seconds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::crypto::data::TokenInfo;
+
+    fn usdt_bsc_to_eth(amount: &str) -> MultiChainSwap {
+        let usdt = |address: &str| TokenInfo { symbol: "USDT".to_string(), address: Some(address.to_string()), decimals: 18 };
+        MultiChainSwap {
+            from_chain: "bsc".to_string(),
+            to_chain: "eth".to_string(),
+            from_token: usdt("0x55d398326f99059fF775485246999027B3197955"),
+            to_token: usdt("0xdAC17F958D2ee523a2206206994597C13D831ec7"),
+            amount: amount.to_string(),
+            slippage: "0.5".to_string(),
+            bridge: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn static_quote_is_the_flat_fee_plus_the_bps_share() {
+        let quoter = StaticBridgeQuoter { fee_bps: 10, flat_fee: "0.5".to_string(), estimated_seconds: 120 };
+
+        let quote = quoter.quote(&usdt_bsc_to_eth("100")).await.unwrap();
+        assert_eq!(quote.fee.parse::<f64>().unwrap(), 0.6);
+        assert_eq!(quote.estimated_seconds, 120);
+    }
+
+    #[tokio::test]
+    async fn invalid_amounts_or_fees_are_swap_errors() {
+        let quoter = StaticBridgeQuoter { fee_bps: 10, flat_fee: "0".to_string(), estimated_seconds: 600 };
+        assert!(matches!(quoter.quote(&usdt_bsc_to_eth("lots")).await, Err(CryptoError::SwapError(_))));
+
+        let quoter = StaticBridgeQuoter { flat_fee: "free".to_string(), ..quoter };
+        assert!(matches!(quoter.quote(&usdt_bsc_to_eth("100")).await, Err(CryptoError::SwapError(_))));
+    }
+}
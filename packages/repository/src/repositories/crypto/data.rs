@@ -1,8 +1,14 @@
+use ethers::types::{Address, TransactionReceipt, U256, U64};
+use ethers::utils::{format_units, parse_units, to_checksum};
 use hex;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-#[allow(dead_code)]
+use super::blockchain_client::BlockchainClient;
+use super::bridge::{BridgeQuoter, StaticBridgeQuoter};
+use super::router::SwapRouter;
+use std::sync::Arc;
+
 #[derive(Debug)]
 pub enum CryptoError {
     /// Error during wallet creation
@@ -22,8 +28,42 @@ pub enum CryptoError {
 
     /// Serialization/deserialization error
     SerializationError(String),
+
+    /// Transaction hash unknown to the node
+    TransactionNotFound(String),
+}
+
+impl CryptoError {
+    /// Stable machine-readable code for API responses
+    pub fn code(&self) -> &'static str {
+        match self {
+            CryptoError::WalletCreationError(_) => "WALLET_CREATION_FAILED",
+            CryptoError::BalanceError(_) => "BALANCE_UNAVAILABLE",
+            CryptoError::SwapError(_) => "SWAP_ERROR",
+            CryptoError::InvalidAddress(_) => "INVALID_ADDRESS",
+            CryptoError::NetworkError(_) => "NETWORK_ERROR",
+            CryptoError::SerializationError(_) => "SERIALIZATION_ERROR",
+            CryptoError::TransactionNotFound(_) => "TRANSACTION_NOT_FOUND",
+        }
+    }
 }
 
+impl std::fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CryptoError::WalletCreationError(msg) => write!(f, "Wallet creation failed: {}", msg),
+            CryptoError::BalanceError(msg) => write!(f, "Balance error: {}", msg),
+            CryptoError::SwapError(msg) => write!(f, "Swap error: {}", msg),
+            CryptoError::InvalidAddress(msg) => write!(f, "Invalid address: {}", msg),
+            CryptoError::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            CryptoError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            CryptoError::TransactionNotFound(msg) => write!(f, "Transaction not found: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Wallet {
@@ -85,7 +125,7 @@ impl Wallet {
         let mut balances = Vec::new();
 
         // Iterate through all configured RPC endpoints
-        for (chain, _endpoint) in &config.rpc_endpoints {
+        for chain in config.rpc_endpoints.keys() {
             // Get native token balance
             match self.get_balance(config, chain, &format!("{}_NATIVE", chain.to_uppercase())) {
                 Ok(balance) => balances.push(balance),
@@ -99,10 +139,11 @@ impl Wallet {
         Ok(balances)
     }
 
-    /// Swap tokens (single-chain or multi-chain)
-    pub fn swap_tokens(
+    /// Swap tokens (single-chain or multi-chain); single-chain swaps are quoted and built by `router`
+    pub async fn swap_tokens(
         &self,
         config: &CryptoConfig,
+        router: &dyn SwapRouter,
         swap: SwapType,
     ) -> Result<SwapResult, CryptoError> {
         // Validate wallet
@@ -114,10 +155,10 @@ impl Wallet {
 
         match swap {
             SwapType::SingleChain(single_swap) => {
-                Self::execute_single_chain_swap(self, config, single_swap)
+                Self::execute_single_chain_swap(self, config, router, single_swap).await
             }
             SwapType::MultiChain(multi_swap) => {
-                Self::execute_multi_chain_swap(self, config, multi_swap)
+                Self::execute_multi_chain_swap(self, config, multi_swap).await
             }
         }
     }
@@ -148,17 +189,12 @@ impl Wallet {
     }
 
     // Private helper methods
-    fn execute_single_chain_swap(
+    async fn execute_single_chain_swap(
         &self,
-        _config: &CryptoConfig,
+        config: &CryptoConfig,
+        router: &dyn SwapRouter,
         swap: SingleChainSwap,
     ) -> Result<SwapResult, CryptoError> {
-        // In production, this would:
-        // 1. Get the best swap route from DEX aggregators (1inch, ParaSwap, etc.)
-        // 2. Build and sign the transaction
-        // 3. Submit to the blockchain
-        // 4. Monitor transaction status
-
         tracing::info!(
             "Executing single-chain swap on {}: {} {} -> {}",
             swap.chain,
@@ -168,36 +204,92 @@ impl Wallet {
         );
 
         // Validate slippage
-        let slippage: f64 = swap
-            .slippage
-            .parse()
-            .map_err(|_| CryptoError::SwapError("Invalid slippage value".to_string()))?;
+        let slippage = validate_slippage(&swap.slippage)?;
 
-        if slippage < 0.0 || slippage > 50.0 {
+        let chain = config.chain_dex(&swap.chain)?;
+        if swap.from_token.address.is_none() || swap.to_token.address.is_none() {
             return Err(CryptoError::SwapError(
-                "Slippage must be between 0 and 50%".to_string(),
+                "Native coin swaps are not supported, use the wrapped token address".to_string(),
             ));
         }
+        let token_in = resolve_token_address(&swap.from_token.address, chain.wrapped_native)?;
+        let token_out = resolve_token_address(&swap.to_token.address, chain.wrapped_native)?;
+        let amount_in = parse_token_amount(&swap.amount, swap.from_token.decimals)?;
+        let owner: Address = self
+            .address
+            .parse()
+            .map_err(|_| CryptoError::InvalidAddress(format!("Invalid wallet address: {}", self.address)))?;
+
+        let client = BlockchainClient::new(chain.rpc_url).await.map_err(|e| {
+            CryptoError::NetworkError(format!("Failed to create blockchain client: {}", e))
+        })?;
+
+        // Quote first so the router enforces a slippage-protected minimum output
+        let quote = router.quote(&client, &chain, token_in, token_out, amount_in).await?;
+        let amount_out_min = min_amount_out(quote.amount_out, slippage);
+        let route: Vec<String> = quote.path.iter().map(|address| to_checksum(address, None)).collect();
+        let call = router.build_tx(&chain, &quote, amount_in, amount_out_min, owner).await?;
+
+        let estimate = client
+            .estimate_gas(owner, token_in, &call, amount_in)
+            .await
+            .map_err(|e| CryptoError::SwapError(format!("Unable to estimate gas: {}", e)));
+
+        // Everything up to signing has run; report it without broadcasting
+        if swap.dry_run {
+            let estimated_fee = format_token_amount(estimate?.fee, 18)?;
+            return Ok(SwapResult {
+                tx_hash: DRY_RUN_TX_HASH.to_string(),
+                amount_out: format_token_amount(quote.amount_out, swap.to_token.decimals)?,
+                min_amount_out: format_token_amount(amount_out_min, swap.to_token.decimals)?,
+                fee: estimated_fee,
+                status: SwapStatus::Pending,
+                route,
+                estimated_seconds: None,
+            });
+        }
 
-        // TODO: Implement actual swap logic
-        // - Approve token spending if needed
-        // - Get quote from DEX
-        // - Build swap transaction
-        // - Sign with self.private_key
-        // - Broadcast transaction
+        let tx = client
+            .send_swap(&self.private_key, token_in, &call, amount_in, swap.wait_for_receipt)
+            .await
+            .map_err(|e| CryptoError::SwapError(format!("Failed to submit swap: {}", e)))?;
+
+        // A real swap approves the router itself, so an estimate failing on allowance doesn't stop it
+        let estimated_fee = match estimate {
+            Ok(estimate) => format_token_amount(estimate.fee, 18)?,
+            Err(e) => {
+                tracing::info!(error = %e, "gas estimate unavailable before approval");
+                "0.0".to_string()
+            }
+        };
+
+        // The actual fee once mined, the estimate until then
+        let (status, fee) = match &tx.receipt {
+            None => (SwapStatus::Pending, estimated_fee),
+            Some(receipt) => {
+                let fee = match (receipt.gas_used, receipt.effective_gas_price) {
+                    (Some(gas_used), Some(gas_price)) => format_units(gas_used * gas_price, 18)
+                        .map_err(|e| CryptoError::SerializationError(e.to_string()))?,
+                    _ => estimated_fee,
+                };
+                (SwapStatus::from_receipt(receipt), fee)
+            }
+        };
 
-        // Placeholder response
         Ok(SwapResult {
-            tx_hash: format!("0x{}", hex::encode(rand::thread_rng().gen::<[u8; 32]>())),
-            amount_out: "0.0".to_string(),
-            fee: "0.0".to_string(),
-            status: SwapStatus::Pending,
+            tx_hash: format!("{:?}", tx.tx_hash),
+            amount_out: format_token_amount(quote.amount_out, swap.to_token.decimals)?,
+            min_amount_out: format_token_amount(amount_out_min, swap.to_token.decimals)?,
+            fee,
+            status,
+            route,
+            estimated_seconds: None,
         })
     }
 
-    fn execute_multi_chain_swap(
+    async fn execute_multi_chain_swap(
         &self,
-        _config: &CryptoConfig,
+        config: &CryptoConfig,
         swap: MultiChainSwap,
     ) -> Result<SwapResult, CryptoError> {
         // In production, this would:
@@ -221,9 +313,11 @@ impl Wallet {
             ));
         }
 
+        validate_slippage(&swap.slippage)?;
+        let bridge_quote = config.bridge_quoter.quote(&swap).await?;
+
         // TODO: Implement actual cross-chain swap logic
         // - Select appropriate bridge
-        // - Estimate bridge fees
         // - Build bridge transaction
         // - Sign and submit
         // - Monitor both chains for completion
@@ -232,12 +326,67 @@ impl Wallet {
         Ok(SwapResult {
             tx_hash: format!("0x{}", hex::encode(rand::thread_rng().gen::<[u8; 32]>())),
             amount_out: "0.0".to_string(),
-            fee: "0.0".to_string(),
+            min_amount_out: "0.0".to_string(),
+            fee: bridge_quote.fee,
             status: SwapStatus::Pending,
+            route: Vec::new(),
+            estimated_seconds: Some(bridge_quote.estimated_seconds),
         })
     }
 }
 
+/// `tx_hash` of a `dry_run` swap, which never reaches the chain
+pub const DRY_RUN_TX_HASH: &str = "dryrun";
+
+/// Resolve a token's contract address, treating a missing address as the chain's native coin
+pub fn resolve_token_address(address: &Option<String>, wrapped_native: &str) -> Result<Address, CryptoError> {
+    address
+        .as_deref()
+        .unwrap_or(wrapped_native)
+        .parse()
+        .map_err(|_| CryptoError::InvalidAddress(format!("Invalid token address: {:?}", address)))
+}
+
+/// Parse a human-readable token amount into base units
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<U256, CryptoError> {
+    let amount: U256 = parse_units(amount, decimals as u32)
+        .map_err(|_| CryptoError::SwapError("Invalid amount".to_string()))?
+        .into();
+    if amount.is_zero() {
+        return Err(CryptoError::SwapError("Amount must be greater than zero".to_string()));
+    }
+    if amount > U256::from(u128::MAX) {
+        return Err(CryptoError::SwapError("Amount is too large".to_string()));
+    }
+    Ok(amount)
+}
+
+/// Format base units back into a human-readable token amount
+pub fn format_token_amount(amount: U256, decimals: u8) -> Result<String, CryptoError> {
+    format_units(amount, decimals as u32).map_err(|e| CryptoError::SerializationError(e.to_string()))
+}
+
+/// Smallest acceptable output for a quote at `slippage` percent, rounded down to whole basis points
+pub fn min_amount_out(quoted: U256, slippage: f64) -> U256 {
+    let slippage_bps = (slippage * 100.0).round() as u64;
+    quoted * U256::from(10_000 - slippage_bps) / U256::from(10_000)
+}
+
+/// Parse a slippage percentage and check it is within 0..=50%
+pub fn validate_slippage(slippage: &str) -> Result<f64, CryptoError> {
+    let slippage: f64 = slippage
+        .parse()
+        .map_err(|_| CryptoError::SwapError("Invalid slippage value".to_string()))?;
+
+    if !(0.0..=50.0).contains(&slippage) {
+        return Err(CryptoError::SwapError(
+            "Slippage must be between 0 and 50%".to_string(),
+        ));
+    }
+
+    Ok(slippage)
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Balance {
@@ -254,6 +403,48 @@ pub struct Balance {
     pub usd_value: Option<String>,
 }
 
+/// Balances held by one address on one chain; `error` is set when the chain couldn't be read
+/// (including a timeout), in which case `balances` is empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainBalances {
+    pub chain: String,
+    pub balances: Vec<Balance>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletBalances {
+    pub address: String,
+    pub chains: Vec<ChainBalances>,
+}
+
+/// ERC20 token whose balance is reported alongside the native coin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceToken {
+    pub symbol: String,
+    pub address: String,
+    pub decimals: u8,
+}
+
+impl BalanceToken {
+    /// Parse `SYMBOL:address:decimals` entries separated by commas, skipping malformed ones
+    pub fn parse_list(raw: &str) -> Vec<BalanceToken> {
+        raw.split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.trim().split(':');
+                let symbol = parts.next()?.trim();
+                let address = parts.next()?.trim();
+                let decimals = parts.next()?.trim().parse().ok()?;
+                (!symbol.is_empty() && address.parse::<Address>().is_ok()).then(|| BalanceToken {
+                    symbol: symbol.to_string(),
+                    address: address.to_string(),
+                    decimals,
+                })
+            })
+            .collect()
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SwapType {
@@ -284,6 +475,14 @@ pub struct SingleChainSwap {
 
     /// Optional DEX/protocol to use (e.g., "uniswap", "pancakeswap")
     pub dex: Option<String>,
+
+    /// Wait for the transaction to be mined before returning
+    #[serde(default)]
+    pub wait_for_receipt: bool,
+
+    /// Validate, route and quote the swap but don't sign or broadcast it
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 #[allow(dead_code)]
@@ -333,11 +532,38 @@ pub struct SwapResult {
     /// Amount received
     pub amount_out: String,
 
+    /// Slippage-protected floor passed to the router; the swap reverts below it
+    pub min_amount_out: String,
+
     /// Gas/fee paid
     pub fee: String,
 
     /// Swap status
     pub status: SwapStatus,
+
+    /// Token addresses the swap is routed through
+    pub route: Vec<String>,
+
+    /// Expected time until a cross-chain swap completes; unset for single-chain swaps
+    pub estimated_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapQuote {
+    /// Chain the quote was computed on
+    pub chain: String,
+
+    /// Amount to swap
+    pub amount_in: String,
+
+    /// Expected amount received
+    pub amount_out: String,
+
+    /// Price impact as a percentage
+    pub price_impact: f64,
+
+    /// Token addresses the swap is routed through
+    pub route: Vec<String>,
 }
 
 #[allow(dead_code)]
@@ -348,6 +574,29 @@ pub enum SwapStatus {
     Failed(String),
 }
 
+impl SwapStatus {
+    /// Map a mined transaction's receipt to a swap status
+    pub fn from_receipt(receipt: &TransactionReceipt) -> Self {
+        if receipt.status == Some(U64::from(1)) {
+            SwapStatus::Completed
+        } else {
+            SwapStatus::Failed("Transaction reverted".to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionStatus {
+    /// Transaction hash
+    pub tx_hash: String,
+
+    /// Chain the transaction was submitted to
+    pub chain: String,
+
+    /// Current swap status
+    pub status: SwapStatus,
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub struct CryptoConfig {
@@ -359,6 +608,82 @@ pub struct CryptoConfig {
 
     /// Default slippage tolerance
     pub default_slippage: String,
+
+    /// V2 DEX factory addresses per chain
+    pub dex_factories: std::collections::HashMap<String, String>,
+
+    /// V2 DEX router addresses per chain
+    pub dex_routers: std::collections::HashMap<String, String>,
+
+    /// Wrapped native token addresses per chain (used as routing hop)
+    pub wrapped_native_tokens: std::collections::HashMap<String, String>,
+
+    /// Native coin symbol per chain, for balance reporting
+    pub native_symbols: std::collections::HashMap<String, String>,
+
+    /// ERC20 tokens whose balances are reported per chain
+    pub balance_tokens: std::collections::HashMap<String, Vec<BalanceToken>>,
+
+    /// How long one chain may take to answer a balance lookup before it's reported as timed out
+    pub balance_timeout: std::time::Duration,
+
+    /// Prices the bridge leg of cross-chain swaps
+    pub bridge_quoter: Arc<dyn BridgeQuoter>,
+}
+
+/// DEX endpoints for a single configured chain
+pub struct ChainDex<'a> {
+    pub rpc_url: &'a str,
+    pub factory: &'a str,
+    pub router: &'a str,
+    pub wrapped_native: &'a str,
+}
+
+impl CryptoConfig {
+    /// Build the config from env, defaulting to BSC mainnet
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        let bsc_rpc_url = std::env::var("BSC_RPC_URL")
+            .unwrap_or_else(|_| "https://bsc-dataseed.binance.org/".to_string());
+
+        config.rpc_endpoints.insert("bsc".to_string(), bsc_rpc_url);
+        config.dex_factories.insert(
+            "bsc".to_string(),
+            "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73".to_string(),
+        );
+        config.dex_routers.insert(
+            "bsc".to_string(),
+            "0x10ED43C718714eb63d5aA57B78B54704E256024E".to_string(),
+        );
+        config.wrapped_native_tokens.insert(
+            "bsc".to_string(),
+            "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".to_string(),
+        );
+        config.native_symbols.insert("bsc".to_string(), "BNB".to_string());
+        let bsc_tokens = std::env::var("BSC_BALANCE_TOKENS").unwrap_or_else(|_| {
+            "USDT:0x55d398326f99059fF775485246999027B3197955:18,BUSD:0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56:18"
+                .to_string()
+        });
+        config.balance_tokens.insert("bsc".to_string(), BalanceToken::parse_list(&bsc_tokens));
+
+        if let Some(seconds) = std::env::var("BALANCE_TIMEOUT_SECONDS").ok().and_then(|v| v.parse::<u64>().ok()) {
+            config.balance_timeout = std::time::Duration::from_secs(seconds.max(1));
+        }
+
+        config
+    }
+
+    /// Look up the DEX endpoints for a chain, failing if any are missing
+    pub fn chain_dex(&self, chain: &str) -> Result<ChainDex<'_>, CryptoError> {
+        let unsupported = || CryptoError::SwapError(format!("Unsupported chain: {}", chain));
+        Ok(ChainDex {
+            rpc_url: self.rpc_endpoints.get(chain).ok_or_else(unsupported)?,
+            factory: self.dex_factories.get(chain).ok_or_else(unsupported)?,
+            router: self.dex_routers.get(chain).ok_or_else(unsupported)?,
+            wrapped_native: self.wrapped_native_tokens.get(chain).ok_or_else(unsupported)?,
+        })
+    }
 }
 
 impl Default for CryptoConfig {
@@ -367,6 +692,39 @@ impl Default for CryptoConfig {
             rpc_endpoints: std::collections::HashMap::new(),
             api_keys: std::collections::HashMap::new(),
             default_slippage: "0.5".to_string(),
+            dex_factories: std::collections::HashMap::new(),
+            dex_routers: std::collections::HashMap::new(),
+            wrapped_native_tokens: std::collections::HashMap::new(),
+            native_symbols: std::collections::HashMap::new(),
+            balance_tokens: std::collections::HashMap::new(),
+            balance_timeout: std::time::Duration::from_secs(5),
+            bridge_quoter: Arc::new(StaticBridgeQuoter::from_env()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(status: u64) -> TransactionReceipt {
+        TransactionReceipt { status: Some(U64::from(status)), ..Default::default() }
+    }
+
+    #[test]
+    fn receipt_status_maps_to_swap_status() {
+        assert!(matches!(SwapStatus::from_receipt(&receipt(1)), SwapStatus::Completed));
+        assert!(matches!(SwapStatus::from_receipt(&receipt(0)), SwapStatus::Failed(_)));
+    }
+
+    #[test]
+    fn min_amount_out_applies_the_slippage_floor() {
+        assert_eq!(min_amount_out(U256::from(100), 1.0), U256::from(99));
+        assert_eq!(min_amount_out(U256::from(100), 0.0), U256::from(100));
+        // Half a percent of 1 token (18 decimals) is 0.005 tokens
+        let one = U256::exp10(18);
+        assert_eq!(min_amount_out(one, 0.5), one - U256::exp10(15) * 5);
+        // Rounded down, never above the slippage the client accepted
+        assert_eq!(min_amount_out(U256::from(7), 1.0), U256::from(6));
+    }
+}
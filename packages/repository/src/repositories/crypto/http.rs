@@ -0,0 +1,82 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}, Json};
+
+use super::data::CryptoError;
+
+impl CryptoError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            CryptoError::InvalidAddress(_) | CryptoError::SwapError(_) => StatusCode::BAD_REQUEST,
+            CryptoError::TransactionNotFound(_) => StatusCode::NOT_FOUND,
+            CryptoError::NetworkError(_) | CryptoError::BalanceError(_) => StatusCode::BAD_GATEWAY,
+            CryptoError::WalletCreationError(_) | CryptoError::SerializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Same `{ "status": false, "code", "message" }` envelope the app's `ErrorResponse` uses.
+/// Client errors carry the detail; server-side ones are logged and answered generically
+impl IntoResponse for CryptoError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let message = if status.is_client_error() {
+            self.to_string()
+        } else {
+            tracing::error!(error = %self, "crypto error");
+            match self {
+                CryptoError::NetworkError(_) | CryptoError::BalanceError(_) => "blockchain node unavailable".to_string(),
+                _ => "unable to process request".to_string(),
+            }
+        };
+
+        let body = serde_json::json!({
+            "status": false,
+            "code": self.code(),
+            "message": message,
+        });
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn respond(err: CryptoError) -> (StatusCode, serde_json::Value) {
+        let response = err.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn every_variant_has_its_status_and_code() {
+        let cases = [
+            (CryptoError::WalletCreationError("x".into()), StatusCode::INTERNAL_SERVER_ERROR, "WALLET_CREATION_FAILED"),
+            (CryptoError::BalanceError("x".into()), StatusCode::BAD_GATEWAY, "BALANCE_UNAVAILABLE"),
+            (CryptoError::SwapError("x".into()), StatusCode::BAD_REQUEST, "SWAP_ERROR"),
+            (CryptoError::InvalidAddress("x".into()), StatusCode::BAD_REQUEST, "INVALID_ADDRESS"),
+            (CryptoError::NetworkError("x".into()), StatusCode::BAD_GATEWAY, "NETWORK_ERROR"),
+            (CryptoError::SerializationError("x".into()), StatusCode::INTERNAL_SERVER_ERROR, "SERIALIZATION_ERROR"),
+            (CryptoError::TransactionNotFound("x".into()), StatusCode::NOT_FOUND, "TRANSACTION_NOT_FOUND"),
+        ];
+
+        for (err, status, code) in cases {
+            let (actual, body) = respond(err).await;
+            assert_eq!(actual, status, "{}", code);
+            assert_eq!(body["status"], false);
+            assert_eq!(body["code"], code);
+        }
+    }
+
+    #[tokio::test]
+    async fn server_side_details_are_not_echoed() {
+        let (_, body) = respond(CryptoError::NetworkError("http://10.0.0.5:8545 refused".into())).await;
+        assert_eq!(body["message"], "blockchain node unavailable");
+
+        let (_, body) = respond(CryptoError::SerializationError("bad abi".into())).await;
+        assert_eq!(body["message"], "unable to process request");
+
+        let (_, body) = respond(CryptoError::SwapError("Invalid slippage value".into())).await;
+        assert_eq!(body["message"], "Swap error: Invalid slippage value");
+    }
+}
@@ -1,13 +1,23 @@
 use bip39::Mnemonic;
-use data::{CryptoConfig, CryptoError, Wallet};
+use data::{Balance, ChainBalances, CryptoConfig, CryptoError, SwapQuote, SwapStatus, SwapType, TransactionStatus, Wallet, WalletBalances};
+use ethers::types::{Address, H256};
+use futures::future::join_all;
+use ethers::signers::coins_bip39::English;
+use ethers::signers::{MnemonicBuilder, Signer};
+use ethers::utils::to_checksum;
 use hex;
 use rand::Rng;
-use sha2::{Digest, Sha256};
+use std::sync::Arc;
 
 pub mod blockchain_client;
+pub mod bridge;
 pub mod data;
+pub mod router;
+#[cfg(feature = "axum")]
+pub mod http;
 
-pub use blockchain_client::BlockchainClient;
+pub use blockchain_client::{BlockchainClient, TransactionLookup};
+use router::{PancakeSwapV2Router, SwapRouter};
 
 #[allow(dead_code)]
 pub trait CryptoRepositoryTrait {
@@ -19,18 +29,31 @@ pub trait CryptoRepositoryTrait {
 #[derive(Clone)]
 pub struct CryptoRepository {
     config: CryptoConfig,
+    /// Prices single-chain quotes; `PancakeSwapV2Router` unless replaced with `with_router`
+    router: Arc<dyn SwapRouter>,
+}
+
+impl Default for CryptoRepository {
+    fn default() -> Self {
+        Self::new(CryptoConfig::default())
+    }
 }
 
 #[allow(dead_code)]
 impl CryptoRepository {
     pub fn new(config: CryptoConfig) -> Self {
-        Self { config }
+        Self { config, router: Arc::new(PancakeSwapV2Router) }
     }
 
-    pub fn default() -> Self {
-        Self {
-            config: CryptoConfig::default(),
-        }
+    /// Quote through `router` instead of the default V2 router
+    pub fn with_router(mut self, router: Arc<dyn SwapRouter>) -> Self {
+        self.router = router;
+        self
+    }
+
+    /// The router quotes are priced by; pass it to `Wallet::swap_tokens` to execute them the same way
+    pub fn router(&self) -> &dyn SwapRouter {
+        self.router.as_ref()
     }
 
     /// Get a reference to the repository's configuration
@@ -38,25 +61,16 @@ impl CryptoRepository {
         &self.config
     }
 
-    /// Generate a random private key (32 bytes)
-    fn generate_private_key(&self) -> String {
-        let mut rng = rand::thread_rng();
-        let private_key: [u8; 32] = rng.gen();
-        hex::encode(private_key)
-    }
-
-    /// Derive address from private key (simplified - in production use proper key derivation)
-    fn derive_address(&self, private_key: &str) -> Result<String, CryptoError> {
-        // This is a simplified version. In production, use proper elliptic curve cryptography
-        // For Ethereum: use secp256k1, keccak256
-        // For Bitcoin: use secp256k1, ripemd160, base58
+    /// Derive the first account of `seed_phrase` (BIP-44 path m/44'/60'/0'/0/0), so the phrase the
+    /// user keeps restores the same key and address in any standard wallet
+    fn derive_account(&self, seed_phrase: &str) -> Result<(String, String), CryptoError> {
+        let signer = MnemonicBuilder::<English>::default()
+            .phrase(seed_phrase)
+            .build()
+            .map_err(|e| CryptoError::WalletCreationError(format!("Failed to derive key: {}", e)))?;
 
-        let mut hasher = Sha256::new();
-        hasher.update(private_key.as_bytes());
-        let result = hasher.finalize();
-
-        // Simplified address format (0x + first 40 chars of hash)
-        Ok(format!("0x{}", hex::encode(&result[..20])))
+        let private_key = hex::encode(signer.signer().to_bytes());
+        Ok((private_key, to_checksum(&signer.address(), None)))
     }
 
     /// Generate mnemonic seed phrase
@@ -82,6 +96,138 @@ impl CryptoRepository {
             CryptoError::NetworkError(format!("Failed to create blockchain client: {}", e))
         })
     }
+
+    /// Quote a swap without signing or broadcasting anything
+    pub async fn quote_swap(&self, swap: SwapType) -> Result<SwapQuote, CryptoError> {
+        let swap = match swap {
+            SwapType::SingleChain(single_swap) => single_swap,
+            SwapType::MultiChain(_) => {
+                return Err(CryptoError::SwapError(
+                    "Cross-chain quotes are not supported".to_string(),
+                ))
+            }
+        };
+
+        data::validate_slippage(&swap.slippage)?;
+
+        let chain = self.config.chain_dex(&swap.chain)?;
+        let token_in = data::resolve_token_address(&swap.from_token.address, chain.wrapped_native)?;
+        let token_out = data::resolve_token_address(&swap.to_token.address, chain.wrapped_native)?;
+        let amount_in = data::parse_token_amount(&swap.amount, swap.from_token.decimals)?;
+
+        let client = self.generate_blockchain_client(chain.rpc_url).await?;
+
+        let quote = self.router.quote(&client, &chain, token_in, token_out, amount_in).await?;
+
+        let amount_out = data::format_token_amount(quote.amount_out, swap.to_token.decimals)?;
+
+        Ok(SwapQuote {
+            chain: swap.chain,
+            amount_in: swap.amount,
+            amount_out,
+            price_impact: quote.price_impact,
+            route: quote.path.iter().map(|address| to_checksum(address, None)).collect(),
+        })
+    }
+
+    /// Native and configured-token balances of `address` on every configured chain. Chains are read
+    /// concurrently and each gets `balance_timeout`, so one slow RPC only fails its own entry
+    pub async fn get_wallet_balances(&self, address: &str) -> Result<WalletBalances, CryptoError> {
+        let owner: Address = address
+            .parse()
+            .map_err(|_| CryptoError::InvalidAddress(format!("Invalid wallet address: {}", address)))?;
+
+        let lookups = self.config.rpc_endpoints.iter().map(|(chain, rpc_url)| async move {
+            match tokio::time::timeout(self.config.balance_timeout, self.chain_balances(chain, rpc_url, owner)).await {
+                Ok(Ok(balances)) => ChainBalances { chain: chain.clone(), balances, error: None },
+                Ok(Err(e)) => {
+                    tracing::warn!(chain = %chain, error = %e, "balance lookup failed");
+                    ChainBalances { chain: chain.clone(), balances: Vec::new(), error: Some(e.code().to_string()) }
+                }
+                Err(_) => {
+                    tracing::warn!(chain = %chain, "balance lookup timed out");
+                    ChainBalances { chain: chain.clone(), balances: Vec::new(), error: Some("TIMEOUT".to_string()) }
+                }
+            }
+        });
+        let mut chains = join_all(lookups).await;
+        chains.sort_by(|a, b| a.chain.cmp(&b.chain));
+
+        Ok(WalletBalances { address: to_checksum(&owner, None), chains })
+    }
+
+    async fn chain_balances(&self, chain: &str, rpc_url: &str, owner: Address) -> Result<Vec<Balance>, CryptoError> {
+        let client = self.generate_blockchain_client(rpc_url).await?;
+        let balance_error = |e: Box<dyn std::error::Error + Send + Sync>| CryptoError::BalanceError(e.to_string());
+
+        let native_symbol = self.config.native_symbols.get(chain).cloned().unwrap_or_else(|| chain.to_uppercase());
+        let native = client.get_native_balance(owner).await.map_err(balance_error)?;
+        let mut balances = vec![Balance {
+            symbol: native_symbol,
+            amount: data::format_token_amount(native, 18)?,
+            chain: chain.to_string(),
+            usd_value: None,
+        }];
+
+        let tokens = self.config.balance_tokens.get(chain).map(Vec::as_slice).unwrap_or_default();
+        let token_lookups = tokens.iter().map(|token| {
+            let client = &client;
+            async move {
+                let address: Address = token
+                    .address
+                    .parse()
+                    .map_err(|_| CryptoError::InvalidAddress(format!("Invalid token address: {}", token.address)))?;
+                let amount = client.get_token_balance(address, owner).await.map_err(balance_error)?;
+                Ok::<_, CryptoError>(Balance {
+                    symbol: token.symbol.clone(),
+                    amount: data::format_token_amount(amount, token.decimals)?,
+                    chain: chain.to_string(),
+                    usd_value: None,
+                })
+            }
+        });
+        for balance in join_all(token_lookups).await {
+            balances.push(balance?);
+        }
+
+        Ok(balances)
+    }
+
+    /// Check whether a submitted swap transaction has been mined and whether it succeeded
+    pub async fn get_swap_status(&self, chain: &str, tx_hash: &str) -> Result<TransactionStatus, CryptoError> {
+        // A malformed hash is the caller's mistake, so reject it before any RPC work
+        let hash: H256 = tx_hash
+            .parse()
+            .map_err(|_| CryptoError::SwapError(format!("Invalid transaction hash: {}", tx_hash)))?;
+
+        let rpc_url = self
+            .config
+            .rpc_endpoints
+            .get(chain)
+            .ok_or_else(|| CryptoError::SwapError(format!("Unsupported chain: {}", chain)))?;
+
+        let client = self.generate_blockchain_client(rpc_url).await?;
+        let lookup = client.get_transaction_receipt(hash).await.map_err(|e| {
+            CryptoError::NetworkError(format!("Failed to fetch transaction receipt: {}", e))
+        })?;
+
+        let status = match lookup {
+            TransactionLookup::Mined(receipt) => SwapStatus::from_receipt(&receipt),
+            TransactionLookup::Pending => SwapStatus::Pending,
+            TransactionLookup::NotFound => {
+                return Err(CryptoError::TransactionNotFound(format!(
+                    "Transaction {} not found on {}",
+                    tx_hash, chain
+                )))
+            }
+        };
+
+        Ok(TransactionStatus {
+            tx_hash: tx_hash.to_string(),
+            chain: chain.to_string(),
+            status,
+        })
+    }
 }
 
 impl CryptoRepositoryTrait for CryptoRepository {
@@ -89,12 +235,89 @@ impl CryptoRepositoryTrait for CryptoRepository {
         // Generate seed phrase
         let seed_phrase = self.generate_seed_phrase()?;
 
-        // Generate private key
-        let private_key = self.generate_private_key();
-
-        // Derive address from private key
-        let address = self.derive_address(&private_key)?;
+        // Derive the key and address from the phrase so the phrase can recover the wallet
+        let (private_key, address) = self.derive_account(&seed_phrase)?;
 
         Ok(Wallet::new(address, private_key, seed_phrase))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::LocalWallet;
+
+    #[test]
+    fn seed_phrase_restores_the_same_wallet() {
+        let wallet = CryptoRepository::default().create_wallet().unwrap();
+        assert_eq!(wallet.seed_phrase.split_whitespace().count(), 24);
+
+        let restored = MnemonicBuilder::<English>::default()
+            .phrase(wallet.seed_phrase.as_str())
+            .derivation_path("m/44'/60'/0'/0/0")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(to_checksum(&restored.address(), None), wallet.address);
+
+        let from_key: LocalWallet = wallet.private_key.parse().unwrap();
+        assert_eq!(from_key.address(), restored.address());
+    }
+
+    fn token(symbol: &str) -> data::TokenInfo {
+        data::TokenInfo { symbol: symbol.to_string(), address: None, decimals: 18 }
+    }
+
+    fn cross_chain() -> data::MultiChainSwap {
+        data::MultiChainSwap {
+            from_chain: "bsc".to_string(),
+            to_chain: "ethereum".to_string(),
+            from_token: token("BNB"),
+            to_token: token("ETH"),
+            amount: "1".to_string(),
+            slippage: "0.5".to_string(),
+            bridge: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn cross_chain_swaps_report_the_bridge_quoters_fee() {
+        let quoter = bridge::StaticBridgeQuoter { fee_bps: 30, flat_fee: "0.01".to_string(), estimated_seconds: 90 };
+        let expected = bridge::BridgeQuoter::quote(&quoter, &cross_chain()).await.unwrap();
+        let config = data::CryptoConfig { bridge_quoter: std::sync::Arc::new(quoter), ..Default::default() };
+        let wallet = CryptoRepository::new(config.clone()).create_wallet().unwrap();
+
+        let result = wallet
+            .swap_tokens(&config, &PancakeSwapV2Router, SwapType::MultiChain(cross_chain()))
+            .await
+            .unwrap();
+        assert_eq!(result.fee, expected.fee);
+        assert_eq!(result.fee.parse::<f64>().unwrap(), 0.013);
+        assert_eq!(result.estimated_seconds, Some(90));
+        assert!(matches!(result.status, SwapStatus::Pending));
+
+        // There is nothing to quote on a single DEX for a cross-chain route
+        let quote = CryptoRepository::new(config).quote_swap(SwapType::MultiChain(cross_chain())).await;
+        assert!(matches!(quote, Err(CryptoError::SwapError(_))), "{:?}", quote);
+    }
+
+    #[tokio::test]
+    async fn cross_chain_swaps_still_validate_their_input() {
+        let repo = CryptoRepository::default();
+        let wallet = repo.create_wallet().unwrap();
+
+        let mut same_chain = cross_chain();
+        same_chain.to_chain = same_chain.from_chain.clone();
+        let result = wallet
+            .swap_tokens(repo.config(), &PancakeSwapV2Router, SwapType::MultiChain(same_chain))
+            .await;
+        assert!(matches!(result, Err(CryptoError::SwapError(_))), "{:?}", result);
+
+        let mut bad_slippage = cross_chain();
+        bad_slippage.slippage = "75".to_string();
+        let result = wallet
+            .swap_tokens(repo.config(), &PancakeSwapV2Router, SwapType::MultiChain(bad_slippage))
+            .await;
+        assert!(matches!(result, Err(CryptoError::SwapError(_))), "{:?}", result);
+    }
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use ethers::abi::AbiEncode;
+use ethers::types::{Address, Bytes, U256};
+
+use super::blockchain_client::{BlockchainClient, RouteQuote, SwapExactTokensForTokensCall, SWAP_DEADLINE_SECONDS};
+use super::data::{resolve_token_address, ChainDex, CryptoError};
+
+/// Unsigned swap call: `data` sent to `to` with `value` wei. `to` is also the spender the input
+/// token has to approve
+#[derive(Debug, Clone)]
+pub struct SwapCall {
+    pub to: Address,
+    pub data: Bytes,
+    pub value: U256,
+}
+
+/// A DEX or aggregator that can price and build single-chain swaps
+#[async_trait]
+pub trait SwapRouter: Send + Sync {
+    /// Best route and expected output for swapping `amount_in` of `token_in` into `token_out`
+    async fn quote(
+        &self,
+        client: &BlockchainClient,
+        chain: &ChainDex<'_>,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+    ) -> Result<RouteQuote, CryptoError>;
+
+    /// Unsigned call executing `quote`, paying `recipient` and reverting below `amount_out_min`
+    async fn build_tx(
+        &self,
+        chain: &ChainDex<'_>,
+        quote: &RouteQuote,
+        amount_in: U256,
+        amount_out_min: U256,
+        recipient: Address,
+    ) -> Result<SwapCall, CryptoError>;
+}
+
+/// The chain's configured V2 factory and router (PancakeSwap on BSC): the direct pair, or a hop
+/// through the wrapped native token when there is none
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PancakeSwapV2Router;
+
+#[async_trait]
+impl SwapRouter for PancakeSwapV2Router {
+    async fn quote(
+        &self,
+        client: &BlockchainClient,
+        chain: &ChainDex<'_>,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+    ) -> Result<RouteQuote, CryptoError> {
+        let wrapped_native = resolve_token_address(&None, chain.wrapped_native)?;
+        client
+            .quote_best_route(chain.factory, token_in, token_out, wrapped_native, amount_in)
+            .await
+            .map_err(|e| CryptoError::SwapError(format!("Unable to quote swap: {}", e)))
+    }
+
+    async fn build_tx(
+        &self,
+        chain: &ChainDex<'_>,
+        quote: &RouteQuote,
+        amount_in: U256,
+        amount_out_min: U256,
+        recipient: Address,
+    ) -> Result<SwapCall, CryptoError> {
+        if quote.path.len() < 2 {
+            return Err(CryptoError::SwapError("Swap path needs at least two tokens".to_string()));
+        }
+        let router: Address = chain
+            .router
+            .parse()
+            .map_err(|_| CryptoError::InvalidAddress(format!("Invalid router address: {}", chain.router)))?;
+
+        let call = SwapExactTokensForTokensCall {
+            amount_in,
+            amount_out_min,
+            path: quote.path.clone(),
+            to: recipient,
+            deadline: U256::from(chrono::Utc::now().timestamp() + SWAP_DEADLINE_SECONDS),
+        };
+        Ok(SwapCall { to: router, data: call.encode().into(), value: U256::zero() })
+    }
+}
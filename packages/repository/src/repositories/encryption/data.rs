@@ -11,6 +11,12 @@ pub enum EncryptionError {
 
     // #[error("jwt error: {0}")]
     JwtError(String),
+
+    // #[error("token not yet valid")]
+    TokenNotYetValid,
+
+    // #[error("invalid link: {0}")]
+    InvalidLink(String),
 }
 
 #[allow(dead_code)]
@@ -21,6 +27,106 @@ pub struct JwtConfig {
     pub expiry_seconds: i64,
 }
 
+impl JwtConfig {
+    /// Secret from `JWT_SECRET`, falling back to the built-in default
+    pub fn from_env() -> Self {
+        Self {
+            secret: secret_from_env("JWT_SECRET"),
+            expiry_seconds: 3600,
+        }
+    }
+}
+
+/// Every secret the service signs or encrypts with, and the fallback used when its env var is unset.
+/// The fallbacks are public knowledge, so they must never sign anything in production
+pub const DEFAULT_SECRETS: &[(&str, &str)] = &[
+    ("JWT_SECRET", "default_secret_key"),
+    ("USER_ACCESS_TOKEN", "default_user_access_token"),
+    ("USER_REFRESH_TOKEN", "default_user_refresh_token"),
+    ("ADMIN_SECRET_TOKEN", "default_admin_token"),
+    ("WEB_ACCESS_TOKEN", "default_web_token"),
+    ("APP_ACCESS_TOKEN", "default_app_token"),
+    ("APP_REFRESH_TOKEN", "default_app_refresh_token"),
+    ("SIGNED_LINK_SECRET", "default_signed_link_secret"),
+];
+
+/// Value of a secret env var, or its entry in `DEFAULT_SECRETS` when unset
+fn secret_from_env(var: &str) -> String {
+    std::env::var(var).unwrap_or_else(|_| {
+        DEFAULT_SECRETS
+            .iter()
+            .find(|(name, _)| *name == var)
+            .map(|(_, default)| default.to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Comma-separated retired secrets from `<var>_PREVIOUS`, empty when unset
+fn previous_secrets_from_env(var: &str) -> Vec<String> {
+    std::env::var(format!("{}_PREVIOUS", var))
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Lifetime in seconds from `var` (e.g. `USER_ACCESS_TOKEN_TTL`), or `default` when unset or not a
+/// positive number
+fn ttl_from_env(var: &str, default: i64) -> i64 {
+    match std::env::var(var) {
+        Ok(raw) => match raw.trim().parse::<i64>() {
+            Ok(seconds) if seconds > 0 => seconds,
+            _ => {
+                tracing::warn!(var, value = %raw, default, "invalid token ttl, using default");
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Env vars whose secret is missing, empty or still set to its built-in default
+pub fn insecure_secrets() -> Vec<&'static str> {
+    DEFAULT_SECRETS
+        .iter()
+        .filter(|(var, default)| {
+            let value = secret_from_env(var);
+            value.is_empty() || value == *default
+        })
+        .map(|(var, _)| *var)
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct InsecureSecretsError {
+    pub vars: Vec<&'static str>,
+}
+
+impl std::fmt::Display for InsecureSecretsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "default or empty secrets in use: {}", self.vars.join(", "))
+    }
+}
+
+impl std::error::Error for InsecureSecretsError {}
+
+/// Fail when `production` is set and any secret is still a default; outside production only warn
+pub fn validate_secrets(production: bool) -> Result<(), InsecureSecretsError> {
+    let vars = insecure_secrets();
+    if vars.is_empty() {
+        return Ok(());
+    }
+    if production {
+        return Err(InsecureSecretsError { vars });
+    }
+    tracing::warn!(vars = %vars.join(", "), "running with default secrets, set them before deploying");
+    Ok(())
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub struct ArgonConfig {
@@ -28,6 +134,17 @@ pub struct ArgonConfig {
     pub t_cost: u32,
     pub m_cost_kib: u32,
     pub p_cost: u32,
+    /// Application-wide secret mixed into every hash (argon2's secret input) on top of the per-hash
+    /// salt, so a leaked database alone can't be cracked offline. Read from `PASSWORD_PEPPER`.
+    /// Changing or removing it invalidates every stored hash
+    pub pepper: Option<String>,
+}
+
+impl ArgonConfig {
+    /// `PASSWORD_PEPPER` when set and non-empty
+    pub fn pepper_from_env() -> Option<String> {
+        std::env::var("PASSWORD_PEPPER").ok().filter(|p| !p.is_empty())
+    }
 }
 
 /// Sub payload can be raw JSON or a JSON string (from other services)
@@ -45,71 +162,207 @@ pub enum Sub {
 pub struct Claims {
     pub sub: Sub,
     pub exp: i64,
+    /// Service that minted the token
+    pub iss: String,
+    /// Token type the token was minted for (e.g. "user_access")
+    pub aud: String,
+    /// Unix timestamp before which the token must be rejected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+    /// Unique token id, set on refresh tokens so they can be tracked and revoked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// Additional top-level claims such as `purpose`, for scoped tokens
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Claims {
-    pub fn new<T: Serialize>(payload: &T, expiry_seconds: i64) -> Result<Self, serde_json::Error> {
+    pub fn new<T: Serialize>(payload: &T, token_type: &TokenParams) -> Result<Self, serde_json::Error> {
         let sub = Sub::Json(serde_json::to_value(payload)?);
-        let exp = chrono::Utc::now().timestamp() + expiry_seconds;
-        Ok(Claims { sub, exp })
+        Ok(Self::with_sub(sub, token_type))
     }
 
-    pub fn new_text<T: Serialize>(payload: &T, expiry_seconds: i64) -> Result<Self, serde_json::Error> {
+    pub fn new_text<T: Serialize>(payload: &T, token_type: &TokenParams) -> Result<Self, serde_json::Error> {
         let sub = Sub::Text(serde_json::to_string(payload)?);
-        let exp = chrono::Utc::now().timestamp() + expiry_seconds;
-        Ok(Claims { sub, exp })
+        Ok(Self::with_sub(sub, token_type))
+    }
+
+    fn with_sub(sub: Sub, token_type: &TokenParams) -> Self {
+        Claims {
+            sub,
+            exp: chrono::Utc::now().timestamp() + token_type.expiry_seconds,
+            iss: token_type.issuer.clone(),
+            aud: token_type.audience.clone(),
+            nbf: None,
+            jti: None,
+            extra: serde_json::Map::new(),
+        }
     }
 }
 
+/// Optional claims for `create_token_value`; the default is a plain token with the type's expiry
+#[derive(Clone, Debug, Default)]
+pub struct TokenOptions {
+    /// Extra top-level claims; registered ones (`sub`, `exp`, ...) are dropped
+    pub extra: serde_json::Map<String, serde_json::Value>,
+    /// Lifetime in seconds in place of the token type's `expiry_seconds`
+    pub expiry_override: Option<i64>,
+    /// Unix time before which the token is rejected; the lifetime counts from then
+    pub not_before: Option<i64>,
+    pub jti: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct TokenParams {
+    /// Signs new tokens and is tried first when verifying
     pub key: String,
+    /// Retired keys still accepted when verifying, so tokens outlive a rotation until they expire
+    pub previous_keys: Vec<String>,
     pub expiry_seconds: i64,
+    /// Expected `iss` claim, shared by every token this deployment mints
+    pub issuer: String,
+    /// Expected `aud` claim, unique per token type so one type can't stand in for another
+    pub audience: String,
 }
 
 #[allow(dead_code)]
 pub struct Token;
 
+/// Token types a bearer token can be introspected as, see `EncryptionRepositoryExt::decode_any_token`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    UserAccess,
+    UserRefresh,
+    AdminAccess,
+    WebAccess,
+    AppAccess,
+    AppRefresh,
+}
+
+impl TokenKind {
+    pub const ALL: [TokenKind; 6] = [
+        TokenKind::UserAccess,
+        TokenKind::UserRefresh,
+        TokenKind::AdminAccess,
+        TokenKind::WebAccess,
+        TokenKind::AppAccess,
+        TokenKind::AppRefresh,
+    ];
+
+    pub fn params(self) -> TokenParams {
+        match self {
+            TokenKind::UserAccess => Token::user_access_token(),
+            TokenKind::UserRefresh => Token::user_refresh_token(),
+            TokenKind::AdminAccess => Token::admin_access_token(),
+            TokenKind::WebAccess => Token::web_access_token(),
+            TokenKind::AppAccess => Token::app_access_token(),
+            TokenKind::AppRefresh => Token::app_refresh_token(),
+        }
+    }
+
+    pub fn is_refresh(self) -> bool {
+        matches!(self, TokenKind::UserRefresh | TokenKind::AppRefresh)
+    }
+}
+
+/// Every lifetime below is a default, overridable in seconds with `<TYPE>_TTL`
 impl Token {
+    /// Issuer stamped into every token, overridable with `JWT_ISSUER`
+    pub fn issuer() -> String {
+        std::env::var("JWT_ISSUER").unwrap_or_else(|_| "trade-server".to_string())
+    }
+
     pub fn user_access_token() -> TokenParams {
         TokenParams {
-            key: std::env::var("USER_ACCESS_TOKEN").unwrap_or_else(|_| "default_user_access_token".to_string()),
-            expiry_seconds: 72 * 3600, // 72 hours
+            key: secret_from_env("USER_ACCESS_TOKEN"),
+            previous_keys: previous_secrets_from_env("USER_ACCESS_TOKEN"),
+            expiry_seconds: ttl_from_env("USER_ACCESS_TOKEN_TTL", 72 * 3600), // 72 hours
+            issuer: Token::issuer(),
+            audience: "user_access".to_string(),
         }
     }
 
     pub fn user_refresh_token() -> TokenParams {
         TokenParams {
-            key: std::env::var("USER_REFRESH_TOKEN").unwrap_or_else(|_| "default_user_refresh_token".to_string()),
-            expiry_seconds: 100 * 24 * 3600, // 100 days
+            key: secret_from_env("USER_REFRESH_TOKEN"),
+            previous_keys: previous_secrets_from_env("USER_REFRESH_TOKEN"),
+            expiry_seconds: ttl_from_env("USER_REFRESH_TOKEN_TTL", 100 * 24 * 3600), // 100 days
+            issuer: Token::issuer(),
+            audience: "user_refresh".to_string(),
         }
     }
 
     pub fn admin_access_token() -> TokenParams {
         TokenParams {
-            key: std::env::var("ADMIN_SECRET_TOKEN").unwrap_or_else(|_| "default_admin_token".to_string()),
-            expiry_seconds: 72 * 3600, // 72 hours
+            key: secret_from_env("ADMIN_SECRET_TOKEN"),
+            previous_keys: previous_secrets_from_env("ADMIN_SECRET_TOKEN"),
+            expiry_seconds: ttl_from_env("ADMIN_ACCESS_TOKEN_TTL", 72 * 3600), // 72 hours
+            issuer: Token::issuer(),
+            audience: "admin_access".to_string(),
         }
     }
 
     pub fn web_access_token() -> TokenParams {
         TokenParams {
-            key: std::env::var("WEB_ACCESS_TOKEN").unwrap_or_else(|_| "default_web_token".to_string()),
-            expiry_seconds: 5 * 60, // 5 minutes
+            key: secret_from_env("WEB_ACCESS_TOKEN"),
+            previous_keys: previous_secrets_from_env("WEB_ACCESS_TOKEN"),
+            expiry_seconds: ttl_from_env("WEB_ACCESS_TOKEN_TTL", 5 * 60), // 5 minutes
+            issuer: Token::issuer(),
+            audience: "web_access".to_string(),
         }
     }
 
     pub fn app_access_token() -> TokenParams {
         TokenParams {
-            key: std::env::var("APP_ACCESS_TOKEN").unwrap_or_else(|_| "default_app_token".to_string()),
-            expiry_seconds: 6 * 3600, // 6 hours
+            key: secret_from_env("APP_ACCESS_TOKEN"),
+            previous_keys: previous_secrets_from_env("APP_ACCESS_TOKEN"),
+            expiry_seconds: ttl_from_env("APP_ACCESS_TOKEN_TTL", 6 * 3600), // 6 hours
+            issuer: Token::issuer(),
+            audience: "app_access".to_string(),
         }
     }
 
     pub fn app_refresh_token() -> TokenParams {
         TokenParams {
-            key: std::env::var("APP_REFRESH_TOKEN").unwrap_or_else(|_| "default_app_refresh_token".to_string()),
-            expiry_seconds: 72 * 3600, // 72 hours
+            key: secret_from_env("APP_REFRESH_TOKEN"),
+            previous_keys: previous_secrets_from_env("APP_REFRESH_TOKEN"),
+            expiry_seconds: ttl_from_env("APP_REFRESH_TOKEN_TTL", 72 * 3600), // 72 hours
+            issuer: Token::issuer(),
+            audience: "app_refresh".to_string(),
+        }
+    }
+
+    /// One-time links (email verification, password reset); the lifetime is chosen per link
+    pub fn signed_link() -> TokenParams {
+        TokenParams {
+            key: secret_from_env("SIGNED_LINK_SECRET"),
+            previous_keys: previous_secrets_from_env("SIGNED_LINK_SECRET"),
+            expiry_seconds: ttl_from_env("SIGNED_LINK_TTL", 24 * 3600), // 24 hours
+            issuer: Token::issuer(),
+            audience: "signed_link".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The app token variables aren't read by any other test, so setting them can't race one
+    #[test]
+    fn ttl_env_overrides_the_default_and_bad_values_fall_back() {
+        std::env::set_var("APP_ACCESS_TOKEN_TTL", "900");
+        assert_eq!(Token::app_access_token().expiry_seconds, 900);
+
+        for invalid in ["soon", "-5", "0", ""] {
+            std::env::set_var("APP_REFRESH_TOKEN_TTL", invalid);
+            assert_eq!(Token::app_refresh_token().expiry_seconds, 72 * 3600, "{:?}", invalid);
         }
+
+        std::env::remove_var("APP_ACCESS_TOKEN_TTL");
+        std::env::remove_var("APP_REFRESH_TOKEN_TTL");
+        assert_eq!(Token::app_access_token().expiry_seconds, 6 * 3600);
     }
-}
\ No newline at end of file
+}
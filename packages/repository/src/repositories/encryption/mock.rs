@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use uuid::Uuid;
+
+use super::data::{EncryptionError, TokenOptions, TokenParams};
+use super::EncryptionRepositoryTrait;
+
+/// Stand-in for `EncryptionRepository` in tests: plain-text "hashing", no real JWTs, and a count of
+/// the tokens created. Never use it outside tests
+#[derive(Debug, Default)]
+pub struct MockEncryption {
+  tokens_created: AtomicUsize,
+}
+
+impl MockEncryption {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// How many times `create_token_value` (and so any `create_token*` helper) was called
+  pub fn tokens_created(&self) -> usize {
+    self.tokens_created.load(Ordering::SeqCst)
+  }
+}
+
+impl EncryptionRepositoryTrait for MockEncryption {
+  fn hash_password(&self, plain: &str) -> Result<String, EncryptionError> {
+    Ok(format!("mock-hash:{}", plain))
+  }
+
+  fn verify_password(&self, hash: &str, plain: &str) -> Result<bool, EncryptionError> {
+    Ok(hash == format!("mock-hash:{}", plain))
+  }
+
+  fn encrypt_data(&self, data: &str) -> Result<String, EncryptionError> {
+    Ok(data.to_string())
+  }
+
+  fn decrypt_data(&self, encrypted_data: &str) -> Result<String, EncryptionError> {
+    Ok(encrypted_data.to_string())
+  }
+
+  fn create_token_value(&self, payload: serde_json::Value, token_type: TokenParams, _options: TokenOptions) -> Result<String, EncryptionError> {
+    let n = self.tokens_created.fetch_add(1, Ordering::SeqCst) + 1;
+    Ok(format!("mock-token-{}:{}:{}", n, token_type.audience, payload))
+  }
+
+  fn decode_token(&self, _token_string: &str, _token_type: TokenParams) -> Result<serde_json::Value, EncryptionError> {
+    Err(EncryptionError::JwtError("mock tokens can't be decoded".to_string()))
+  }
+
+  fn create_code(&self, length: usize) -> String {
+    "0".repeat(length)
+  }
+
+  fn create_signed_link(&self, user_id: Uuid, purpose: &str, _ttl_seconds: i64) -> Result<String, EncryptionError> {
+    Ok(format!("{}:{}", purpose, user_id))
+  }
+
+  fn verify_signed_link(&self, token: &str, purpose: &str) -> Result<Uuid, EncryptionError> {
+    token
+      .strip_prefix(purpose)
+      .and_then(|rest| rest.strip_prefix(':'))
+      .and_then(|id| Uuid::parse_str(id).ok())
+      .ok_or_else(|| EncryptionError::InvalidLink("purpose mismatch".to_string()))
+  }
+}
@@ -1,32 +1,92 @@
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::{Algorithm as ArgonAlgorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
 use argon2::password_hash::{rand_core::OsRng, SaltString};
 use argon2::password_hash::rand_core::RngCore;
-use data::{ArgonConfig, JwtConfig, EncryptionError, TokenParams};
+use data::{ArgonConfig, JwtConfig, EncryptionError, Token, TokenKind, TokenOptions, TokenParams};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation, Algorithm};
+use jsonwebtoken::errors::ErrorKind;
 use base64::engine::general_purpose::{URL_SAFE_NO_PAD};
 use base64::Engine;
 use aes_gcm::{Aes256Gcm, aead::{Aead, KeyInit}, Nonce};
 use sha2::{Sha256, Digest};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
 
 pub mod data;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 
+/// Compare secrets without short-circuiting on the first differing byte
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+  a.as_bytes().ct_eq(b.as_bytes()).into()
+}
+
+/// Object safe, so services can hold an `Arc<dyn EncryptionRepositoryTrait>`; the typed token
+/// helpers live on `EncryptionRepositoryExt`
 #[allow(dead_code)]
-pub trait EncryptionRepositoryTrait {
+pub trait EncryptionRepositoryTrait: Send + Sync {
   fn hash_password(&self, plain: &str) -> Result<String, EncryptionError>;
   fn verify_password(&self, hash: &str, plain: &str) -> Result<bool, EncryptionError>;
 
   fn encrypt_data(&self, data: &str) -> Result<String, EncryptionError>;
   fn decrypt_data(&self, encrypted_data: &str) -> Result<String, EncryptionError>;
-  
-  fn create_token<T: serde::Serialize>(&self, payload: T, token_type: TokenParams) -> Result<String, EncryptionError>;
+
+  /// Sign a token whose `sub` is `payload` encoded as a JSON string
+  fn create_token_value(&self, payload: serde_json::Value, token_type: TokenParams, options: TokenOptions) -> Result<String, EncryptionError>;
   fn decode_token(&self, token_string: &str, token_type: TokenParams) -> Result<serde_json::Value, EncryptionError>;
   fn create_code(&self, length: usize) -> String;
+
+  fn create_signed_link(&self, user_id: Uuid, purpose: &str, ttl_seconds: i64) -> Result<String, EncryptionError>;
+  fn verify_signed_link(&self, token: &str, purpose: &str) -> Result<Uuid, EncryptionError>;
+}
+
+/// Typed-payload token helpers, available on every `EncryptionRepositoryTrait` including `dyn` ones
+pub trait EncryptionRepositoryExt: EncryptionRepositoryTrait {
+  fn create_token<T: serde::Serialize>(&self, payload: T, token_type: TokenParams) -> Result<String, EncryptionError> {
+    self.create_token_value(payload_value(payload)?, token_type, TokenOptions::default())
+  }
+
+  fn create_token_with<T: serde::Serialize>(&self, payload: T, token_type: TokenParams, extra: serde_json::Map<String, serde_json::Value>, expiry_override: Option<i64>) -> Result<String, EncryptionError> {
+    let options = TokenOptions { extra, expiry_override, ..TokenOptions::default() };
+    self.create_token_value(payload_value(payload)?, token_type, options)
+  }
+
+  fn create_token_with_nbf<T: serde::Serialize>(&self, payload: T, token_type: TokenParams, not_before: i64) -> Result<String, EncryptionError> {
+    let options = TokenOptions { not_before: Some(not_before), ..TokenOptions::default() };
+    self.create_token_value(payload_value(payload)?, token_type, options)
+  }
+
+  fn create_token_with_jti<T: serde::Serialize>(&self, payload: T, token_type: TokenParams, jti: &str) -> Result<String, EncryptionError> {
+    let options = TokenOptions { jti: Some(jti.to_string()), ..TokenOptions::default() };
+    self.create_token_value(payload_value(payload)?, token_type, options)
+  }
+
+  /// Decode a token of any `TokenKind`, reporting which one it is. Audiences are unique per kind,
+  /// so at most one can match; signed links are never accepted here
+  fn decode_any_token(&self, token: &str) -> Result<(TokenKind, serde_json::Value), EncryptionError> {
+    let mut not_yet_valid = false;
+    for kind in TokenKind::ALL {
+      match self.decode_token(token, kind.params()) {
+        Ok(claims) => return Ok((kind, claims)),
+        Err(EncryptionError::TokenNotYetValid) => not_yet_valid = true,
+        Err(_) => {}
+      }
+    }
+    if not_yet_valid {
+      return Err(EncryptionError::TokenNotYetValid);
+    }
+    Err(EncryptionError::JwtError("token does not match any known type".to_string()))
+  }
+}
+
+impl<R: EncryptionRepositoryTrait + ?Sized> EncryptionRepositoryExt for R {}
+
+fn payload_value<T: serde::Serialize>(payload: T) -> Result<serde_json::Value, EncryptionError> {
+  serde_json::to_value(payload).map_err(|e| EncryptionError::JwtError(e.to_string()))
 }
 
 #[allow(dead_code)]
 #[derive(Clone)]
 pub struct EncryptionRepository {
-  argon: Argon2<'static>,
   argon_cfg: ArgonConfig,
   jwt_cfg: JwtConfig,
   encoding_key: EncodingKey,
@@ -37,12 +97,10 @@ pub struct EncryptionRepository {
 #[allow(dead_code)]
 impl EncryptionRepository {
   pub fn new(argon_cfg: ArgonConfig, jwt_cfg: JwtConfig) -> Self {
-    let argon = Argon2::default();
     let encoding_key = EncodingKey::from_secret(jwt_cfg.secret.as_bytes());
     let decoding_key = DecodingKey::from_secret(jwt_cfg.secret.as_bytes());
     
     Self {
-      argon,
       argon_cfg,
       jwt_cfg,
       encoding_key,
@@ -50,8 +108,22 @@ impl EncryptionRepository {
     }
   }
 
-  pub fn default() -> Self {
-    let argon = Argon2::default();
+  /// Secrets from the environment (see `data::DEFAULT_SECRETS`), default argon parameters
+  pub fn from_env() -> Self {
+    Self::new(
+      ArgonConfig {
+        t_cost: 2,
+        m_cost_kib: 65536,
+        p_cost: 1,
+        pepper: ArgonConfig::pepper_from_env(),
+      },
+      JwtConfig::from_env(),
+    )
+  }
+}
+
+impl Default for EncryptionRepository {
+  fn default() -> Self {
     let jwt_cfg = JwtConfig {
       secret: "default_secret_key".to_string(),
       expiry_seconds: 3600,
@@ -60,11 +132,11 @@ impl EncryptionRepository {
     let decoding_key = DecodingKey::from_secret(jwt_cfg.secret.as_bytes());
     
     Self {
-      argon,
       argon_cfg: ArgonConfig {
         t_cost: 2,
         m_cost_kib: 65536,
         p_cost: 1,
+        pepper: None,
       },
       jwt_cfg,
       encoding_key,
@@ -73,11 +145,35 @@ impl EncryptionRepository {
   }
 }
 
+impl EncryptionRepository {
+  /// Hasher with the configured cost parameters, keyed with the pepper when one is set. Verification
+  /// reads the costs from the stored hash, so changing them only affects new hashes
+  fn hasher(&self) -> Result<Argon2<'_>, EncryptionError> {
+    let cfg = &self.argon_cfg;
+    let params = Params::new(cfg.m_cost_kib, cfg.t_cost, cfg.p_cost, None)
+      .map_err(|e| EncryptionError::HashError(e.to_string()))?;
+    match &cfg.pepper {
+      Some(pepper) => Argon2::new_with_secret(pepper.as_bytes(), ArgonAlgorithm::default(), Version::default(), params)
+        .map_err(|e| EncryptionError::HashError(e.to_string())),
+      None => Ok(Argon2::new(ArgonAlgorithm::default(), Version::default(), params)),
+    }
+  }
+
+  fn sign_claims(&self, claims: &data::Claims, token_type: &TokenParams) -> Result<String, EncryptionError> {
+    let encoding_key = EncodingKey::from_secret(token_type.key.as_bytes());
+
+    match encode(&Header::default(), claims, &encoding_key) {
+      Ok(token) => Ok(token),
+      Err(e) => Err(EncryptionError::JwtError(e.to_string())),
+    }
+  }
+}
+
 impl EncryptionRepositoryTrait for EncryptionRepository {
   fn hash_password(&self, plain: &str) -> Result<String, EncryptionError> {
     let salt = SaltString::generate(&mut OsRng);
     
-    match self.argon.hash_password(plain.as_bytes(), &salt) {
+    match self.hasher()?.hash_password(plain.as_bytes(), &salt) {
       Ok(hash) => Ok(hash.to_string()),
       Err(e) => Err(EncryptionError::HashError(e.to_string())),
     }
@@ -89,7 +185,7 @@ impl EncryptionRepositoryTrait for EncryptionRepository {
       Err(e) => return Err(EncryptionError::VerifyError(e.to_string())),
     };
 
-    match self.argon.verify_password(plain.as_bytes(), &parsed_hash) {
+    match self.hasher()?.verify_password(plain.as_bytes(), &parsed_hash) {
       Ok(_) => Ok(true),
       Err(_) => Ok(false),
     }
@@ -150,31 +246,52 @@ impl EncryptionRepositoryTrait for EncryptionRepository {
     }
   }
 
-  fn create_token<T: serde::Serialize>(&self, payload: T, token_type: TokenParams) -> Result<String, EncryptionError> {
+  fn create_token_value(&self, payload: serde_json::Value, token_type: TokenParams, options: TokenOptions) -> Result<String, EncryptionError> {
     // Encode payload as a JSON string within claims `sub`
-    let claims = match data::Claims::new_text(&payload, token_type.expiry_seconds) {
+    let mut claims = match data::Claims::new_text(&payload, &token_type) {
       Ok(claims) => claims,
       Err(e) => return Err(EncryptionError::JwtError(e.to_string())),
     };
-
-    let encoding_key = EncodingKey::from_secret(token_type.key.as_bytes());
-    
-    match encode(&Header::default(), &claims, &encoding_key) {
-      Ok(token) => Ok(token),
-      Err(e) => Err(EncryptionError::JwtError(e.to_string())),
+    let lifetime = options.expiry_override.unwrap_or(token_type.expiry_seconds);
+    claims.exp = chrono::Utc::now().timestamp() + lifetime;
+    if let Some(not_before) = options.not_before {
+      // Keep the full lifetime after activation instead of counting it from now
+      claims.exp = claims.exp.max(not_before + lifetime);
+      claims.nbf = Some(not_before);
     }
+    claims.jti = options.jti;
+    // Registered claims are set above and can't be overridden through `extra`
+    const RESERVED: [&str; 6] = ["sub", "exp", "iss", "aud", "nbf", "jti"];
+    claims.extra = options.extra.into_iter().filter(|(key, _)| !RESERVED.contains(&key.as_str())).collect();
+
+    self.sign_claims(&claims, &token_type)
   }
 
   fn decode_token(&self, token_string: &str, token_type: TokenParams) -> Result<serde_json::Value, EncryptionError> {
     // Normalize token (trim whitespace and surrounding quotes)
     let token = token_string.trim().trim_matches('"');
 
-    let decoding_key = DecodingKey::from_secret(token_type.key.as_bytes());
     let mut validation = Validation::default();
     validation.algorithms = vec![Algorithm::HS256, Algorithm::HS384, Algorithm::HS512];
+    validation.set_issuer(&[&token_type.issuer]);
+    validation.set_audience(&[&token_type.audience]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+    validation.validate_nbf = true;
+
+    // Current key first; previous keys only get a go when the signature is what failed
+    let mut result = decode::<serde_json::Value>(token, &DecodingKey::from_secret(token_type.key.as_bytes()), &validation);
+    for previous in &token_type.previous_keys {
+      match &result {
+        Err(e) if matches!(e.kind(), ErrorKind::InvalidSignature) => {
+          result = decode::<serde_json::Value>(token, &DecodingKey::from_secret(previous.as_bytes()), &validation);
+        }
+        _ => break,
+      }
+    }
 
-    match decode::<serde_json::Value>(token, &decoding_key, &validation) {
+    match result {
       Ok(data) => Ok(data.claims),
+      Err(e) if matches!(e.kind(), ErrorKind::ImmatureSignature) => Err(EncryptionError::TokenNotYetValid),
       Err(e) => {
         tracing::info!("decode_token error: {}", e);
         Err(EncryptionError::JwtError(e.to_string()))
@@ -194,11 +311,186 @@ impl EncryptionRepositoryTrait for EncryptionRepository {
       for &b in &buf {
         if code.len() >= length { break; }
         if b < 250 {
-          let digit = (b % 10) as u8;
+          let digit = b % 10;
           code.push((b'0' + digit) as char);
         }
       }
     }
     code
   }
-}
\ No newline at end of file
+
+  /// Stateless link token for `user_id`, signed with its own secret and only valid for `purpose`
+  fn create_signed_link(&self, user_id: Uuid, purpose: &str, ttl_seconds: i64) -> Result<String, EncryptionError> {
+    let mut extra = serde_json::Map::new();
+    extra.insert("purpose".to_string(), serde_json::Value::String(purpose.to_string()));
+
+    self.create_token_with(user_id, Token::signed_link(), extra, Some(ttl_seconds))
+  }
+
+  /// Check signature, expiry and purpose of a link token and return the user it was issued for
+  fn verify_signed_link(&self, token: &str, purpose: &str) -> Result<Uuid, EncryptionError> {
+    let claims = self.decode_token(token, Token::signed_link())?;
+
+    if claims.get("purpose").and_then(|p| p.as_str()) != Some(purpose) {
+      return Err(EncryptionError::InvalidLink("purpose mismatch".to_string()));
+    }
+
+    claims
+      .get("sub")
+      .and_then(|sub| sub.as_str())
+      .and_then(|sub| serde_json::from_str::<Uuid>(sub).ok())
+      .ok_or_else(|| EncryptionError::InvalidLink("malformed subject".to_string()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Cheap argon costs so the tests don't spend seconds hashing
+  fn repo_with_pepper(pepper: Option<&str>) -> EncryptionRepository {
+    EncryptionRepository::new(
+      ArgonConfig { t_cost: 1, m_cost_kib: 8, p_cost: 1, pepper: pepper.map(str::to_string) },
+      JwtConfig { secret: "test-secret".to_string(), expiry_seconds: 60 },
+    )
+  }
+
+  fn params(key: &str, audience: &str) -> TokenParams {
+    TokenParams {
+      key: key.to_string(),
+      previous_keys: Vec::new(),
+      expiry_seconds: 60,
+      issuer: "trade-server".to_string(),
+      audience: audience.to_string(),
+    }
+  }
+
+  #[test]
+  fn token_for_another_audience_or_issuer_is_rejected() {
+    let repo = repo_with_pepper(None);
+    let token = repo.create_token("user-1", params("shared-key", "user_access")).unwrap();
+
+    assert!(repo.decode_token(&token, params("shared-key", "user_access")).is_ok());
+    assert!(repo.decode_token(&token, params("shared-key", "admin_access")).is_err());
+
+    let other_issuer = TokenParams { issuer: "someone-else".to_string(), ..params("shared-key", "user_access") };
+    assert!(repo.decode_token(&token, other_issuer).is_err());
+  }
+
+  #[test]
+  fn token_is_rejected_before_its_activation_time() {
+    let repo = repo_with_pepper(None);
+    let now = chrono::Utc::now().timestamp();
+
+    // Well past jsonwebtoken's default 60s leeway
+    let delayed = repo.create_token_with_nbf("user-1", params("key", "user_access"), now + 3600).unwrap();
+    assert!(matches!(repo.decode_token(&delayed, params("key", "user_access")), Err(EncryptionError::TokenNotYetValid)));
+
+    let active = repo.create_token_with_nbf("user-1", params("key", "user_access"), now - 10).unwrap();
+    assert!(repo.decode_token(&active, params("key", "user_access")).is_ok());
+  }
+
+  #[test]
+  fn constant_time_eq_matches_plain_equality() {
+    assert!(constant_time_eq("483920", "483920"));
+    assert!(!constant_time_eq("483920", "483921"));
+    assert!(!constant_time_eq("483920", "48392"));
+    assert!(!constant_time_eq("", "483920"));
+    assert!(constant_time_eq("", ""));
+  }
+
+  #[test]
+  fn hash_verifies_only_under_its_own_pepper() {
+    let peppered = repo_with_pepper(Some("pepper-a"));
+    let hash = peppered.hash_password("correct horse").unwrap();
+
+    assert!(peppered.verify_password(&hash, "correct horse").unwrap());
+    assert!(!peppered.verify_password(&hash, "wrong horse").unwrap());
+    assert!(!repo_with_pepper(Some("pepper-b")).verify_password(&hash, "correct horse").unwrap());
+    assert!(!repo_with_pepper(None).verify_password(&hash, "correct horse").unwrap());
+  }
+
+  #[test]
+  fn unpeppered_hash_fails_once_a_pepper_is_set() {
+    let hash = repo_with_pepper(None).hash_password("correct horse").unwrap();
+    assert!(!repo_with_pepper(Some("pepper-a")).verify_password(&hash, "correct horse").unwrap());
+  }
+
+  #[test]
+  fn hash_uses_configured_costs() {
+    for pepper in [None, Some("pepper-a")] {
+      let hash = repo_with_pepper(pepper).hash_password("correct horse").unwrap();
+      assert!(hash.contains("m=8,t=1,p=1"), "unexpected parameters in {}", hash);
+    }
+  }
+  #[test]
+  fn previous_key_still_verifies_while_new_tokens_use_the_current_one() {
+    let repo = repo_with_pepper(None);
+    let rotated = TokenParams { previous_keys: vec!["old-key".to_string()], ..params("new-key", "user_access") };
+
+    let old_token = repo.create_token("user-1", params("old-key", "user_access")).unwrap();
+    assert!(repo.decode_token(&old_token, rotated.clone()).is_ok());
+
+    let new_token = repo.create_token("user-1", rotated.clone()).unwrap();
+    assert!(repo.decode_token(&new_token, params("new-key", "user_access")).is_ok());
+    assert!(repo.decode_token(&new_token, params("old-key", "user_access")).is_err());
+
+    let stranger = repo.create_token("user-1", params("other-key", "user_access")).unwrap();
+    assert!(repo.decode_token(&stranger, rotated).is_err());
+  }
+  #[test]
+  fn extra_claims_and_expiry_override_round_trip() {
+    let repo = repo_with_pepper(None);
+    let mut extra = serde_json::Map::new();
+    extra.insert("purpose".to_string(), serde_json::json!("verify_email"));
+    // Registered claims can't be smuggled in through `extra`
+    extra.insert("iss".to_string(), serde_json::json!("someone-else"));
+
+    let token = repo.create_token_with("user-1", params("key", "user_access"), extra, Some(300)).unwrap();
+    let claims = repo.decode_token(&token, params("key", "user_access")).unwrap();
+
+    assert_eq!(claims["purpose"], "verify_email");
+    assert_eq!(claims["iss"], "trade-server");
+    let lifetime = claims["exp"].as_i64().unwrap() - chrono::Utc::now().timestamp();
+    assert!((295..=300).contains(&lifetime), "lifetime {}", lifetime);
+  }
+  #[test]
+  fn signed_link_verifies_only_for_its_purpose_and_lifetime() {
+    let repo = repo_with_pepper(None);
+    let user_id = Uuid::new_v4();
+
+    let link = repo.create_signed_link(user_id, "verify_email", 600).unwrap();
+    assert_eq!(repo.verify_signed_link(&link, "verify_email").unwrap(), user_id);
+    assert!(matches!(repo.verify_signed_link(&link, "reset_password"), Err(EncryptionError::InvalidLink(_))));
+
+    // Expired by more than the 60s leeway
+    let expired = repo.create_signed_link(user_id, "verify_email", -120).unwrap();
+    assert!(repo.verify_signed_link(&expired, "verify_email").is_err());
+  }
+
+  #[test]
+  fn signed_links_and_access_tokens_are_not_interchangeable() {
+    let repo = repo_with_pepper(None);
+    let link = repo.create_signed_link(Uuid::new_v4(), "verify_email", 600).unwrap();
+    assert!(repo.decode_token(&link, Token::user_access_token()).is_err());
+
+    let access = repo.create_token("user-1", Token::user_access_token()).unwrap();
+    assert!(repo.verify_signed_link(&access, "verify_email").is_err());
+  }
+
+  #[test]
+  fn decode_any_token_reports_which_kind_matched() {
+    let repo = repo_with_pepper(None);
+    for kind in [TokenKind::UserAccess, TokenKind::UserRefresh, TokenKind::AdminAccess] {
+      let token = repo.create_token("user-1", kind.params()).unwrap();
+      let (matched, claims) = repo.decode_any_token(&token).unwrap();
+      assert_eq!(matched, kind);
+      assert_eq!(claims["aud"], kind.params().audience);
+    }
+
+    // Signed links and garbage aren't any of the session token kinds
+    let link = repo.create_signed_link(Uuid::new_v4(), "verify_email", 600).unwrap();
+    assert!(repo.decode_any_token(&link).is_err());
+    assert!(repo.decode_any_token("garbage").is_err());
+  }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use std::collections::HashMap;
+
+use super::templates::EmailTemplate;
+
+#[derive(Error, Debug)]
+pub enum MailError {
+    /// Worth retrying later, e.g. the mail server is unreachable or answered 4xx
+    #[error("Transient mail error: {0}")]
+    Transient(String),
+
+    /// Will never succeed, e.g. the recipient was rejected with 5xx
+    #[error("Permanent mail error: {0}")]
+    Permanent(String),
+}
+
+/// An email ready to hand to a `Mailer`: a text body and an optional HTML alternative
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    #[serde(default)]
+    pub html: Option<String>,
+}
+
+impl EmailMessage {
+    /// Render a named template; an unknown template or missing value can never succeed
+    pub fn from_template(to: String, template: &str, context: &HashMap<String, String>) -> Result<Self, MailError> {
+        let template = EmailTemplate::from_name(template)
+            .ok_or_else(|| MailError::Permanent(format!("unknown email template: {}", template)))?;
+        let rendered = template.render(context)?;
+
+        Ok(Self {
+            to,
+            subject: rendered.subject,
+            body: rendered.text,
+            html: Some(rendered.html),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Envelope and `From:` address
+    pub from: String,
+    /// Implicit TLS (SMTPS); turn off only for local catch-all servers
+    pub tls: bool,
+}
+
+impl SmtpConfig {
+    /// `SMTP_HOST`, `SMTP_PORT`, `SMTP_USERNAME`, `SMTP_PASSWORD`, `SMTP_FROM`, `SMTP_TLS` (default true)
+    pub fn from_env() -> Self {
+        let tls = std::env::var("SMTP_TLS").map(|v| v != "false" && v != "0").unwrap_or(true);
+        let port = std::env::var("SMTP_PORT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(if tls { 465 } else { 25 });
+
+        Self {
+            host: std::env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string()),
+            port,
+            username: std::env::var("SMTP_USERNAME").ok(),
+            password: std::env::var("SMTP_PASSWORD").ok(),
+            from: std::env::var("SMTP_FROM").unwrap_or_else(|_| "no-reply@localhost".to_string()),
+            tls,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use data::{EmailMessage, MailError, SmtpConfig};
+
+pub mod data;
+pub mod smtp;
+pub mod templates;
+
+/// Delivers outgoing email; pick the backend with `MAILER`
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, msg: EmailMessage) -> Result<(), MailError>;
+}
+
+/// Writes emails to the log instead of sending them, for development and tests
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, msg: EmailMessage) -> Result<(), MailError> {
+        tracing::info!(to = %msg.to, subject = %msg.subject, body = %msg.body, "email (log mailer)");
+        Ok(())
+    }
+}
+
+/// `MAILER=smtp` sends through `SmtpMailer`; anything else logs with `LogMailer`
+pub fn from_env() -> Arc<dyn Mailer> {
+    match std::env::var("MAILER").map(|v| v.to_lowercase()).as_deref() {
+        Ok("smtp") => Arc::new(smtp::SmtpMailer::new(SmtpConfig::from_env())),
+        _ => Arc::new(LogMailer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use templates::{CodeContext, EmailTemplate};
+
+    #[tokio::test]
+    async fn log_mailer_sends_the_rendered_reset_code() {
+        let context = CodeContext { first_name: "Ada".to_string(), code: "482913".to_string(), expires_in: "7 days".to_string() };
+        let message = EmailMessage::from_template("ada@example.com".to_string(), EmailTemplate::ResetCode.as_str(), &context.into()).unwrap();
+
+        assert_eq!(message.subject, "Your password reset code");
+        assert!(message.body.contains("482913"), "{}", message.body);
+        assert!(message.html.as_deref().unwrap().contains("482913"));
+        LogMailer.send(message).await.unwrap();
+    }
+
+    #[test]
+    fn unknown_template_is_a_permanent_error() {
+        let result = EmailMessage::from_template("ada@example.com".to_string(), "welcome", &Default::default());
+        assert!(matches!(result, Err(MailError::Permanent(_))));
+    }
+}
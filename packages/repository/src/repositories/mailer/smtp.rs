@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::{ClientConfig, OwnedTrustAnchor, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+
+use super::data::{EmailMessage, MailError, SmtpConfig};
+use super::Mailer;
+
+/// Minimal SMTP client: implicit TLS or plaintext, optional AUTH PLAIN, one message per connection
+pub struct SmtpMailer {
+    config: SmtpConfig,
+    tls: TlsConnector,
+}
+
+impl SmtpMailer {
+    pub fn new(config: SmtpConfig) -> Self {
+        let mut roots = RootCertStore::empty();
+        roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+            OwnedTrustAnchor::from_subject_spki_name_constraints(ta.subject, ta.spki, ta.name_constraints)
+        }));
+        let tls_config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        Self {
+            config,
+            tls: TlsConnector::from(Arc::new(tls_config)),
+        }
+    }
+
+    async fn deliver<S>(&self, stream: S, msg: &EmailMessage) -> Result<(), MailError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut stream = BufReader::new(stream);
+
+        expect_reply(&mut stream, &[220]).await?;
+        command(&mut stream, "EHLO localhost", &[250]).await?;
+
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            let credentials = STANDARD.encode(format!("\0{}\0{}", username, password));
+            command(&mut stream, &format!("AUTH PLAIN {}", credentials), &[235]).await?;
+        }
+
+        command(&mut stream, &format!("MAIL FROM:<{}>", self.config.from), &[250]).await?;
+        command(&mut stream, &format!("RCPT TO:<{}>", msg.to), &[250, 251]).await?;
+        command(&mut stream, "DATA", &[354]).await?;
+
+        let data = format!("{}.\r\n", render(&self.config.from, msg));
+        write(&mut stream, &data).await?;
+        expect_reply(&mut stream, &[250]).await?;
+
+        // The message is accepted at this point; a failed QUIT doesn't matter
+        let _ = command(&mut stream, "QUIT", &[221]).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, msg: EmailMessage) -> Result<(), MailError> {
+        // Header values must not smuggle extra headers
+        if [&msg.to, &msg.subject].iter().any(|v| v.contains(['\r', '\n'])) {
+            return Err(MailError::Permanent("line break in recipient or subject".to_string()));
+        }
+
+        let stream = TcpStream::connect((self.config.host.as_str(), self.config.port))
+            .await
+            .map_err(|e| MailError::Transient(format!("connect error: {}", e)))?;
+
+        if self.config.tls {
+            let server_name = ServerName::try_from(self.config.host.as_str())
+                .map_err(|e| MailError::Permanent(format!("invalid SMTP host: {}", e)))?;
+            let stream = self
+                .tls
+                .connect(server_name, stream)
+                .await
+                .map_err(|e| MailError::Transient(format!("TLS error: {}", e)))?;
+            self.deliver(stream, &msg).await
+        } else {
+            self.deliver(stream, &msg).await
+        }
+    }
+}
+
+/// Headers and dot-stuffed body of the DATA section, without the terminating "."
+fn render(from: &str, msg: &EmailMessage) -> String {
+    let subject = if msg.subject.is_ascii() {
+        msg.subject.clone()
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(&msg.subject))
+    };
+
+    let mut data = format!(
+        "From: <{}>\r\nTo: <{}>\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\n",
+        from,
+        msg.to,
+        subject,
+        chrono::Utc::now().to_rfc2822(),
+    );
+
+    match &msg.html {
+        None => {
+            data.push_str("Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n");
+            push_lines(&mut data, &msg.body);
+        }
+        Some(html) => {
+            let boundary = format!("=_{}", uuid::Uuid::new_v4().simple());
+            data.push_str(&format!("Content-Type: multipart/alternative; boundary=\"{}\"\r\n\r\n", boundary));
+            for (content_type, part) in [("text/plain", &msg.body), ("text/html", html)] {
+                data.push_str(&format!(
+                    "--{}\r\nContent-Type: {}; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+                    boundary, content_type
+                ));
+                push_lines(&mut data, part);
+            }
+            data.push_str(&format!("--{}--\r\n", boundary));
+        }
+    }
+    data
+}
+
+/// Append `text` with CRLF line endings, dot-stuffing lines that start with "."
+fn push_lines(data: &mut String, text: &str) {
+    for line in text.lines() {
+        // A leading "." would otherwise be read as end of data
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+}
+
+async fn write<S>(stream: &mut BufReader<S>, data: &str) -> Result<(), MailError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let stream = stream.get_mut();
+    stream
+        .write_all(data.as_bytes())
+        .await
+        .map_err(|e| MailError::Transient(format!("write error: {}", e)))?;
+    stream
+        .flush()
+        .await
+        .map_err(|e| MailError::Transient(format!("write error: {}", e)))
+}
+
+async fn command<S>(stream: &mut BufReader<S>, line: &str, expected: &[u16]) -> Result<(), MailError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    write(stream, &format!("{}\r\n", line)).await?;
+    expect_reply(stream, expected).await
+}
+
+/// Read a (possibly multi-line) reply; 4xx is transient, 5xx or anything unexpected is permanent
+async fn expect_reply<S>(stream: &mut BufReader<S>, expected: &[u16]) -> Result<(), MailError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        let read = stream
+            .read_line(&mut line)
+            .await
+            .map_err(|e| MailError::Transient(format!("read error: {}", e)))?;
+        if read == 0 {
+            return Err(MailError::Transient("connection closed by server".to_string()));
+        }
+
+        let code: u16 = line
+            .get(..3)
+            .and_then(|c| c.parse().ok())
+            .ok_or_else(|| MailError::Transient(format!("malformed reply: {}", line.trim_end())))?;
+
+        // "250-..." continues the reply, "250 ..." ends it
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+
+        return if expected.contains(&code) {
+            Ok(())
+        } else if (400..500).contains(&code) {
+            Err(MailError::Transient(line.trim_end().to_string()))
+        } else {
+            Err(MailError::Permanent(line.trim_end().to_string()))
+        };
+    }
+}
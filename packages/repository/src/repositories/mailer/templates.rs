@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::data::MailError;
+
+/// Named email templates, each with a subject and text/HTML bodies using `{{ key }}` placeholders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTemplate {
+    ResetCode,
+    VerifyEmail,
+}
+
+impl EmailTemplate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailTemplate::ResetCode => "reset_code",
+            EmailTemplate::VerifyEmail => "verify_email",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "reset_code" => Some(EmailTemplate::ResetCode),
+            "verify_email" => Some(EmailTemplate::VerifyEmail),
+            _ => None,
+        }
+    }
+
+    fn sources(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            EmailTemplate::ResetCode => (
+                "Your password reset code",
+                include_str!("templates/reset_code.txt"),
+                include_str!("templates/reset_code.html"),
+            ),
+            EmailTemplate::VerifyEmail => (
+                "Verify your email address",
+                include_str!("templates/verify_email.txt"),
+                include_str!("templates/verify_email.html"),
+            ),
+        }
+    }
+
+    /// Fill in the template; a placeholder missing from `context` is a permanent error
+    pub fn render(&self, context: &HashMap<String, String>) -> Result<RenderedEmail, MailError> {
+        let (subject, text, html) = self.sources();
+        Ok(RenderedEmail {
+            subject: subject.to_string(),
+            text: fill(text, context, false)?,
+            html: fill(html, context, true)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Context for `EmailTemplate::ResetCode` and `EmailTemplate::VerifyEmail`
+#[derive(Debug, Clone)]
+pub struct CodeContext {
+    pub first_name: String,
+    pub code: String,
+    /// Human-readable lifetime, e.g. "7 days"
+    pub expires_in: String,
+}
+
+impl From<CodeContext> for HashMap<String, String> {
+    fn from(context: CodeContext) -> Self {
+        HashMap::from([
+            ("first_name".to_string(), context.first_name),
+            ("code".to_string(), context.code),
+            ("expires_in".to_string(), context.expires_in),
+        ])
+    }
+}
+
+fn fill(template: &str, context: &HashMap<String, String>, escape: bool) -> Result<String, MailError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| MailError::Permanent("unclosed template placeholder".to_string()))?;
+
+        let key = after[..end].trim();
+        let value = context
+            .get(key)
+            .ok_or_else(|| MailError::Permanent(format!("missing template value: {}", key)))?;
+        if escape {
+            out.push_str(&escape_html(value));
+        } else {
+            out.push_str(value);
+        }
+        rest = &after[end + 2..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(first_name: &str) -> HashMap<String, String> {
+        CodeContext { first_name: first_name.to_string(), code: "482913".to_string(), expires_in: "7 days".to_string() }.into()
+    }
+
+    #[test]
+    fn reset_code_renders_the_code_in_both_parts() {
+        let rendered = EmailTemplate::ResetCode.render(&context("Ada")).unwrap();
+        assert!(rendered.text.contains("482913"), "{}", rendered.text);
+        assert!(rendered.html.contains("482913"), "{}", rendered.html);
+        assert!(rendered.text.contains("7 days"));
+        assert!(!rendered.text.contains("{{"));
+    }
+
+    #[test]
+    fn html_part_escapes_context_values() {
+        let rendered = EmailTemplate::ResetCode.render(&context("<b>Ada</b>")).unwrap();
+        assert!(rendered.html.contains("&lt;b&gt;Ada&lt;/b&gt;"), "{}", rendered.html);
+        assert!(rendered.text.contains("<b>Ada</b>"));
+    }
+
+    #[test]
+    fn missing_context_value_is_a_permanent_error() {
+        let mut context = context("Ada");
+        context.remove("code");
+        assert!(matches!(EmailTemplate::ResetCode.render(&context), Err(MailError::Permanent(_))));
+    }
+}
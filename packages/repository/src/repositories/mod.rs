@@ -1,5 +1,6 @@
 pub mod crypto;
 pub mod encryption;
+pub mod mailer;
 pub mod queue;
 
 use std::sync::Arc;
@@ -8,30 +9,50 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct Repositories {
     // Shared services
-    pub encryption: Arc<encryption::EncryptionRepository>,
+    pub encryption: Arc<dyn encryption::EncryptionRepositoryTrait>,
     pub queue: Arc<queue::rabbitmq::RabbitMQRepository>,
+    pub queue_names: queue::data::QueueNames,
     pub crypto: Arc<crypto::CryptoRepository>,
+    pub mailer: Arc<dyn mailer::Mailer>,
 }
 
 impl Repositories {
     pub fn new() -> Self {
-        // Encryption uses a sane default; override as needed in callers
-        let encryption: Arc<encryption::EncryptionRepository> =
-            Arc::new(encryption::EncryptionRepository::default());
+        // Secrets come from the environment; callers validate them with encryption::data::validate_secrets
+        let encryption: Arc<dyn encryption::EncryptionRepositoryTrait> =
+            Arc::new(encryption::EncryptionRepository::from_env());
 
         // Queue and cache endpoints from env with defaults
         let rabbitmq_url =
             std::env::var("AMQP_URL").unwrap_or_else(|_| "amqp://127.0.0.1:5672/%2f".to_string());
 
+        let prefetch = std::env::var("QUEUE_PREFETCH")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(queue::rabbitmq::DEFAULT_PREFETCH);
+
         let queue: Arc<queue::rabbitmq::RabbitMQRepository> =
-            Arc::new(queue::rabbitmq::RabbitMQRepository::new(rabbitmq_url));
+            Arc::new(queue::rabbitmq::RabbitMQRepository::new(rabbitmq_url).with_prefetch(prefetch));
+
+        let queue_names = queue::data::QueueNames::from_env();
+
+        let crypto: Arc<crypto::CryptoRepository> =
+            Arc::new(crypto::CryptoRepository::new(crypto::data::CryptoConfig::from_env()));
 
-        let crypto: Arc<crypto::CryptoRepository> = Arc::new(crypto::CryptoRepository::default());
+        let mailer = mailer::from_env();
 
         Self {
             encryption,
             queue,
+            queue_names,
             crypto,
+            mailer,
         }
     }
 }
+
+impl Default for Repositories {
+    fn default() -> Self {
+        Self::new()
+    }
+}
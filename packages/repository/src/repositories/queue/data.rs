@@ -18,6 +18,10 @@ pub enum QueueError {
     #[error("Message deserialization error: {0}")]
     DeserializationError(String),
 
+    /// The message can never be processed; it is dead-lettered instead of requeued
+    #[error("Rejected message: {0}")]
+    Rejected(String),
+
     #[error("Queue error: {0}")]
     QueueError(String),
 
@@ -28,3 +32,47 @@ pub enum QueueError {
 #[allow(dead_code)]
 pub type MessageHandler = Box<dyn Fn(Vec<u8>) -> Result<(), QueueError> + Send + Sync>;
 
+
+/// Queue names used by publishers and consumers.
+///
+/// `QUEUE_PREFIX` namespaces every queue (`prod` turns `emails` into `prod.emails`);
+/// `EMAIL_QUEUE` overrides the base name of the email queue.
+#[derive(Debug, Clone)]
+pub struct QueueNames {
+    /// Email jobs for the worker
+    pub emails: String,
+}
+
+impl QueueNames {
+    pub fn new(prefix: Option<&str>, emails: &str) -> Self {
+        let name = |base: &str| match prefix.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(prefix) => format!("{}.{}", prefix, base),
+            None => base.to_string(),
+        };
+
+        Self { emails: name(emails) }
+    }
+
+    pub fn from_env() -> Self {
+        let prefix = std::env::var("QUEUE_PREFIX").ok();
+        let emails = std::env::var("EMAIL_QUEUE").unwrap_or_else(|_| "emails".to_string());
+        Self::new(prefix.as_deref(), &emails)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_namespaces_every_queue() {
+        assert_eq!(QueueNames::new(Some("prod"), "emails").emails, "prod.emails");
+        assert_eq!(QueueNames::new(Some(" staging "), "mail").emails, "staging.mail");
+    }
+
+    #[test]
+    fn blank_or_missing_prefix_keeps_the_base_name() {
+        assert_eq!(QueueNames::new(None, "emails").emails, "emails");
+        assert_eq!(QueueNames::new(Some("  "), "emails").emails, "emails");
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use tokio_util::sync::CancellationToken;
+
+use super::data::{MessageHandler, QueueError};
+use super::QueueRepositoryTrait;
+
+/// Stand-in for `RabbitMQRepository` in tests: published messages are kept per queue, and consuming
+/// hands every pending message to the handler once, then returns. Handled messages are recorded
+/// as acked or, with the error, dead-lettered. Never use it outside tests
+#[derive(Debug, Default)]
+pub struct InMemoryQueue {
+    pending: Mutex<HashMap<String, Vec<Vec<u8>>>>,
+    published: Mutex<Vec<(String, Vec<u8>)>>,
+    acked: Mutex<Vec<Vec<u8>>>,
+    dead_lettered: Mutex<Vec<(Vec<u8>, String)>>,
+}
+
+impl InMemoryQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every message published so far, with the queue it went to
+    pub fn published(&self) -> Vec<(String, Vec<u8>)> {
+        self.published.lock().unwrap().clone()
+    }
+
+    /// Messages a handler accepted
+    pub fn acked(&self) -> Vec<Vec<u8>> {
+        self.acked.lock().unwrap().clone()
+    }
+
+    /// Messages a handler refused, with the error it returned
+    pub fn dead_lettered(&self) -> Vec<(Vec<u8>, String)> {
+        self.dead_lettered.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl QueueRepositoryTrait for InMemoryQueue {
+    async fn consume<F>(&self, queue: &str, handler: F, shutdown: CancellationToken) -> Result<(), QueueError>
+    where
+        F: Fn(Vec<u8>) -> Result<(), QueueError> + Send + Sync,
+        Self: Sized,
+    {
+        let pending = self.pending.lock().unwrap().remove(queue).unwrap_or_default();
+        for message in pending {
+            if shutdown.is_cancelled() {
+                break;
+            }
+            match handler(message.clone()) {
+                Ok(()) => self.acked.lock().unwrap().push(message),
+                Err(err) => self.dead_lettered.lock().unwrap().push((message, err.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    async fn consume_boxed(&self, queue: &str, handler: MessageHandler, shutdown: CancellationToken) -> Result<(), QueueError> {
+        self.consume(queue, handler, shutdown).await
+    }
+
+    async fn acknowledge(&self, _delivery_tag: u64) -> Result<(), QueueError> {
+        Ok(())
+    }
+
+    async fn reject(&self, _delivery_tag: u64, _requeue: bool) -> Result<(), QueueError> {
+        Ok(())
+    }
+
+    async fn publish(&self, queue: &str, message: &[u8]) -> Result<(), QueueError> {
+        self.published.lock().unwrap().push((queue.to_string(), message.to_vec()));
+        self.pending.lock().unwrap().entry(queue.to_string()).or_default().push(message.to_vec());
+        Ok(())
+    }
+}
@@ -1,16 +1,26 @@
 use async_trait::async_trait;
-use data::QueueError;
+use data::{MessageHandler, QueueError};
+use tokio_util::sync::CancellationToken;
 
 pub mod data;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 pub mod rabbitmq;
 
 #[allow(dead_code)]
 #[async_trait]
 pub trait QueueRepositoryTrait: Send + Sync {
-    /// Consume messages from a queue with a handler function
-    async fn consume<F>(&self, queue: &str, handler: F) -> Result<(), QueueError>
+    /// Consume messages from a queue with a handler function until `shutdown` is cancelled.
+    ///
+    /// A message already being handled when shutdown fires is finished and acked before returning.
+    /// Generic, so it isn't available through `dyn QueueRepositoryTrait`; use `consume_boxed` there.
+    async fn consume<F>(&self, queue: &str, handler: F, shutdown: CancellationToken) -> Result<(), QueueError>
     where
-        F: Fn(Vec<u8>) -> Result<(), QueueError> + Send + Sync;
+        F: Fn(Vec<u8>) -> Result<(), QueueError> + Send + Sync,
+        Self: Sized;
+
+    /// `consume` with a boxed handler, callable on a `dyn QueueRepositoryTrait`
+    async fn consume_boxed(&self, queue: &str, handler: MessageHandler, shutdown: CancellationToken) -> Result<(), QueueError>;
 
     /// Acknowledge a message has been processed
     async fn acknowledge(&self, delivery_tag: u64) -> Result<(), QueueError>;
@@ -22,3 +32,38 @@ pub trait QueueRepositoryTrait: Send + Sync {
     async fn publish(&self, queue: &str, message: &[u8]) -> Result<(), QueueError>;
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::mock::InMemoryQueue;
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Holds the queue the way services do, as a trait object
+    struct Worker {
+        queue: Arc<dyn QueueRepositoryTrait>,
+    }
+
+    #[tokio::test]
+    async fn boxed_consume_works_through_a_trait_object() {
+        let memory = Arc::new(InMemoryQueue::new());
+        let worker = Worker { queue: memory.clone() };
+        worker.queue.publish("emails", b"first").await.unwrap();
+        worker.queue.publish("emails", b"bad").await.unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let handler: MessageHandler = Box::new(move |message| {
+            recorded.lock().unwrap().push(message.clone());
+            match message.as_slice() {
+                b"bad" => Err(QueueError::Rejected("unreadable".to_string())),
+                _ => Ok(()),
+            }
+        });
+        worker.queue.consume_boxed("emails", handler, CancellationToken::new()).await.unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), [b"first".to_vec(), b"bad".to_vec()]);
+        assert_eq!(memory.acked(), [b"first".to_vec()]);
+        assert_eq!(memory.dead_lettered(), [(b"bad".to_vec(), "Rejected message: unreadable".to_string())]);
+    }
+}
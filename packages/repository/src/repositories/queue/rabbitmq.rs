@@ -1,15 +1,66 @@
 use async_trait::async_trait;
-use lapin::{options::{BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicPublishOptions, QueueDeclareOptions}, types::FieldTable, BasicProperties, Channel, Connection, ConnectionProperties};
+use lapin::{options::{BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicPublishOptions, BasicQosOptions, QueueDeclareOptions}, types::{AMQPValue, FieldTable}, BasicProperties, Channel, Connection, ConnectionProperties};
 use crate::shared::data::repositories::queue::{QueueRepositoryTrait};
-use crate::shared::data::repositories::queue::data::QueueError;
+use crate::shared::data::repositories::queue::data::{MessageHandler, QueueError};
+use tokio_util::sync::CancellationToken;
+
+/// Unacked deliveries a consumer may hold at once unless configured otherwise
+pub const DEFAULT_PREFETCH: u16 = 10;
 
 pub struct RabbitMQRepository {
     connection_url: String,
+    prefetch: u16,
 }
 
 impl RabbitMQRepository {
     pub fn new(connection_url: String) -> Self {
-        Self { connection_url }
+        Self { connection_url, prefetch: DEFAULT_PREFETCH }
+    }
+
+    /// Limit how many unacked deliveries each consumer buffers, so work spreads across workers
+    pub fn with_prefetch(mut self, prefetch: u16) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Open and close a connection to confirm the broker is reachable
+    pub async fn check_connection(&self) -> Result<(), QueueError> {
+        let conn = Connection::connect(&self.connection_url, ConnectionProperties::default())
+            .await
+            .map_err(|e| QueueError::ConnectionError(format!("RabbitMQ connect error: {}", e)))?;
+        let _ = conn.close(200, "health check").await;
+        Ok(())
+    }
+
+    /// Name of the dead-letter queue paired with `queue`
+    pub fn dead_letter_queue(queue: &str) -> String {
+        format!("{}.dlq", queue)
+    }
+
+    /// Declare `queue` (durable) together with its dead-letter queue.
+    ///
+    /// Publisher and consumer must declare identical arguments, so both go through here.
+    async fn declare_queue(channel: &Channel, queue: &str) -> Result<(), lapin::Error> {
+        let dead_letter_queue = Self::dead_letter_queue(queue);
+        channel
+            .queue_declare(
+                &dead_letter_queue,
+                QueueDeclareOptions { durable: true, ..Default::default() },
+                FieldTable::default(),
+            )
+            .await?;
+
+        let mut arguments = FieldTable::default();
+        arguments.insert("x-dead-letter-exchange".into(), AMQPValue::LongString("".into()));
+        arguments.insert("x-dead-letter-routing-key".into(), AMQPValue::LongString(dead_letter_queue.into()));
+        channel
+            .queue_declare(
+                queue,
+                QueueDeclareOptions { durable: true, ..Default::default() },
+                arguments,
+            )
+            .await?;
+        Ok(())
     }
 
     async fn get_channel(&self) -> Result<Channel, QueueError> {
@@ -24,20 +75,21 @@ impl RabbitMQRepository {
 
 #[async_trait]
 impl QueueRepositoryTrait for RabbitMQRepository {
-    async fn consume<F>(&self, queue: &str, handler: F) -> Result<(), QueueError>
+    async fn consume<F>(&self, queue: &str, handler: F, shutdown: CancellationToken) -> Result<(), QueueError>
     where
         F: Fn(Vec<u8>) -> Result<(), QueueError> + Send + Sync,
+        Self: Sized,
     {
         let channel = self.get_channel().await?;
-        channel
-            .queue_declare(
-                queue,
-                QueueDeclareOptions { durable: true, ..Default::default() },
-                FieldTable::default(),
-            )
+        Self::declare_queue(&channel, queue)
             .await
             .map_err(|e| QueueError::ConsumeError(format!("Queue declare error: {}", e)))?;
 
+        channel
+            .basic_qos(self.prefetch, BasicQosOptions::default())
+            .await
+            .map_err(|e| QueueError::ConsumeError(format!("QoS error: {}", e)))?;
+
         let mut consumer = channel
             .basic_consume(
                 queue,
@@ -48,11 +100,31 @@ impl QueueRepositoryTrait for RabbitMQRepository {
             .await
             .map_err(|e| QueueError::ConsumeError(format!("Consume error: {}", e)))?;
 
-        while let Some(delivery) = consumer.next().await {
+        loop {
+            // Only waiting for the next delivery is cancellable; handling below always runs to its ack/nack
+            let delivery = tokio::select! {
+                _ = shutdown.cancelled() => {
+                    // Prefetched but unhandled deliveries go back to the queue when the channel closes
+                    let _ = channel.close(200, "shutdown").await;
+                    break;
+                }
+                next = consumer.next() => match next {
+                    Some(delivery) => delivery,
+                    None => break,
+                },
+            };
             let delivery = delivery
                 .map_err(|e| QueueError::ConsumeError(format!("Delivery error: {}", e)))?;
             let tag = delivery.delivery_tag;
-            match handler(delivery.data.clone()) {
+
+            // Restore the publisher's correlation id so handler logs can be tied to the originating request
+            let correlation_id = serde_json::from_slice::<serde_json::Value>(&delivery.data)
+                .ok()
+                .and_then(|v| v.get("correlation_id").and_then(|id| id.as_str()).map(str::to_string))
+                .unwrap_or_default();
+            let span = tracing::info_span!("job", queue = %queue, correlation_id = %correlation_id);
+
+            match span.in_scope(|| handler(delivery.data.clone())) {
                 Ok(_) => {
                     channel
                         .basic_ack(tag, BasicAckOptions::default())
@@ -60,8 +132,13 @@ impl QueueRepositoryTrait for RabbitMQRepository {
                         .map_err(|e| QueueError::AcknowledgeError(format!("Ack error: {}", e)))?;
                 }
                 Err(err) => {
+                    // Without requeue the broker routes the message to the queue's dead-letter queue
+                    let requeue = !matches!(err, QueueError::Rejected(_) | QueueError::DeserializationError(_));
+                    if !requeue {
+                        tracing::warn!(queue = %queue, error = %err, "dead-lettering message");
+                    }
                     channel
-                        .basic_nack(tag, BasicNackOptions { requeue: true, ..Default::default() })
+                        .basic_nack(tag, BasicNackOptions { requeue, ..Default::default() })
                         .await
                         .map_err(|e| QueueError::QueueError(format!("Nack error: {}. original: {}", e, err)))?;
                 }
@@ -71,6 +148,10 @@ impl QueueRepositoryTrait for RabbitMQRepository {
         Ok(())
     }
 
+    async fn consume_boxed(&self, queue: &str, handler: MessageHandler, shutdown: CancellationToken) -> Result<(), QueueError> {
+        self.consume(queue, handler, shutdown).await
+    }
+
     async fn acknowledge(&self, delivery_tag: u64) -> Result<(), QueueError> {
         let channel = self.get_channel().await?;
         channel
@@ -89,12 +170,7 @@ impl QueueRepositoryTrait for RabbitMQRepository {
 
     async fn publish(&self, queue: &str, message: &[u8]) -> Result<(), QueueError> {
         let channel = self.get_channel().await?;
-        channel
-            .queue_declare(
-                queue,
-                QueueDeclareOptions { durable: true, ..Default::default() },
-                FieldTable::default(),
-            )
+        Self::declare_queue(&channel, queue)
             .await
             .map_err(|e| QueueError::PublishError(format!("Queue declare error: {}", e)))?;
 
@@ -0,0 +1,154 @@
+//! Reads from a real BSC node, so every test here is ignored by default. Run them with
+//! `LIVE_BSC_RPC_URL=https://... cargo test -p repository --test live_chain -- --ignored`. The
+//! swap tests need `LIVE_ANVIL_RPC_URL` instead, pointing at an anvil fork of BSC mainnet
+
+use ethers::prelude::{Http, LocalWallet, Middleware, Provider, Signer, SignerMiddleware};
+use ethers::abi::{encode, Token};
+use ethers::types::{Address, Bytes, TransactionRequest, U256};
+use ethers::utils::{parse_ether, to_checksum};
+use repository::repositories::crypto::blockchain_client::{BlockchainClient, PairData};
+use repository::repositories::crypto::data::{CryptoConfig, SingleChainSwap, SwapStatus, SwapType, TokenInfo, Wallet};
+use repository::repositories::crypto::router::PancakeSwapV2Router;
+use repository::repositories::crypto::CryptoRepository;
+
+const PANCAKE_V2_FACTORY: &str = "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73";
+const WBNB: &str = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c";
+const CAKE: &str = "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82";
+
+async fn client() -> BlockchainClient {
+    let url = std::env::var("LIVE_BSC_RPC_URL").expect("LIVE_BSC_RPC_URL is required for live tests");
+    BlockchainClient::new(&url).await.expect("connect to LIVE_BSC_RPC_URL")
+}
+
+#[tokio::test]
+#[ignore = "needs LIVE_BSC_RPC_URL"]
+async fn batched_snapshot_matches_the_sequential_calls() {
+    let client = client().await;
+    let pair = client
+        .find_pair(CAKE, WBNB, PANCAKE_V2_FACTORY)
+        .await
+        .unwrap()
+        .expect("CAKE/WBNB pair exists");
+
+    let (batched_pair, batched_metadata) = client.get_pair_data_with_metadata(pair, CAKE).await.unwrap();
+    let sequential_pair = client.get_pair_data(pair, CAKE).await.unwrap();
+    let sequential_metadata = client.get_token_metadata(CAKE).await.unwrap();
+
+    assert_eq!(batched_metadata.name, sequential_metadata.name);
+    assert_eq!(batched_metadata.symbol, sequential_metadata.symbol);
+    assert_eq!(batched_metadata.decimals, sequential_metadata.decimals);
+    assert_eq!(batched_pair.pair_address, sequential_pair.pair_address);
+
+    // Reserves may move between the two reads, but the sides must not be swapped
+    let ratio = |p: &PairData| {
+        p.token_reserve.as_u128() as f64 / p.quote_reserve.as_u128() as f64
+    };
+    let drift = (ratio(&batched_pair) / ratio(&sequential_pair) - 1.0).abs();
+    assert!(drift < 0.01, "reserve ratio drifted by {}", drift);
+}
+
+fn live_repository() -> CryptoRepository {
+    let mut config = CryptoConfig::from_env();
+    let url = std::env::var("LIVE_BSC_RPC_URL").expect("LIVE_BSC_RPC_URL is required for live tests");
+    config.rpc_endpoints.insert("bsc".to_string(), url);
+    CryptoRepository::new(config)
+}
+
+fn bnb_to_cake(amount: &str) -> SingleChainSwap {
+    SingleChainSwap {
+        chain: "bsc".to_string(),
+        from_token: TokenInfo { symbol: "BNB".to_string(), address: None, decimals: 18 },
+        to_token: TokenInfo { symbol: "CAKE".to_string(), address: Some(CAKE.to_string()), decimals: 18 },
+        amount: amount.to_string(),
+        slippage: "0.5".to_string(),
+        dex: None,
+        wait_for_receipt: false,
+        dry_run: false,
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs LIVE_BSC_RPC_URL"]
+async fn quote_of_a_liquid_pair_has_a_positive_output() {
+    let quote = live_repository().quote_swap(SwapType::SingleChain(bnb_to_cake("1"))).await.unwrap();
+
+    assert!(quote.amount_out.parse::<f64>().unwrap() > 0.0, "{:?}", quote);
+    assert!(quote.route.len() >= 2, "{:?}", quote.route);
+}
+
+/// First of anvil's default dev accounts, funded on every fork
+const ANVIL_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Anvil's first dev account on the fork at `LIVE_ANVIL_RPC_URL`, with 1 BNB wrapped so it can
+/// be swapped (swaps only take ERC20 inputs)
+async fn fork_account_with_wbnb() -> (String, SignerMiddleware<Provider<Http>, LocalWallet>) {
+    let url = std::env::var("LIVE_ANVIL_RPC_URL").expect("LIVE_ANVIL_RPC_URL is required for fork tests");
+    let provider = Provider::<Http>::try_from(url.as_str()).unwrap();
+    let chain_id = provider.get_chainid().await.unwrap().as_u64();
+    let signer: LocalWallet = ANVIL_KEY.parse::<LocalWallet>().unwrap().with_chain_id(chain_id);
+    let account = SignerMiddleware::new(provider, signer);
+
+    // `deposit()` has selector 0xd0e30db0
+    let deposit = TransactionRequest::new()
+        .to(WBNB.parse::<Address>().unwrap())
+        .value(parse_ether(1).unwrap())
+        .data(Bytes::from_static(&[0xd0, 0xe3, 0x0d, 0xb0]));
+    account.send_transaction(deposit, None).await.unwrap().await.unwrap().expect("deposit mined");
+    (url, account)
+}
+
+fn fork_config(url: String) -> CryptoConfig {
+    let mut config = CryptoConfig::from_env();
+    config.rpc_endpoints.insert("bsc".to_string(), url);
+    config
+}
+
+fn wbnb_to_cake(amount: &str) -> SingleChainSwap {
+    let mut swap = bnb_to_cake(amount);
+    swap.from_token.address = Some(WBNB.to_string());
+    swap
+}
+
+#[tokio::test]
+#[ignore = "needs LIVE_ANVIL_RPC_URL, an anvil fork of BSC mainnet"]
+async fn swap_on_a_fork_is_signed_broadcast_and_mined() {
+    let (url, account) = fork_account_with_wbnb().await;
+    let wallet = Wallet::new(to_checksum(&account.address(), None), ANVIL_KEY.to_string(), String::new());
+
+    let mut swap = wbnb_to_cake("0.1");
+    swap.wait_for_receipt = true;
+
+    let result = wallet
+        .swap_tokens(&fork_config(url), &PancakeSwapV2Router, SwapType::SingleChain(swap))
+        .await
+        .unwrap();
+    assert!(matches!(result.status, SwapStatus::Completed), "{:?}", result);
+    assert!(result.tx_hash.starts_with("0x") && result.tx_hash.len() == 66, "{}", result.tx_hash);
+}
+
+#[tokio::test]
+#[ignore = "needs LIVE_ANVIL_RPC_URL, an anvil fork of BSC mainnet"]
+async fn gas_estimate_names_a_missing_allowance_then_prices_the_swap() {
+    let (url, account) = fork_account_with_wbnb().await;
+    let config = fork_config(url);
+    let wallet = Wallet::new(to_checksum(&account.address(), None), ANVIL_KEY.to_string(), String::new());
+    let dry_run = || {
+        let mut swap = wbnb_to_cake("0.1");
+        swap.dry_run = true;
+        SwapType::SingleChain(swap)
+    };
+
+    let err = wallet.swap_tokens(&config, &PancakeSwapV2Router, dry_run()).await.unwrap_err();
+    assert!(err.to_string().contains("Insufficient allowance"), "{}", err);
+
+    // approve(router, max)
+    let router: Address = config.dex_routers["bsc"].parse().unwrap();
+    let approve = TransactionRequest::new()
+        .to(WBNB.parse::<Address>().unwrap())
+        .data(Bytes::from([&[0x09, 0x5e, 0xa7, 0xb3][..], &encode(&[Token::Address(router), Token::Uint(U256::MAX)])].concat()));
+    account.send_transaction(approve, None).await.unwrap().await.unwrap().expect("approval mined");
+
+    let result = wallet.swap_tokens(&config, &PancakeSwapV2Router, dry_run()).await.unwrap();
+    assert_eq!(result.tx_hash, "dryrun");
+    assert!(result.fee.parse::<f64>().unwrap() > 0.0, "{:?}", result);
+}
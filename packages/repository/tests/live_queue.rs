@@ -0,0 +1,121 @@
+//! Talks to a real RabbitMQ broker, so every test here is ignored by default. Run them with
+//! `LIVE_AMQP_URL=amqp://127.0.0.1:5672/%2f cargo test -p repository --test live_queue -- --ignored`
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+use lapin::{Connection, ConnectionProperties};
+use repository::repositories::queue::rabbitmq::RabbitMQRepository;
+use repository::repositories::queue::QueueRepositoryTrait;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+fn url() -> String {
+    std::env::var("LIVE_AMQP_URL").expect("LIVE_AMQP_URL is required for live tests")
+}
+
+/// A queue no other test run uses
+fn fresh_queue() -> String {
+    format!("live-test.{}", Uuid::new_v4())
+}
+
+/// Messages in `queue` that are ready for delivery, i.e. neither delivered nor unacked
+async fn ready_messages(queue: &str) -> u32 {
+    let conn = Connection::connect(&url(), ConnectionProperties::default()).await.unwrap();
+    let channel = conn.create_channel().await.unwrap();
+    let declared = channel
+        .queue_declare(queue, QueueDeclareOptions { passive: true, ..Default::default() }, FieldTable::default())
+        .await
+        .unwrap();
+    let _ = conn.close(200, "done").await;
+    declared.message_count()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs LIVE_AMQP_URL"]
+async fn consumer_holds_at_most_prefetch_unacked_messages() {
+    let queue = fresh_queue();
+    let repo = Arc::new(RabbitMQRepository::new(url()).with_prefetch(2));
+    for i in 0..5u8 {
+        repo.publish(&queue, &[i]).await.unwrap();
+    }
+
+    // The first message blocks the handler, so the broker may only push `prefetch` deliveries
+    let (release, gate) = mpsc::channel::<()>();
+    let gate = Mutex::new(gate);
+    let shutdown = CancellationToken::new();
+    let consumer = {
+        let (repo, queue, shutdown) = (repo.clone(), queue.clone(), shutdown.clone());
+        tokio::spawn(async move {
+            repo.consume(&queue, move |_| {
+                let _ = gate.lock().unwrap().recv();
+                Ok(())
+            }, shutdown)
+            .await
+        })
+    };
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(ready_messages(&queue).await, 3);
+
+    shutdown.cancel();
+    for _ in 0..5 {
+        let _ = release.send(());
+    }
+    consumer.await.unwrap().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs LIVE_AMQP_URL"]
+async fn cancelling_an_idle_consumer_returns_cleanly() {
+    let repo = RabbitMQRepository::new(url());
+    let shutdown = CancellationToken::new();
+    let cancel = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        cancel.cancel();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(5), repo.consume(&fresh_queue(), |_| Ok(()), shutdown))
+        .await
+        .expect("consume returns once cancelled");
+    result.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs LIVE_AMQP_URL"]
+async fn cancelling_mid_message_still_acks_it() {
+    let queue = fresh_queue();
+    let repo = Arc::new(RabbitMQRepository::new(url()));
+    repo.publish(&queue, b"job").await.unwrap();
+
+    let (started, handling) = mpsc::channel::<()>();
+    let (release, gate) = mpsc::channel::<()>();
+    let (started, gate) = (Mutex::new(started), Mutex::new(gate));
+    let shutdown = CancellationToken::new();
+    let consumer = {
+        let (repo, queue, shutdown) = (repo.clone(), queue.clone(), shutdown.clone());
+        tokio::spawn(async move {
+            repo.consume(&queue, move |_| {
+                started.lock().unwrap().send(()).unwrap();
+                let _ = gate.lock().unwrap().recv();
+                Ok(())
+            }, shutdown)
+            .await
+        })
+    };
+
+    tokio::task::spawn_blocking(move || handling.recv_timeout(Duration::from_secs(5)))
+        .await
+        .unwrap()
+        .expect("the message reaches the handler");
+    shutdown.cancel();
+    release.send(()).unwrap();
+    consumer.await.unwrap().unwrap();
+
+    // Acked rather than requeued or dead-lettered
+    assert_eq!(ready_messages(&queue).await, 0);
+    assert_eq!(ready_messages(&RabbitMQRepository::dead_letter_queue(&queue)).await, 0);
+}
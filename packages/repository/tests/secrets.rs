@@ -0,0 +1,28 @@
+//! `validate_secrets` reads the process environment, so every case runs in one test, in order
+
+use repository::repositories::encryption::data::{validate_secrets, DEFAULT_SECRETS};
+
+#[test]
+fn default_secrets_are_refused_only_in_production() {
+    for (var, _) in DEFAULT_SECRETS {
+        std::env::remove_var(var);
+    }
+    let err = validate_secrets(true).unwrap_err();
+    assert_eq!(err.vars.len(), DEFAULT_SECRETS.len());
+    assert!(validate_secrets(false).is_ok());
+
+    // A secret explicitly set to its public default is as bad as an unset one
+    for (var, default) in DEFAULT_SECRETS {
+        std::env::set_var(var, default);
+    }
+    assert!(validate_secrets(true).is_err());
+
+    for (var, _) in DEFAULT_SECRETS {
+        std::env::set_var(var, format!("{}-generated-0123456789", var.to_lowercase()));
+    }
+    std::env::set_var("JWT_SECRET", "");
+    assert_eq!(validate_secrets(true).unwrap_err().vars, vec!["JWT_SECRET"]);
+
+    std::env::set_var("JWT_SECRET", "jwt-generated-0123456789");
+    assert!(validate_secrets(true).is_ok());
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+
+use crate::shared::{
+    data::{AuthAdmin, SuccessResponse},
+    middlewares::auth::require_admin_auth,
+    data::state::AppState,
+    extractors::validated_json::{Validate, ValidatedJson},
+};
+use model::models::admin;
+
+pub mod service;
+use service::{AdminAccountError, AdminAccountService};
+
+pub struct AdminAccountController;
+
+impl AdminAccountController {
+    fn create_service(app_state: &AppState) -> AdminAccountService {
+        AdminAccountService::new(
+            app_state.model.admin.clone(),
+            app_state.repository.encryption.clone(),
+        )
+    }
+
+    pub async fn change_password(
+        State(app_state): State<AppState>,
+        Extension(auth_admin): Extension<AuthAdmin>,
+        ValidatedJson(request): ValidatedJson<admin::AdminChangePasswordRequest>,
+    ) -> Result<impl IntoResponse, AdminAccountError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.change_password(auth_admin.id, request).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/change-password", post(AdminAccountController::change_password))
+        .layer(axum::middleware::from_fn(require_admin_auth))
+}
+
+impl Validate for admin::AdminChangePasswordRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.current_password.is_empty() {
+            return Err("current_password is required".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use model::models::admin::{self as admin, repo::{AdminRepository, AdminRepositoryError, AdminRepositoryTrait}};
+use repository::repositories::encryption::EncryptionRepositoryTrait;
+
+use crate::shared::data::ErrorResponse;
+use crate::shared::utils::password_policy::{PasswordPolicy, PasswordPolicyError};
+
+#[derive(Debug)]
+pub enum AdminAccountError {
+    NotFound(String),
+    InvalidCurrentPassword,
+    PasswordMismatch,
+    WeakPassword(String),
+    PasswordReused,
+    HashFailed,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for AdminAccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AdminAccountError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            AdminAccountError::InvalidCurrentPassword => write!(f, "Current password is invalid"),
+            AdminAccountError::PasswordMismatch => write!(f, "Passwords do not match"),
+            AdminAccountError::WeakPassword(msg) => write!(f, "Weak password: {}", msg),
+            AdminAccountError::PasswordReused => write!(f, "New password must differ from the current one"),
+            AdminAccountError::HashFailed => write!(f, "Failed to hash password"),
+            AdminAccountError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AdminAccountError {}
+
+impl IntoResponse for AdminAccountError {
+    fn into_response(self) -> Response {
+        match self {
+            AdminAccountError::InvalidCurrentPassword => ErrorResponse::response(StatusCode::UNAUTHORIZED, "INVALID_CURRENT_PASSWORD", "current password is invalid"),
+            AdminAccountError::PasswordMismatch => ErrorResponse::response(StatusCode::BAD_REQUEST, "PASSWORD_MISMATCH", "password are not the same"),
+            AdminAccountError::WeakPassword(msg) => ErrorResponse::response(StatusCode::BAD_REQUEST, "WEAK_PASSWORD", msg),
+            AdminAccountError::PasswordReused => ErrorResponse::response(StatusCode::BAD_REQUEST, "PASSWORD_REUSED", "new password must differ from the current one"),
+            AdminAccountError::NotFound(msg) => ErrorResponse::response(StatusCode::NOT_FOUND, "NOT_FOUND", msg),
+            AdminAccountError::HashFailed => ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "HASH_FAILED", "unable to change password"),
+            AdminAccountError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "admin account database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
+impl From<PasswordPolicyError> for AdminAccountError {
+    fn from(e: PasswordPolicyError) -> Self {
+        match e {
+            PasswordPolicyError::Mismatch => AdminAccountError::PasswordMismatch,
+            PasswordPolicyError::Weak(msg) => AdminAccountError::WeakPassword(msg),
+            PasswordPolicyError::ReusedCurrent => AdminAccountError::PasswordReused,
+            PasswordPolicyError::HashFailed => AdminAccountError::HashFailed,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AdminAccountService {
+    admin_repo: AdminRepository,
+    encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
+}
+
+impl AdminAccountService {
+    pub fn new(admin_repo: AdminRepository, encryption_repo: Arc<dyn EncryptionRepositoryTrait>) -> Self {
+        Self { admin_repo, encryption_repo }
+    }
+
+    pub async fn change_password(
+        &self,
+        admin_id: Uuid,
+        req: admin::AdminChangePasswordRequest,
+    ) -> Result<admin::AdminChangePasswordResponse, AdminAccountError> {
+        PasswordPolicy::check(&req.new_password, &req.confirm_password)?;
+
+        let mut model = self.admin_repo.get_by_id(admin_id).await.map_err(|e| match e {
+            AdminRepositoryError::NotFound(msg) => AdminAccountError::NotFound(msg),
+            e => AdminAccountError::DatabaseError(e.to_string()),
+        })?;
+
+        let is_valid = self
+            .encryption_repo
+            .verify_password(&model.password, &req.current_password)
+            .map_err(|_| AdminAccountError::InvalidCurrentPassword)?;
+        if !is_valid {
+            return Err(AdminAccountError::InvalidCurrentPassword);
+        }
+
+        model.password = PasswordPolicy::new(self.encryption_repo.as_ref()).validate_and_hash(
+            &req.new_password,
+            &req.confirm_password,
+            Some(&model.password),
+        )?;
+
+        // The repository bumps updated_at and keeps created_at as stored
+        let updated = self
+            .admin_repo
+            .update(model)
+            .await
+            .map_err(|e| AdminAccountError::DatabaseError(e.to_string()))?;
+
+        tracing::info!(admin_id = %admin_id, "admin password changed");
+
+        Ok(admin::AdminChangePasswordResponse {
+            id: updated.id.to_string(),
+            message: "password has been changed".to_string(),
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+
+use crate::shared::{
+    data::SuccessResponse,
+    data::state::AppState,
+    extractors::validated_json::{Validate, ValidatedJson},
+};
+use model::models::admin;
+
+mod service;
+use service::{AdminAuthError, AdminAuthService};
+
+pub struct AdminAuthController;
+
+impl AdminAuthController {
+    fn create_service(app_state: &AppState) -> AdminAuthService {
+        AdminAuthService::new(
+            app_state.model.admin.clone(),
+            app_state.repository.encryption.clone(),
+        )
+    }
+
+    pub async fn sign_in(
+        State(app_state): State<AppState>,
+        ValidatedJson(request): ValidatedJson<admin::AdminLoginRequest>,
+    ) -> Result<impl IntoResponse, AdminAuthError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.sign_in(request).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/sign-in", post(AdminAuthController::sign_in))
+}
+
+impl Validate for admin::AdminLoginRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.email_address.trim().is_empty() || self.password.is_empty() {
+            return Err("email_address and password are required".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use std::sync::Arc;
+use model::models::admin::{self as admin, repo::{AdminRepository, AdminRepositoryError, AdminRepositoryTrait}};
+use repository::repositories::encryption::{EncryptionRepositoryExt, EncryptionRepositoryTrait, data::Token};
+
+use crate::shared::data::{AuthAdmin, ErrorResponse};
+use crate::shared::utils::email::normalize_email;
+
+#[derive(Debug)]
+pub enum AdminAuthError {
+    InvalidCredentials,
+    TokenCreationFailed,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for AdminAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AdminAuthError::InvalidCredentials => write!(f, "Invalid credentials"),
+            AdminAuthError::TokenCreationFailed => write!(f, "Failed to create token"),
+            AdminAuthError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AdminAuthError {}
+
+impl IntoResponse for AdminAuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AdminAuthError::InvalidCredentials => ErrorResponse::response(StatusCode::UNAUTHORIZED, "INVALID_CREDENTIALS", "Invalid credentials"),
+            AdminAuthError::TokenCreationFailed => ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "TOKEN_CREATION_FAILED", "Failed to sign in"),
+            AdminAuthError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "admin auth database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AdminAuthService {
+    admin_repo: AdminRepository,
+    encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
+}
+
+impl AdminAuthService {
+    pub fn new(admin_repo: AdminRepository, encryption_repo: Arc<dyn EncryptionRepositoryTrait>) -> Self {
+        Self { admin_repo, encryption_repo }
+    }
+
+    pub async fn sign_in(&self, request: admin::AdminLoginRequest) -> Result<admin::AdminAuthResponse, AdminAuthError> {
+        // Unknown email and wrong password are reported the same way
+        let admin = match self.admin_repo.get_by_email(&normalize_email(&request.email_address)).await {
+            Ok(admin) => admin,
+            Err(AdminRepositoryError::NotFound(_)) => return Err(AdminAuthError::InvalidCredentials),
+            Err(e) => return Err(AdminAuthError::DatabaseError(e.to_string())),
+        };
+
+        let is_valid = self
+            .encryption_repo
+            .verify_password(&admin.password, &request.password)
+            .map_err(|_| AdminAuthError::InvalidCredentials)?;
+        if !is_valid {
+            return Err(AdminAuthError::InvalidCredentials);
+        }
+
+        let auth_admin = AuthAdmin::from_admin(admin);
+        let access_token = self
+            .encryption_repo
+            .create_token(auth_admin.clone(), Token::admin_access_token())
+            .map_err(|_| AdminAuthError::TokenCreationFailed)?;
+
+        Ok(admin::AdminAuthResponse {
+            id: auth_admin.id.to_string(),
+            access_token,
+        })
+    }
+}
@@ -0,0 +1,13 @@
+use axum::Router;
+pub mod account;
+pub mod auth;
+pub mod users;
+
+use crate::shared::data::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .nest("/auth", auth::router())
+        .nest("/users", users::router())
+        .merge(account::router())
+}
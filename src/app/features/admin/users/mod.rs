@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+
+use crate::shared::{
+    data::SuccessResponse,
+    middlewares::{account_status::AccountStatusCache, auth::require_admin_auth},
+    data::state::AppState,
+    extractors::pagination::Pagination,
+};
+use model::models::admin::UserListQuery;
+use uuid::Uuid;
+
+mod service;
+use service::{AdminUsersError, AdminUsersService};
+
+pub struct AdminUsersController;
+
+impl AdminUsersController {
+    fn create_service(app_state: &AppState) -> AdminUsersService {
+        AdminUsersService::new(app_state.model.user.clone(), app_state.model.session.clone())
+    }
+
+    pub async fn list_users(
+        State(app_state): State<AppState>,
+        Pagination(options): Pagination,
+        Query(query): Query<UserListQuery>,
+    ) -> Result<impl IntoResponse, AdminUsersError> {
+        let service = Self::create_service(&app_state);
+        let page = service.list_users(options, query.q).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(page))))
+    }
+
+    pub async fn ban_user(
+        State(app_state): State<AppState>,
+        account_status: Option<Extension<AccountStatusCache>>,
+        Path(user_id): Path<Uuid>,
+    ) -> Result<impl IntoResponse, AdminUsersError> {
+        Self::set_banned(app_state, account_status, user_id, true).await
+    }
+
+    pub async fn unban_user(
+        State(app_state): State<AppState>,
+        account_status: Option<Extension<AccountStatusCache>>,
+        Path(user_id): Path<Uuid>,
+    ) -> Result<impl IntoResponse, AdminUsersError> {
+        Self::set_banned(app_state, account_status, user_id, false).await
+    }
+
+    async fn set_banned(
+        app_state: AppState,
+        account_status: Option<Extension<AccountStatusCache>>,
+        user_id: Uuid,
+        banned: bool,
+    ) -> Result<impl IntoResponse, AdminUsersError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.set_banned(user_id, banned).await?;
+        // Make the change visible to require_user_auth right away instead of after the cache TTL
+        if let Some(Extension(cache)) = account_status {
+            cache.invalidate(user_id);
+        }
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(AdminUsersController::list_users))
+        .route("/:id/ban", post(AdminUsersController::ban_user))
+        .route("/:id/unban", post(AdminUsersController::unban_user))
+        .layer(axum::middleware::from_fn(require_admin_auth))
+}
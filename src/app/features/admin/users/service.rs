@@ -0,0 +1,103 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use chrono::Utc;
+use uuid::Uuid;
+
+use model::models::session::repo::{SessionRepository, SessionRepositoryError, SessionRepositoryTrait};
+use model::models::user::{self as user, repo::{UserRepository, UserRepositoryError, UserRepositoryTrait}};
+use model::shared::PaginationOptions;
+
+use crate::shared::data::ErrorResponse;
+
+#[derive(Debug)]
+pub enum AdminUsersError {
+    NotFound(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for AdminUsersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AdminUsersError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            AdminUsersError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AdminUsersError {}
+
+impl IntoResponse for AdminUsersError {
+    fn into_response(self) -> Response {
+        match self {
+            AdminUsersError::NotFound(msg) => ErrorResponse::response(StatusCode::NOT_FOUND, "NOT_FOUND", msg),
+            AdminUsersError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "admin users database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AdminUsersService {
+    user_repo: UserRepository,
+    session_repo: SessionRepository,
+}
+
+impl AdminUsersService {
+    pub fn new(user_repo: UserRepository, session_repo: SessionRepository) -> Self {
+        Self { user_repo, session_repo }
+    }
+
+    pub async fn list_users(
+        &self,
+        options: PaginationOptions,
+        email_filter: Option<String>,
+    ) -> Result<user::SecureUsersPage, AdminUsersError> {
+        let page = self
+            .user_repo
+            .list(&options, email_filter.as_deref())
+            .await
+            .map_err(|e| AdminUsersError::DatabaseError(e.to_string()))?;
+
+        // SecureUserResponse never carries the password or peripheral tokens
+        Ok(page.map(|entity| user::SecureUserResponse::from(user::User::from(entity))))
+    }
+
+    /// Ban or unban a user; `peripheral_timeout` records when the ban was applied. Banning also
+    /// revokes every active session, so the user's refresh tokens stop working at once
+    pub async fn set_banned(&self, user_id: Uuid, banned: bool) -> Result<user::SecureUserResponse, AdminUsersError> {
+        let mut model = self.user_repo.get_by_id(user_id).await.map_err(|e| match e {
+            UserRepositoryError::NotFound(msg) => AdminUsersError::NotFound(msg),
+            e => AdminUsersError::DatabaseError(e.to_string()),
+        })?;
+
+        model.peripheral_is_banned = banned;
+        model.peripheral_timeout = if banned { Some(Utc::now().into()) } else { None };
+
+        let updated = self
+            .user_repo
+            .update(model)
+            .await
+            .map_err(|e| AdminUsersError::DatabaseError(e.to_string()))?;
+
+        if banned {
+            let sessions = self
+                .session_repo
+                .list_active_by_user(user_id)
+                .await
+                .map_err(|e| AdminUsersError::DatabaseError(e.to_string()))?;
+            for session in &sessions {
+                // A session revoked in the meantime (sign-out, refresh) is already what we want
+                match self.session_repo.revoke(session.jti).await {
+                    Ok(()) | Err(SessionRepositoryError::NotFound(_)) => {}
+                    Err(e) => return Err(AdminUsersError::DatabaseError(e.to_string())),
+                }
+            }
+            tracing::info!(user_id = %user_id, revoked = sessions.len(), "revoked sessions of banned user");
+        }
+
+        tracing::info!(user_id = %user_id, banned, "admin updated user ban status");
+
+        Ok(user::SecureUserResponse::from(user::User::from(updated)))
+    }
+}
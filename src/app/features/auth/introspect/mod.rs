@@ -0,0 +1,52 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+
+use crate::shared::{
+    data::SuccessResponse,
+    data::state::AppState,
+    extractors::validated_json::{Validate, ValidatedJson},
+};
+
+pub mod service;
+use service::{IntrospectError, IntrospectRequest, IntrospectService};
+
+pub struct IntrospectController;
+
+impl IntrospectController {
+    fn create_service(app_state: &AppState) -> IntrospectService {
+        IntrospectService::new(
+            app_state.repository.encryption.clone(),
+            app_state.model.session.clone(),
+        )
+    }
+
+    /// RFC 7662-style introspection: invalid, expired or revoked tokens come back as
+    /// `{ "active": false }` with a 200 rather than a 401
+    pub async fn introspect(
+        State(app_state): State<AppState>,
+        ValidatedJson(req): ValidatedJson<IntrospectRequest>,
+    ) -> Result<impl IntoResponse, IntrospectError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.introspect(req).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::<AppState>::new()
+        .route("/", post(IntrospectController::introspect))
+}
+
+impl Validate for IntrospectRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.token.trim().is_empty() {
+            return Err("token is required".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use std::sync::Arc;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use model::models::session::repo::{SessionRepository, SessionRepositoryError, SessionRepositoryTrait};
+use repository::repositories::encryption::{EncryptionRepositoryTrait, data::{Claims, Token}};
+use crate::shared::data::{AuthUser, ErrorResponse};
+
+#[derive(Debug)]
+pub enum IntrospectError {
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for IntrospectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            IntrospectError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for IntrospectError {}
+
+impl IntoResponse for IntrospectError {
+    fn into_response(self) -> Response {
+        match self {
+            IntrospectError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "introspect database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
+/// User token types a gateway can ask about
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntrospectTokenType {
+    #[default]
+    Access,
+    Refresh,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IntrospectRequest {
+    pub token: String,
+    #[serde(rename = "type", default)]
+    pub token_type: IntrospectTokenType,
+}
+
+/// Only `active` is present for inactive tokens, so nothing about them leaks
+#[derive(Debug, Serialize)]
+pub struct IntrospectResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_seconds: Option<i64>,
+}
+
+impl IntrospectResponse {
+    fn inactive() -> Self {
+        Self { active: false, sub: None, exp: None, remaining_seconds: None }
+    }
+}
+
+#[derive(Clone)]
+pub struct IntrospectService {
+    encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
+    session_repo: SessionRepository,
+}
+
+impl IntrospectService {
+    pub fn new(encryption_repo: Arc<dyn EncryptionRepositoryTrait>, session_repo: SessionRepository) -> Self {
+        Self { encryption_repo, session_repo }
+    }
+
+    pub async fn introspect(&self, req: IntrospectRequest) -> Result<IntrospectResponse, IntrospectError> {
+        let params = match req.token_type {
+            IntrospectTokenType::Access => Token::user_access_token(),
+            IntrospectTokenType::Refresh => Token::user_refresh_token(),
+        };
+
+        let Ok(value) = self.encryption_repo.decode_token(&req.token, params) else {
+            return Ok(IntrospectResponse::inactive());
+        };
+        let Ok(claims) = serde_json::from_value::<Claims>(value) else {
+            return Ok(IntrospectResponse::inactive());
+        };
+
+        // Refresh tokens also have to belong to a session that hasn't been revoked
+        if let IntrospectTokenType::Refresh = req.token_type {
+            let Some(jti) = claims.jti.as_deref().and_then(|jti| Uuid::parse_str(jti).ok()) else {
+                return Ok(IntrospectResponse::inactive());
+            };
+            match self.session_repo.get_by_jti(jti).await {
+                Ok(session) if session.is_active() => {}
+                Ok(_) | Err(SessionRepositoryError::NotFound(_)) => return Ok(IntrospectResponse::inactive()),
+                Err(e) => return Err(IntrospectError::DatabaseError(e.to_string())),
+            }
+        }
+
+        let exp = claims.exp;
+        let Ok(auth_user) = AuthUser::from_claims(claims) else {
+            return Ok(IntrospectResponse::inactive());
+        };
+
+        Ok(IntrospectResponse {
+            active: true,
+            sub: Some(auth_user.id.to_string()),
+            exp: Some(exp),
+            remaining_seconds: Some((exp - Utc::now().timestamp()).max(0)),
+        })
+    }
+}
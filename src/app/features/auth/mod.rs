@@ -0,0 +1,10 @@
+use axum::Router;
+pub mod introspect;
+
+use crate::shared::data::state::AppState;
+
+/// Token endpoints for other services rather than end users
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .nest("/introspect", introspect::router())
+}
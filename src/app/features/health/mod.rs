@@ -0,0 +1,41 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+
+use crate::shared::{
+    data::SuccessResponse,
+    data::state::AppState,
+};
+
+pub mod service;
+use service::{HealthError, HealthService};
+
+pub struct HealthController;
+
+impl HealthController {
+    fn create_service(app_state: &AppState) -> HealthService {
+        HealthService::new(
+            app_state.model.db.clone(),
+            app_state.started_at,
+        )
+    }
+
+    /// Database reachability, migration status and uptime; 503 when the database can't be reached
+    pub async fn details(
+        State(app_state): State<AppState>,
+    ) -> Result<impl IntoResponse, HealthError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.details().await?;
+        let status = if resp.database.reachable { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+        Ok((status, Json(SuccessResponse::new(resp))))
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::<AppState>::new()
+        .route("/details", get(HealthController::details))
+}
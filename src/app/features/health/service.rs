@@ -0,0 +1,103 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use std::time::Instant;
+
+use model::migration::{Migrator, MigratorTrait};
+use crate::shared::data::ErrorResponse;
+
+#[derive(Debug)]
+pub enum HealthError {
+    MigrationStatus(String),
+}
+
+impl std::fmt::Display for HealthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HealthError::MigrationStatus(msg) => write!(f, "Migration status unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for HealthError {}
+
+impl IntoResponse for HealthError {
+    fn into_response(self) -> Response {
+        match self {
+            HealthError::MigrationStatus(msg) => {
+                tracing::error!(error = %msg, "health migration status error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "MIGRATION_STATUS_FAILED", "unable to read migration status")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseHealth {
+    pub reachable: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationHealth {
+    pub applied: usize,
+    pub applied_names: Vec<String>,
+    /// Non-empty when code was deployed without migrating the schema
+    pub pending_names: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthDetailsResponse {
+    pub database: DatabaseHealth,
+    /// Absent when the database is unreachable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub migrations: Option<MigrationHealth>,
+    pub uptime_seconds: u64,
+}
+
+#[derive(Clone)]
+pub struct HealthService {
+    db: DatabaseConnection,
+    started_at: Instant,
+}
+
+impl HealthService {
+    pub fn new(db: DatabaseConnection, started_at: Instant) -> Self {
+        Self { db, started_at }
+    }
+
+    pub async fn details(&self) -> Result<HealthDetailsResponse, HealthError> {
+        let uptime_seconds = self.started_at.elapsed().as_secs();
+
+        if let Err(e) = self.db.ping().await {
+            tracing::warn!(error = %e, "health check: database unreachable");
+            return Ok(HealthDetailsResponse {
+                database: DatabaseHealth { reachable: false },
+                migrations: None,
+                uptime_seconds,
+            });
+        }
+
+        let applied_names: Vec<String> = Migrator::get_applied_migrations(&self.db)
+            .await
+            .map_err(|e| HealthError::MigrationStatus(e.to_string()))?
+            .iter()
+            .map(|m| m.name().to_string())
+            .collect();
+        let pending_names = Migrator::get_pending_migrations(&self.db)
+            .await
+            .map_err(|e| HealthError::MigrationStatus(e.to_string()))?
+            .iter()
+            .map(|m| m.name().to_string())
+            .collect();
+
+        Ok(HealthDetailsResponse {
+            database: DatabaseHealth { reachable: true },
+            migrations: Some(MigrationHealth {
+                applied: applied_names.len(),
+                applied_names,
+                pending_names,
+            }),
+            uptime_seconds,
+        })
+    }
+}
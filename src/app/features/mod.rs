@@ -1,5 +1,7 @@
 use axum::Router;
-// pub mod admin;
+pub mod admin;
+pub mod auth;
+pub mod health;
 pub mod user;
 
 use axum::middleware;
@@ -10,7 +12,11 @@ use crate::shared::data::state::AppState;
 pub fn router() -> Router<AppState> {
     Router::new()
         .nest("/user", user::router())
+        .nest("/admin", admin::router())
+        .nest("/auth", auth::router())
+        .nest("/health", health::router())
         .layer(middleware::from_fn(recovery::recover))
-        .layer(middleware::from_fn(request_id::set_request_id))
+        // request_id runs first so the logger can read the id it assigns
         .layer(middleware::from_fn(logging::structured_logger))
+        .layer(middleware::from_fn(request_id::set_request_id))
 }
\ No newline at end of file
@@ -1,20 +1,26 @@
 use axum::{
     extract::{State, Json, Extension},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::post,
     Router,
 };
+use repository::repositories::encryption::data::Token;
 use model::models::user;
+use std::sync::Arc;
 use crate::shared::{
-    data::{ErrorResponse, SuccessResponse},
-    middlewares::auth::require_refresh_auth,
+    data::SuccessResponse,
+    middlewares::auth::{decode_refresh_token, require_refresh_auth},
     data::state::AppState,
 };
-use crate::shared::data::{AuthUser};
+use crate::shared::data::RefreshToken;
+use crate::shared::extractors::client_context::ClientContext;
+use crate::shared::extractors::validated_json::{validate_email, Validate, ValidatedJson};
+use crate::shared::utils::refresh_cookie;
 
 pub mod service;
 pub mod password;
+pub mod session;
 
 use service::{AuthError, AuthService};
 
@@ -25,103 +31,74 @@ impl AuthController {
     /// Creates a new AuthService instance from AppState
     fn create_auth_service(app_state: &AppState) -> AuthService {
         AuthService::new(
-            app_state.model.user.clone(),
-            (*app_state.repository.encryption).clone(),
+            Arc::new(app_state.model.user.clone()),
+            app_state.model.session.clone(),
+            app_state.repository.encryption.clone(),
         )
     }
 
+    /// Token pair as JSON, or with `REFRESH_TOKEN_COOKIE` on, the access token as JSON and the
+    /// refresh token as an HttpOnly cookie
+    fn token_response(status: StatusCode, response: user::AuthUserResponse) -> Response {
+        if !refresh_cookie::enabled() {
+            return (status, Json(SuccessResponse::new(response))).into_response();
+        }
+
+        let max_age = Token::user_refresh_token().expiry_seconds;
+        let Some(cookie) = refresh_cookie::set_cookie(&response.refresh_token, max_age) else {
+            return AuthError::TokenCreationFailed.into_response();
+        };
+        let body = user::AccessTokenResponse::from(response);
+        (status, [(header::SET_COOKIE, cookie)], Json(SuccessResponse::new(body))).into_response()
+    }
+
     /// Handle user registration
     pub async fn sign_up(
         State(app_state): State<AppState>,
-        Json(request): Json<user::RegisterRequest>,
-    ) -> impl IntoResponse {
+        client: ClientContext,
+        ValidatedJson(request): ValidatedJson<user::RegisterRequest>,
+    ) -> Result<impl IntoResponse, AuthError> {
         let auth_service = Self::create_auth_service(&app_state);
-        
-        match auth_service.sign_up(request).await {
-            Ok(response) => {
-                (StatusCode::CREATED, Json(SuccessResponse::new(response))).into_response()
-            }
-            Err(AuthError::EmailAlreadyExists) => (
-                StatusCode::CONFLICT,
-                Json(ErrorResponse::new("Email address already exists".to_string())),
-            ).into_response(),
-            Err(AuthError::PasswordInvalid) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("Password is invalid".to_string())),
-            ).into_response(),
-            Err(AuthError::DatabaseError(msg)) => {
-                tracing::error!(error = %msg, "auth sign_up database error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!("Database error: {}", msg))),
-                )
-                    .into_response()
-            }
-            Err(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::new("Failed to create user".to_string())),
-            ).into_response(),
-        }
+        let response = auth_service.sign_up(request, &client).await?;
+        Ok(Self::token_response(StatusCode::CREATED, response))
     }
 
     /// Handle user login
     pub async fn sign_in(
         State(app_state): State<AppState>,
-        Json(request): Json<user::LoginRequest>,
-    ) -> impl IntoResponse {
+        client: ClientContext,
+        ValidatedJson(request): ValidatedJson<user::LoginRequest>,
+    ) -> Result<impl IntoResponse, AuthError> {
         let auth_service = Self::create_auth_service(&app_state);
-        
-        match auth_service.sign_in(request).await {
-            Ok(response) => {
-                (StatusCode::OK, Json(SuccessResponse::new(response))).into_response()
-            }
-            Err(AuthError::InvalidCredentials) => (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse::new("Invalid credentials".to_string())),
-            ).into_response(),
-            Err(AuthError::UserNotFound) => (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse::new("User not found".to_string())),
-            ).into_response(),
-            Err(AuthError::DatabaseError(msg)) => {
-                tracing::error!(error = %msg, "auth sign_in database error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!("Database error: {}", msg))),
-                )
-                    .into_response()
-            }
-            Err(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::new("Failed to sign in".to_string())),
-            ).into_response(),
-        }
+        let response = auth_service.sign_in(request, &client).await?;
+        Ok(Self::token_response(StatusCode::OK, response))
     }
 
-    /// Handle token refresh
-    /// TODO: Implement proper JWT token extraction and validation
+    /// Handle token refresh; the token comes from the `Authorization` header, the JSON body or the refresh cookie
     pub async fn refresh_token(
         State(app_state): State<AppState>,
-        Extension(auth_user): Extension<AuthUser>,
-    ) -> impl IntoResponse {
-        let auth_service = Self::create_auth_service(&app_state);
+        header_token: Option<Extension<RefreshToken>>,
+        client: ClientContext,
+        headers: HeaderMap,
+        body: Option<Json<user::RefreshTokenRequest>>,
+    ) -> Result<impl IntoResponse, AuthError> {
+        let raw_token = body
+            .map(|Json(body)| body.refresh_token)
+            .or_else(|| refresh_cookie::from_headers(&headers));
+        let refresh = match (header_token, raw_token) {
+            (Some(Extension(refresh)), _) => refresh,
+            (None, Some(token)) => decode_refresh_token(app_state.repository.encryption.as_ref(), &token)
+                .map_err(|msg| AuthError::InvalidToken(msg.to_string()))?,
+            (None, None) => return Err(AuthError::InvalidToken("missing refresh token".to_string())),
+        };
 
-        match auth_service.refresh_token(auth_user).await {
-            Ok(response) => (StatusCode::OK, Json(SuccessResponse::new(response))).into_response(),
-            Err(AuthError::TokenCreationFailed) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::new("Failed to create token".to_string())),
-            )
-                .into_response(),
-            Err(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::new("Failed to refresh token".to_string())),
-            )
-                .into_response(),
-        }
+        let auth_service = Self::create_auth_service(&app_state);
+        let response = auth_service.refresh_token(refresh, &client).await?;
+        Ok(Self::token_response(StatusCode::OK, response))
     }
 }
 
+
 /// Create the authentication router with all auth endpoints
 pub fn router() -> Router<AppState> {
     let refresh_router = Router::new()
@@ -132,5 +109,27 @@ pub fn router() -> Router<AppState> {
         .route("/sign-up", post(AuthController::sign_up))
         .route("/sign-in", post(AuthController::sign_in))
         .merge(refresh_router)
+        .nest("/sessions", session::router())
         .nest("/password", password::router())
-}
\ No newline at end of file
+}
+
+impl Validate for user::RegisterRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.first_name.trim().is_empty() || self.second_name.trim().is_empty() {
+            return Err("first_name and second_name are required".to_string());
+        }
+        if self.password.is_empty() {
+            return Err("password is required".to_string());
+        }
+        validate_email(&self.email_address)
+    }
+}
+
+impl Validate for user::LoginRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.email_address.trim().is_empty() || self.password.is_empty() {
+            return Err("email_address and password are required".to_string());
+        }
+        Ok(())
+    }
+}
@@ -7,11 +7,14 @@ use axum::{
 };
 
 use crate::shared::{
-    data::{AuthUser, ErrorResponse, SuccessResponse},
+    data::{AuthUser, SuccessResponse},
     middlewares::auth::require_user_auth,
     data::state::AppState,
+    extractors::client_context::ClientContext,
+    extractors::validated_json::{validate_email, Validate, ValidatedJson},
 };
 use model::models::user;
+use std::sync::Arc;
 use super::password::service::{PasswordService, PasswordError};
 
 pub mod service;
@@ -21,122 +24,46 @@ pub struct PasswordController;
 impl PasswordController {
     fn create_service(app_state: &AppState) -> PasswordService {
         PasswordService::new(
-            app_state.model.user.clone(),
-            (*app_state.repository.encryption).clone(),
+            Arc::new(app_state.model.user.clone()),
+            app_state.repository.encryption.clone(),
+            app_state.repository.queue.clone(),
+            app_state.repository.queue_names.emails.clone(),
         )
     }
 
     pub async fn send_reset_code(
         State(app_state): State<AppState>,
-        Json(request): Json<user::SendResetCodeRequest>,
-    ) -> impl IntoResponse {
+        client: ClientContext,
+        ValidatedJson(request): ValidatedJson<user::SendResetCodeRequest>,
+    ) -> Result<impl IntoResponse, PasswordError> {
         let service = Self::create_service(&app_state);
-        match service.send_reset_code(request).await {
-            Ok(resp) => (StatusCode::OK, Json(SuccessResponse::new(resp))).into_response(),
-            Err(PasswordError::UserNotFound) => (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse::new("email address was not found".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::DatabaseError(msg)) => {
-                tracing::error!(error = %msg, "password send_reset_code database error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!("Database error: {}", msg))),
-                )
-                    .into_response()
-            }
-            Err(_) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("unable to send verification code".to_string())),
-            )
-                .into_response(),
-        }
+        let resp = service.send_reset_code(request, &client).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
     }
 
     pub async fn verify_code(
         State(app_state): State<AppState>,
-        Json(request): Json<user::VerifyResetCodeRequest>,
-    ) -> impl IntoResponse {
+        client: ClientContext,
+        ValidatedJson(request): ValidatedJson<user::VerifyResetCodeRequest>,
+    ) -> Result<impl IntoResponse, PasswordError> {
         let service = Self::create_service(&app_state);
-        match service.verify_code(request).await {
-            Ok(resp) => (StatusCode::OK, Json(SuccessResponse::new(resp))).into_response(),
-            Err(PasswordError::UserNotFound) => (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse::new("email is not registered with us".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::InvalidCode) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("invalid code".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::CodeExpired) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("code expired".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::TokenCreationFailed) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("unable to verify code".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::DatabaseError(msg)) => {
-                tracing::error!(error = %msg, "password verify_code database error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!("Database error: {}", msg))),
-                )
-                    .into_response()
-            }
-            Err(_) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("unable to verify code".to_string())),
-            )
-                .into_response(),
-        }
+        let resp = service.verify_code(request, &client).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
     }
 
     pub async fn reset_password(
         State(app_state): State<AppState>,
         Extension(auth_user): Extension<AuthUser>,
-        Json(request): Json<user::ResetPasswordRequest>,
-    ) -> impl IntoResponse {
+        client: ClientContext,
+        ValidatedJson(request): ValidatedJson<user::ResetPasswordRequest>,
+    ) -> Result<impl IntoResponse, PasswordError> {
         let service = Self::create_service(&app_state);
-        match service.reset_password(auth_user.id, request).await {
-            Ok(resp) => (StatusCode::OK, Json(SuccessResponse::new(resp))).into_response(),
-            Err(PasswordError::PasswordMismatch) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("password are not the same".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::CodeExpired) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("code expired".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::UserNotFound) => (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse::new("email is not registered with us".to_string())),
-            )
-                .into_response(),
-            Err(PasswordError::DatabaseError(msg)) => {
-                tracing::error!(error = %msg, "password reset_password database error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!("Database error: {}", msg))),
-                )
-                    .into_response()
-            }
-            Err(_) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("unable to reset password".to_string())),
-            )
-                .into_response(),
-        }
+        let resp = service.reset_password(auth_user.id, request, &client).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
     }
 }
 
+
 pub fn router() -> Router<AppState> {
     let public = Router::new()
         .route("/send-reset-code", post(PasswordController::send_reset_code))
@@ -147,4 +74,28 @@ pub fn router() -> Router<AppState> {
         .layer(axum::middleware::from_fn(require_user_auth));
 
     Router::new().nest("/", public).nest("/", protected)
-}
\ No newline at end of file
+}
+
+impl Validate for user::SendResetCodeRequest {
+    fn validate(&self) -> Result<(), String> {
+        validate_email(&self.email_address)
+    }
+}
+
+impl Validate for user::VerifyResetCodeRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.auth_code.trim().is_empty() {
+            return Err("auth_code is required".to_string());
+        }
+        validate_email(&self.email_address)
+    }
+}
+
+impl Validate for user::ResetPasswordRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.password.is_empty() {
+            return Err("password is required".to_string());
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,35 @@
-use chrono::{Duration, Utc};
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use chrono::Utc;
 use uuid::Uuid;
 
-use model::models::user::{self as user, repo::UserRepositoryTrait};
-use model::models::user::repo::UserRepository;
-use repository::repositories::encryption::{EncryptionRepository, EncryptionRepositoryTrait, data::Token};
-use crate::shared::data::AuthUser;
+use model::models::user::{self as user, repo::{UserRepositoryError, UserRepositoryTrait}};
+use repository::repositories::encryption::{constant_time_eq, EncryptionRepositoryExt, EncryptionRepositoryTrait, data::Token};
+use repository::repositories::mailer::templates::{CodeContext, EmailTemplate};
+use model::cleanup::RESET_CODE_LIFETIME;
+use model::jobs::{EmailJob, JobEnvelope};
+use repository::repositories::queue::QueueRepositoryTrait;
+use std::sync::Arc;
+use crate::shared::data::{AuthUser, ErrorResponse};
+use crate::shared::extractors::client_context::ClientContext;
+use crate::shared::utils::audit::{AuditEvent, AuditEventType};
+use crate::shared::utils::email::normalize_email;
+use crate::shared::utils::password_policy::{PasswordPolicy, PasswordPolicyError};
+
+/// Wrong codes allowed before verification is locked until a new code is sent
+const MAX_CODE_ATTEMPTS: i32 = 5;
+
+/// Returned by `send_reset_code` for known and unknown addresses alike
+const RESET_CODE_SENT_MESSAGE: &str = "if the account exists, a code has been sent to this email";
 
 #[derive(Debug)]
 pub enum PasswordError {
     UserNotFound,
     CodeExpired,
     InvalidCode,
+    CodeLocked,
     PasswordMismatch,
+    WeakPassword(String),
+    PasswordReused,
     TokenCreationFailed,
     DatabaseError(String),
 }
@@ -22,7 +40,10 @@ impl std::fmt::Display for PasswordError {
             PasswordError::UserNotFound => write!(f, "User not found"),
             PasswordError::CodeExpired => write!(f, "Code expired"),
             PasswordError::InvalidCode => write!(f, "Invalid code"),
+            PasswordError::CodeLocked => write!(f, "Too many invalid attempts"),
             PasswordError::PasswordMismatch => write!(f, "Passwords do not match"),
+            PasswordError::WeakPassword(msg) => write!(f, "Weak password: {}", msg),
+            PasswordError::PasswordReused => write!(f, "New password must differ from the current one"),
             PasswordError::TokenCreationFailed => write!(f, "Failed to create token"),
             PasswordError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
         }
@@ -31,31 +52,81 @@ impl std::fmt::Display for PasswordError {
 
 impl std::error::Error for PasswordError {}
 
+impl From<PasswordPolicyError> for PasswordError {
+    fn from(e: PasswordPolicyError) -> Self {
+        match e {
+            PasswordPolicyError::Mismatch => PasswordError::PasswordMismatch,
+            PasswordPolicyError::Weak(msg) => PasswordError::WeakPassword(msg),
+            PasswordPolicyError::ReusedCurrent => PasswordError::PasswordReused,
+            PasswordPolicyError::HashFailed => PasswordError::DatabaseError("password hash failed".to_string()),
+        }
+    }
+}
+
+impl IntoResponse for PasswordError {
+    fn into_response(self) -> Response {
+        match self {
+            PasswordError::UserNotFound => ErrorResponse::response(StatusCode::NOT_FOUND, "USER_NOT_FOUND", "email is not registered with us"),
+            PasswordError::CodeExpired => ErrorResponse::response(StatusCode::BAD_REQUEST, "CODE_EXPIRED", "code expired"),
+            PasswordError::InvalidCode => ErrorResponse::response(StatusCode::BAD_REQUEST, "INVALID_CODE", "invalid code"),
+            PasswordError::CodeLocked => ErrorResponse::response(StatusCode::TOO_MANY_REQUESTS, "CODE_LOCKED", "too many invalid attempts, request a new code"),
+            PasswordError::PasswordMismatch => ErrorResponse::response(StatusCode::BAD_REQUEST, "PASSWORD_MISMATCH", "password are not the same"),
+            PasswordError::WeakPassword(msg) => ErrorResponse::response(StatusCode::BAD_REQUEST, "WEAK_PASSWORD", msg),
+            PasswordError::PasswordReused => ErrorResponse::response(StatusCode::BAD_REQUEST, "PASSWORD_REUSED", "new password must differ from the current one"),
+            PasswordError::TokenCreationFailed => ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "TOKEN_CREATION_FAILED", "unable to verify code"),
+            PasswordError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "password database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PasswordService {
-    user_repo: UserRepository,
-    encryption_repo: EncryptionRepository,
+    user_repo: Arc<dyn UserRepositoryTrait>,
+    encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
+    queue_repo: Arc<dyn QueueRepositoryTrait>,
+    email_queue: String,
 }
 
 impl PasswordService {
-    pub fn new(user_repo: UserRepository, encryption_repo: EncryptionRepository) -> Self {
-        Self { user_repo, encryption_repo }
+    pub fn new(
+        user_repo: Arc<dyn UserRepositoryTrait>,
+        encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
+        queue_repo: Arc<dyn QueueRepositoryTrait>,
+        email_queue: String,
+    ) -> Self {
+        Self { user_repo, encryption_repo, queue_repo, email_queue }
     }
 
-    // Send reset code to the email address, storing it and timeout on the user
+    // Send reset code to the email address, storing it and timeout on the user. The response is the same
+    // whether or not the address is registered so it can't be used to enumerate accounts
     pub async fn send_reset_code(
         &self,
         request: user::SendResetCodeRequest,
+        client: &ClientContext,
     ) -> Result<user::PasswordAuthResponse, PasswordError> {
-        let mut model = self
-            .user_repo
-            .get_by_email(&request.email_address.to_lowercase())
-            .await
-            .map_err(|_| PasswordError::UserNotFound)?;
+        let email_address = normalize_email(&request.email_address);
+        let response = user::PasswordAuthResponse {
+            email_address: email_address.clone(),
+            message: RESET_CODE_SENT_MESSAGE.to_string(),
+        };
+
+        let mut model = match self.user_repo.get_by_email(&email_address).await {
+            Ok(model) => model,
+            Err(UserRepositoryError::NotFound(_)) => {
+                tracing::info!("reset code requested for an unregistered email");
+                AuditEvent::failure(AuditEventType::PasswordResetRequested, "unknown email").client(client).record();
+                return Ok(response);
+            }
+            Err(e) => return Err(PasswordError::DatabaseError(e.to_string())),
+        };
 
         let code = self.encryption_repo.create_code(6);
-        model.peripheral_authentication_code = Some(code);
+        model.peripheral_authentication_code = Some(code.clone());
         model.peripheral_timeout = Some(Utc::now().into());
+        model.peripheral_failed_code_attempts = 0;
 
         let updated = self
             .user_repo
@@ -63,39 +134,84 @@ impl PasswordService {
             .await
             .map_err(|e| PasswordError::DatabaseError(e.to_string()))?;
 
-        Ok(user::PasswordAuthResponse {
-            email_address: updated.personal_email_address,
-            message: "code has been sent to this email".to_string(),
-        })
+        let job = EmailJob {
+            to: updated.personal_email_address.clone(),
+            template: EmailTemplate::ResetCode.as_str().to_string(),
+            context: CodeContext {
+                first_name: updated.personal_first_name.clone(),
+                code,
+                expires_in: "7 days".to_string(),
+            }
+            .into(),
+        };
+        // The code is already stored, so a broker outage shouldn't fail the request; the user can ask again
+        let published = match JobEnvelope::new(job, client.request_id.clone()).to_bytes() {
+            Ok(payload) => self.queue_repo.publish(&self.email_queue, &payload).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = published {
+            tracing::warn!(user_id = %updated.id, error = %e, "reset code email not queued, dispatch deferred");
+        }
+
+        AuditEvent::success(AuditEventType::PasswordResetRequested).user(updated.id).client(client).record();
+
+        Ok(response)
     }
 
     // Verify code and return a short-lived token
     pub async fn verify_code(
         &self,
         req: user::VerifyResetCodeRequest,
+        client: &ClientContext,
     ) -> Result<user::VerifyCodeResponse, PasswordError> {
         let model = self
             .user_repo
-            .get_by_email(&req.email_address.to_lowercase())
+            .get_by_email(&normalize_email(&req.email_address))
             .await
-            .map_err(|_| PasswordError::UserNotFound)?;
+            .map_err(|_| {
+                AuditEvent::failure(AuditEventType::PasswordResetCodeVerified, "unknown email").client(client).record();
+                PasswordError::UserNotFound
+            })?;
+
+        // Every guess, right or wrong, spends an attempt before the code is compared. The counter is
+        // bumped atomically, so parallel guesses can't get more than MAX_CODE_ATTEMPTS comparisons
+        let attempts = self
+            .user_repo
+            .take_code_attempt(model.id, MAX_CODE_ATTEMPTS)
+            .await
+            .map_err(|e| PasswordError::DatabaseError(e.to_string()))?;
+        let Some(attempts) = attempts else {
+            AuditEvent::failure(AuditEventType::PasswordResetCodeVerified, "locked").user(model.id).client(client).record();
+            return Err(PasswordError::CodeLocked);
+        };
 
         // Check code matches
-        match &model.peripheral_authentication_code {
-            Some(stored) if stored == &req.auth_code => {}
-            _ => return Err(PasswordError::InvalidCode),
+        let matches = match &model.peripheral_authentication_code {
+            Some(stored) => constant_time_eq(stored, &req.auth_code),
+            None => false,
+        };
+        if !matches {
+            AuditEvent::failure(AuditEventType::PasswordResetCodeVerified, "invalid code").user(model.id).client(client).record();
+            return Err(if attempts >= MAX_CODE_ATTEMPTS { PasswordError::CodeLocked } else { PasswordError::InvalidCode });
         }
 
         // Check not expired (older than 7 days considered expired)
         let timeout_utc = model
             .peripheral_timeout
-            .map(|t| chrono::DateTime::<Utc>::from(t))
+            .map(chrono::DateTime::<Utc>::from)
             .ok_or(PasswordError::CodeExpired)?;
 
-        if Utc::now() - timeout_utc > Duration::days(7) {
+        if Utc::now() - timeout_utc > RESET_CODE_LIFETIME {
             return Err(PasswordError::CodeExpired);
         }
 
+        self.user_repo
+            .reset_code_attempts(model.id)
+            .await
+            .map_err(|e| PasswordError::DatabaseError(e.to_string()))?;
+
+        AuditEvent::success(AuditEventType::PasswordResetCodeVerified).user(model.id).client(client).record();
+
         // Build auth payload and create token
         let auth_user = AuthUser {
             id: model.id,
@@ -119,9 +235,15 @@ impl PasswordService {
         &self,
         auth_user_id: Uuid,
         req: user::ResetPasswordRequest,
+        client: &ClientContext,
     ) -> Result<user::PasswordAuthResponse, PasswordError> {
-        if req.password != req.confirm_password {
-            return Err(PasswordError::PasswordMismatch);
+        if let Err(e) = PasswordPolicy::check(&req.password, &req.confirm_password) {
+            let reason = match e {
+                PasswordPolicyError::Mismatch => "password mismatch",
+                _ => "weak password",
+            };
+            AuditEvent::failure(AuditEventType::PasswordReset, reason).user(auth_user_id).client(client).record();
+            return Err(e.into());
         }
 
         let mut model = self
@@ -133,19 +255,17 @@ impl PasswordService {
         // Check not expired (older than 7 days considered expired)
         let timeout_utc = model
             .peripheral_timeout
-            .map(|t| chrono::DateTime::<Utc>::from(t))
+            .map(chrono::DateTime::<Utc>::from)
             .ok_or(PasswordError::CodeExpired)?;
-        if Utc::now() - timeout_utc > Duration::days(7) {
+        if Utc::now() - timeout_utc > RESET_CODE_LIFETIME {
             return Err(PasswordError::CodeExpired);
         }
 
-        // Hash and update password
-        let hashed = self
-            .encryption_repo
-            .hash_password(&req.password)
-            .map_err(|_| PasswordError::DatabaseError("password hash failed".to_string()))?;
-
-        model.password = hashed;
+        model.password = PasswordPolicy::new(self.encryption_repo.as_ref()).validate_and_hash(
+            &req.password,
+            &req.confirm_password,
+            Some(&model.password),
+        )?;
 
         let updated = self
             .user_repo
@@ -153,9 +273,32 @@ impl PasswordService {
             .await
             .map_err(|e| PasswordError::DatabaseError(e.to_string()))?;
 
+        AuditEvent::success(AuditEventType::PasswordReset).user(updated.id).client(client).record();
+
         Ok(user::PasswordAuthResponse {
             email_address: updated.personal_email_address,
             message: "code has been sent to this email".to_string(),
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn password_errors_map_to_their_status_and_code() {
+        ErrorResponse::assert_mappings([
+            (PasswordError::UserNotFound, StatusCode::NOT_FOUND, "USER_NOT_FOUND"),
+            (PasswordError::CodeExpired, StatusCode::BAD_REQUEST, "CODE_EXPIRED"),
+            (PasswordError::InvalidCode, StatusCode::BAD_REQUEST, "INVALID_CODE"),
+            (PasswordError::CodeLocked, StatusCode::TOO_MANY_REQUESTS, "CODE_LOCKED"),
+            (PasswordError::PasswordMismatch, StatusCode::BAD_REQUEST, "PASSWORD_MISMATCH"),
+            (PasswordError::WeakPassword("too short".into()), StatusCode::BAD_REQUEST, "WEAK_PASSWORD"),
+            (PasswordError::PasswordReused, StatusCode::BAD_REQUEST, "PASSWORD_REUSED"),
+            (PasswordError::TokenCreationFailed, StatusCode::INTERNAL_SERVER_ERROR, "TOKEN_CREATION_FAILED"),
+            (PasswordError::DatabaseError("gone".into()), StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR"),
+        ])
+        .await;
+    }
+}
@@ -1,16 +1,44 @@
+use axum::{http::{header, HeaderValue, StatusCode}, response::{IntoResponse, Response}};
+use std::sync::{Arc, OnceLock};
 use uuid::Uuid;
-use chrono::Utc;
-use model::models::{user::repo::UserRepositoryTrait};
-use model::models::user::{repo::UserRepository, model as user, entity as user_entity};
-use repository::repositories::{encryption::{EncryptionRepository, EncryptionRepositoryTrait, data::Token}};
-use crate::shared::data::{AuthUser};
+use chrono::{Duration, Utc};
+use model::models::user::repo::{UserRepositoryError, UserRepositoryTrait};
+use model::models::user::{model as user, entity as user_entity};
+use model::models::session::{entity as session_entity, repo::{SessionRepository, SessionRepositoryError, SessionRepositoryTrait}};
+use repository::repositories::{encryption::{EncryptionRepositoryExt, EncryptionRepositoryTrait, data::Token}};
+use crate::shared::data::{AuthUser, ErrorResponse, RefreshToken};
+use crate::shared::extractors::client_context::ClientContext;
+use crate::shared::utils::audit::{AuditEvent, AuditEventType};
+use crate::shared::utils::email::normalize_email;
+
+/// Wrong passwords allowed before the account is locked
+const MAX_LOGIN_ATTEMPTS: i32 = 5;
+
+/// First lock lasts this long; every further wrong password after unlocking doubles it
+const LOCKOUT_BASE_SECONDS: i64 = 60;
+
+/// Upper bound on a single lock
+const LOCKOUT_MAX_SECONDS: i64 = 24 * 60 * 60;
+
+/// Lock length after `failed_attempts` consecutive wrong passwords, `None` below the threshold
+fn lockout_seconds(failed_attempts: i32) -> Option<i64> {
+    let over = failed_attempts - MAX_LOGIN_ATTEMPTS;
+    if over < 0 {
+        return None;
+    }
+    let factor = 1i64.checked_shl(over.min(32) as u32).unwrap_or(i64::MAX);
+    Some(LOCKOUT_BASE_SECONDS.saturating_mul(factor).min(LOCKOUT_MAX_SECONDS))
+}
 
 #[derive(Debug)]
 pub enum AuthError {
     InvalidCredentials,
-    UserNotFound,
     EmailAlreadyExists,
     PasswordInvalid,
+    AccountBanned,
+    /// Too many wrong passwords; seconds until sign-in is allowed again
+    AccountLocked(i64),
+    InvalidToken(String),
     TokenCreationFailed,
     DatabaseError(String),
 }
@@ -19,9 +47,11 @@ impl std::fmt::Display for AuthError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             AuthError::InvalidCredentials => write!(f, "Invalid credentials"),
-            AuthError::UserNotFound => write!(f, "User not found"),
             AuthError::EmailAlreadyExists => write!(f, "Email already exists"),
             AuthError::PasswordInvalid => write!(f, "Password is invalid"),
+            AuthError::AccountBanned => write!(f, "Account is banned"),
+            AuthError::AccountLocked(secs) => write!(f, "Account is locked for {} seconds", secs),
+            AuthError::InvalidToken(msg) => write!(f, "Invalid token: {}", msg),
             AuthError::TokenCreationFailed => write!(f, "Failed to create token"),
             AuthError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
         }
@@ -30,27 +60,97 @@ impl std::fmt::Display for AuthError {
 
 impl std::error::Error for AuthError {}
 
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthError::InvalidCredentials => ErrorResponse::response(StatusCode::UNAUTHORIZED, "INVALID_CREDENTIALS", "Invalid credentials"),
+            AuthError::EmailAlreadyExists => ErrorResponse::response(StatusCode::CONFLICT, "EMAIL_EXISTS", "Email address already exists"),
+            AuthError::PasswordInvalid => ErrorResponse::response(StatusCode::BAD_REQUEST, "PASSWORD_INVALID", "Password is invalid"),
+            AuthError::AccountBanned => ErrorResponse::response(StatusCode::FORBIDDEN, "ACCOUNT_BANNED", "Account is banned"),
+            AuthError::AccountLocked(secs) => {
+                let mut response = ErrorResponse::response(StatusCode::LOCKED, "ACCOUNT_LOCKED", "Too many failed sign-ins, try again later");
+                if let Ok(value) = HeaderValue::from_str(&secs.to_string()) {
+                    response.headers_mut().insert(header::RETRY_AFTER, value);
+                }
+                response
+            }
+            AuthError::InvalidToken(msg) => ErrorResponse::response(StatusCode::UNAUTHORIZED, "INVALID_TOKEN", msg),
+            AuthError::TokenCreationFailed => ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "TOKEN_CREATION_FAILED", "Failed to create token"),
+            AuthError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "auth database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AuthService {
-    user_repo: UserRepository,
-    encryption_repo: EncryptionRepository,
+    user_repo: Arc<dyn UserRepositoryTrait>,
+    session_repo: SessionRepository,
+    encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
 }
 
 impl AuthService {
-    pub fn new(user_repo: UserRepository, encryption_repo: EncryptionRepository) -> Self {
+    pub fn new(user_repo: Arc<dyn UserRepositoryTrait>, session_repo: SessionRepository, encryption_repo: Arc<dyn EncryptionRepositoryTrait>) -> Self {
         Self {
             user_repo,
+            session_repo,
             encryption_repo,
         }
     }
 
-    pub async fn sign_up(&self, request: user::RegisterRequest) -> Result<user::AuthUserResponse, AuthError> {
+    /// Hash verified against when sign-in finds no user, made with the same parameters as real hashes
+    fn dummy_password_hash(&self) -> &'static str {
+        static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+        DUMMY_HASH.get_or_init(|| {
+            self.encryption_repo
+                .hash_password("timing-equalizer-not-a-real-password")
+                .unwrap_or_default()
+        })
+    }
+
+    /// Mint an access token and a refresh token, recording the refresh token as a new session
+    async fn issue_tokens(&self, auth_user: AuthUser, client: &ClientContext) -> Result<user::AuthUserResponse, AuthError> {
+        let jti = Uuid::new_v4();
+        let refresh_params = Token::user_refresh_token();
+        let now = Utc::now();
+
+        let session = session_entity::Model {
+            jti,
+            user_id: auth_user.id,
+            user_agent: client.user_agent.clone(),
+            ip: client.ip.map(|ip| ip.to_string()),
+            created_at: now.into(),
+            expires_at: (now + Duration::seconds(refresh_params.expiry_seconds)).into(),
+            revoked_at: None,
+        };
+
+        let access_token = self.encryption_repo.create_token(auth_user.clone(), Token::user_access_token())
+            .map_err(|_| AuthError::TokenCreationFailed)?;
+
+        let refresh_token = self.encryption_repo.create_token_with_jti(auth_user.clone(), refresh_params, &jti.to_string())
+            .map_err(|_| AuthError::TokenCreationFailed)?;
+
+        self.session_repo.create(session).await
+            .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+
+        Ok(user::AuthUserResponse {
+            id: auth_user.id.to_string(),
+            access_token,
+            refresh_token,
+        })
+    }
+
+    pub async fn sign_up(&self, request: user::RegisterRequest, client: &ClientContext) -> Result<user::AuthUserResponse, AuthError> {
         // Hash password
         let hash_password = self.encryption_repo.hash_password(&request.password)
             .map_err(|_| AuthError::PasswordInvalid)?;
 
+        let email_address = normalize_email(&request.email_address);
+
         // Check if user already exists
-        let user_exist = match self.user_repo.get_by_email(&request.email_address.to_lowercase())
+        let user_exist = match self.user_repo.get_by_email(&email_address)
             .await {
             Ok(user) => Ok(user),
             Err(e) => Err(AuthError::DatabaseError(e.to_string())),
@@ -61,32 +161,12 @@ impl AuthService {
         }
 
         // Create new user
-        let new_user = user_entity::Model {
-            id: Uuid::new_v4(),
-            personal_first_name: request.first_name.clone(),
-            personal_second_name: request.second_name.clone(),
-            personal_email_address: request.email_address.clone().to_lowercase(),
-            personal_profile_image: None,   
-            personal_username: None,
-            password: hash_password,
-            peripheral_authentication_code: None,
-            peripheral_authentication_token: None,
-            peripheral_timeout: None,
-            peripheral_is_banned: false,
-            peripheral_is_verified: false,
-            verification_code: String::new(),
-            verification_timeout: None,
-            setting_custom_setting_default_theme: None,
-            setting_custom_setting_is_accepting_request: false,
-            setting_subscription_price_id: None,
-            setting_subscription_product_id: None,
-            setting_subscription_status: "BASIC".to_string(),
-            setting_subscription_start_date: None,
-            setting_subscription_end_date: None,
-            created_at: Utc::now().into(),
-            updated_at: Utc::now().into(),
-            deleted_at: None,
-        };
+        let new_user = user_entity::Model::new_registration(
+            request.first_name.clone(),
+            request.second_name.clone(),
+            email_address,
+            hash_password,
+        );
 
         // Save user
         let created_user = match self.user_repo.create(new_user).await {
@@ -94,63 +174,138 @@ impl AuthService {
             Err(e) => Err(AuthError::DatabaseError(e.to_string())),
         }?;
 
-        // Create tokens
-        let auth_user = AuthUser::from_user(created_user);
-
-        let access_token = self.encryption_repo.create_token(auth_user.clone(), Token::user_access_token())
-            .map_err(|_| AuthError::TokenCreationFailed)?;
-        
-        let refresh_token = self.encryption_repo.create_token(auth_user.clone(), Token::user_refresh_token())
-            .map_err(|_| AuthError::TokenCreationFailed)?;
+        AuditEvent::success(AuditEventType::SignUp).user(created_user.id).client(client).record();
 
-        Ok(user::AuthUserResponse {
-            id: auth_user.id.to_string(),
-            access_token,
-            refresh_token,
-        })
+        // Create tokens
+        self.issue_tokens(AuthUser::from_user(created_user), client).await
     }
 
-    pub async fn sign_in(&self, request: user::LoginRequest) -> Result<user::AuthUserResponse, AuthError> {
-        // Get user by email
-        let user = self.user_repo.get_by_email(&request.email_address.to_lowercase())
-            .await
-            .map_err(|_| AuthError::UserNotFound)?;
+    pub async fn sign_in(&self, request: user::LoginRequest, client: &ClientContext) -> Result<user::AuthUserResponse, AuthError> {
+        // Get user by email. An unknown email still pays for a password verify so response
+        // timing doesn't tell registered and unregistered addresses apart
+        let user = match self.user_repo.get_by_email(&normalize_email(&request.email_address)).await {
+            Ok(user) => user,
+            Err(_) => {
+                let _ = self.encryption_repo.verify_password(self.dummy_password_hash(), &request.password);
+                AuditEvent::failure(AuditEventType::SignIn, "unknown email").client(client).record();
+                return Err(AuthError::InvalidCredentials);
+            }
+        };
+
+        // A locked account is refused before the password is looked at, so guesses during the lock tell nothing
+        let now = Utc::now();
+        if let Some(locked_until) = user.locked_until.map(|t| t.with_timezone(&Utc)).filter(|t| *t > now) {
+            AuditEvent::failure(AuditEventType::SignIn, "locked").user(user.id).client(client).record();
+            return Err(AuthError::AccountLocked((locked_until - now).num_seconds().max(1)));
+        }
 
         // Verify password
         let is_valid = self.encryption_repo.verify_password(&user.password, &request.password)
             .map_err(|_| AuthError::PasswordInvalid)?;
-        
+
         if !is_valid {
+            AuditEvent::failure(AuditEventType::SignIn, "invalid password").user(user.id).client(client).record();
+            let failed_attempts = self.user_repo.increment_failed_logins(user.id).await
+                .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+            if let Some(secs) = lockout_seconds(failed_attempts) {
+                self.user_repo.set_locked_until(user.id, Some(now + Duration::seconds(secs))).await
+                    .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+                return Err(AuthError::AccountLocked(secs));
+            }
             return Err(AuthError::InvalidCredentials);
         }
-        
-        // Create tokens
-        let auth_user = AuthUser::from_user(user);
 
-        let access_token = self.encryption_repo.create_token(auth_user.clone(), Token::user_access_token())
-            .map_err(|_| AuthError::TokenCreationFailed)?;
-        
-        let refresh_token = self.encryption_repo.create_token(auth_user.clone(), Token::user_refresh_token())
-            .map_err(|_| AuthError::TokenCreationFailed)?;
+        // Checked after the password so a ban doesn't reveal which emails are registered
+        if user.peripheral_is_banned {
+            AuditEvent::failure(AuditEventType::SignIn, "banned").user(user.id).client(client).record();
+            return Err(AuthError::AccountBanned);
+        }
 
-        Ok(user::AuthUserResponse {
-            id: auth_user.id.to_string(),
-            access_token,
-            refresh_token,
-        })
+        AuditEvent::success(AuditEventType::SignIn).user(user.id).client(client).record();
+
+        // Bookkeeping only; a failed write shouldn't stop the user from signing in. Only the login
+        // columns are written, so a ban or password change made since the read above isn't reverted
+        if let Err(e) = self.user_repo.record_login(user.id, now).await {
+            tracing::warn!(user_id = %user.id, error = %e, "failed to record last login");
+        }
+
+        // Create tokens
+        self.issue_tokens(AuthUser::from_user(user), client).await
     }
 
-    pub async fn refresh_token(&self, auth_user: AuthUser) -> Result<user::AuthUserResponse, AuthError> {
-        let access_token = self.encryption_repo.create_token(auth_user.clone(), Token::user_access_token())
-            .map_err(|_| AuthError::TokenCreationFailed)?;
-        let refresh_token = self.encryption_repo.create_token(auth_user.clone(), Token::user_refresh_token())
-            .map_err(|_| AuthError::TokenCreationFailed)?;
+    /// Rotate a refresh token: its session must still be active and its user neither banned nor
+    /// deleted, and the session is revoked once replaced
+    pub async fn refresh_token(&self, refresh: RefreshToken, client: &ClientContext) -> Result<user::AuthUserResponse, AuthError> {
+        let auth_user = refresh.auth_user;
 
-        Ok(user::AuthUserResponse {
-            id: auth_user.id.to_string(),
-            access_token,
-            refresh_token, // Empty for refresh token endpoint
-        })
+        // Tokens minted before sessions were tracked carry no jti and can't be revoked, so refuse them
+        let jti = refresh.jti
+            .as_deref()
+            .and_then(|jti| Uuid::parse_str(jti).ok())
+            .ok_or_else(|| AuthError::InvalidToken("refresh token has no session".to_string()))?;
+
+        let session = match self.session_repo.get_by_jti(jti).await {
+            Ok(session) if session.user_id == auth_user.id && session.is_active() => session,
+            Ok(_) | Err(SessionRepositoryError::NotFound(_)) => {
+                AuditEvent::failure(AuditEventType::TokenRefresh, "inactive session").user(auth_user.id).client(client).record();
+                return Err(AuthError::InvalidToken("session revoked or expired".to_string()));
+            }
+            Err(e) => return Err(AuthError::DatabaseError(e.to_string())),
+        };
+
+        // The token only proves who the user was when it was minted; re-check the account itself
+        let user = match self.user_repo.get_by_id(auth_user.id).await {
+            Ok(user) if user.deleted_at.is_none() => user,
+            Ok(_) | Err(UserRepositoryError::NotFound(_)) => {
+                AuditEvent::failure(AuditEventType::TokenRefresh, "account missing").user(auth_user.id).client(client).record();
+                return Err(AuthError::InvalidToken("account no longer exists".to_string()));
+            }
+            Err(e) => return Err(AuthError::DatabaseError(e.to_string())),
+        };
+        if user.peripheral_is_banned {
+            AuditEvent::failure(AuditEventType::TokenRefresh, "banned").user(user.id).client(client).record();
+            return Err(AuthError::AccountBanned);
+        }
+
+        // The revoke is conditional, so of two refreshes racing with the same token only one gets
+        // new tokens; the loser sees the session already revoked, exactly like a replayed token
+        match self.session_repo.revoke(session.jti).await {
+            Ok(()) => {}
+            Err(SessionRepositoryError::NotFound(_)) => {
+                AuditEvent::failure(AuditEventType::TokenRefresh, "refresh token reuse").user(auth_user.id).client(client).record();
+                return Err(AuthError::InvalidToken("session revoked or expired".to_string()));
+            }
+            Err(e) => return Err(AuthError::DatabaseError(e.to_string())),
+        }
+
+        AuditEvent::success(AuditEventType::TokenRefresh).user(auth_user.id).client(client).record();
+
+        self.issue_tokens(auth_user, client).await
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn auth_errors_map_to_their_status_and_code() {
+        ErrorResponse::assert_mappings([
+            (AuthError::InvalidCredentials, StatusCode::UNAUTHORIZED, "INVALID_CREDENTIALS"),
+            (AuthError::EmailAlreadyExists, StatusCode::CONFLICT, "EMAIL_EXISTS"),
+            (AuthError::PasswordInvalid, StatusCode::BAD_REQUEST, "PASSWORD_INVALID"),
+            (AuthError::AccountBanned, StatusCode::FORBIDDEN, "ACCOUNT_BANNED"),
+            (AuthError::AccountLocked(60), StatusCode::LOCKED, "ACCOUNT_LOCKED"),
+            (AuthError::InvalidToken("expired".into()), StatusCode::UNAUTHORIZED, "INVALID_TOKEN"),
+            (AuthError::TokenCreationFailed, StatusCode::INTERNAL_SERVER_ERROR, "TOKEN_CREATION_FAILED"),
+            (AuthError::DatabaseError("gone".into()), StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR"),
+        ])
+        .await;
+    }
+
+    #[tokio::test]
+    async fn invalid_token_carries_its_reason() {
+        let (_, body) = ErrorResponse::read(AuthError::InvalidToken("expired".into()).into_response()).await;
+        assert_eq!(body.message, "expired");
+    }
+}
@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get},
+    Json, Router,
+};
+
+use crate::shared::{
+    data::{AuthUser, SuccessResponse},
+    middlewares::auth::require_user_auth,
+    data::state::AppState,
+    extractors::client_context::ClientContext,
+};
+use super::session::service::{SessionError, SessionService};
+
+pub mod service;
+
+pub struct SessionController;
+
+impl SessionController {
+    fn create_service(app_state: &AppState) -> SessionService {
+        SessionService::new(
+            app_state.model.session.clone(),
+        )
+    }
+
+    /// List the caller's active refresh-token sessions
+    pub async fn list_sessions(
+        State(app_state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+    ) -> Result<impl IntoResponse, SessionError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.list_sessions(auth_user.id).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+
+    /// Revoke one of the caller's sessions so its refresh token can no longer be used
+    pub async fn revoke_session(
+        State(app_state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        client: ClientContext,
+        Path(jti): Path<String>,
+    ) -> Result<impl IntoResponse, SessionError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.revoke_session(auth_user.id, &jti, &client).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+}
+
+
+pub fn router() -> Router<AppState> {
+    Router::<AppState>::new()
+        .route("/", get(SessionController::list_sessions))
+        .route("/:jti", delete(SessionController::revoke_session))
+        .layer(axum::middleware::from_fn(require_user_auth))
+}
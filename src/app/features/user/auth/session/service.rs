@@ -0,0 +1,75 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use uuid::Uuid;
+use model::models::session::{Session, SessionResponse};
+use model::models::session::repo::{SessionRepository, SessionRepositoryError, SessionRepositoryTrait};
+use crate::shared::data::ErrorResponse;
+use crate::shared::extractors::client_context::ClientContext;
+use crate::shared::utils::audit::{AuditEvent, AuditEventType};
+
+#[derive(Debug)]
+pub enum SessionError {
+    NotFound,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SessionError::NotFound => write!(f, "Session not found"),
+            SessionError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl IntoResponse for SessionError {
+    fn into_response(self) -> Response {
+        match self {
+            SessionError::NotFound => ErrorResponse::response(StatusCode::NOT_FOUND, "SESSION_NOT_FOUND", "Session not found"),
+            SessionError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "session database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SessionService {
+    session_repo: SessionRepository,
+}
+
+impl SessionService {
+    pub fn new(session_repo: SessionRepository) -> Self {
+        Self { session_repo }
+    }
+
+    pub async fn list_sessions(&self, user_id: Uuid) -> Result<Vec<SessionResponse>, SessionError> {
+        let sessions = self.session_repo.list_active_by_user(user_id).await
+            .map_err(|e| SessionError::DatabaseError(e.to_string()))?;
+
+        Ok(sessions.into_iter().map(|s| SessionResponse::from(Session::from(s))).collect())
+    }
+
+    /// Sessions owned by other users are reported as not found rather than forbidden
+    pub async fn revoke_session(&self, user_id: Uuid, jti: &str, client: &ClientContext) -> Result<SessionResponse, SessionError> {
+        let jti = Uuid::parse_str(jti).map_err(|_| SessionError::NotFound)?;
+
+        let session = match self.session_repo.get_by_jti(jti).await {
+            Ok(session) if session.user_id == user_id && session.is_active() => session,
+            Ok(_) | Err(SessionRepositoryError::NotFound(_)) => return Err(SessionError::NotFound),
+            Err(e) => return Err(SessionError::DatabaseError(e.to_string())),
+        };
+
+        match self.session_repo.revoke(jti).await {
+            Ok(()) => {}
+            Err(SessionRepositoryError::NotFound(_)) => return Err(SessionError::NotFound),
+            Err(e) => return Err(SessionError::DatabaseError(e.to_string())),
+        }
+
+        AuditEvent::success(AuditEventType::SessionRevoked).user(user_id).client(client).record();
+
+        Ok(SessionResponse::from(Session::from(session)))
+    }
+}
@@ -1,6 +1,8 @@
 use axum::Router;
 pub mod auth;
 pub mod profile;
+pub mod swap;
+pub mod wallet;
 
 use crate::shared::data::state::AppState;
 
@@ -8,4 +10,6 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .nest("/auth", auth::router())
         .nest("/profile", profile::router())
+        .nest("/swap", swap::router())
+        .nest("/wallet", wallet::router())
 }
\ No newline at end of file
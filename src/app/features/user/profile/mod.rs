@@ -1,15 +1,18 @@
 use axum::{
     extract::{Extension, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, put},
     Json, Router,
 };
+use std::sync::Arc;
 
 use crate::shared::{
-    data::{AuthUser, ErrorResponse, SuccessResponse},
+    data::{AuthUser, SuccessResponse},
     middlewares::auth::require_user_auth,
     data::state::AppState,
+    extractors::validated_json::{Validate, ValidatedJson},
+    utils::etag::{if_none_match, weak_etag},
 };
 
 use model::models::user;
@@ -22,73 +25,35 @@ pub struct ProfileController;
 impl ProfileController {
     fn create_service(app_state: &AppState) -> ProfileService {
         ProfileService::new(
-            app_state.model.user.clone(),
+            Arc::new(app_state.model.user.clone()),
         )
     }
 
+    /// Conditional on `If-None-Match`: polling clients get a bodiless 304 while the profile is unchanged
     pub async fn get_me(
         State(app_state): State<AppState>,
         Extension(auth_user): Extension<AuthUser>,
-    ) -> impl IntoResponse {
+        headers: HeaderMap,
+    ) -> Result<Response, ProfileError> {
         let service = Self::create_service(&app_state);
-        match service.get_profile(auth_user.id).await {
-            Ok(resp) => (StatusCode::OK, Json(SuccessResponse::new(resp))).into_response(),
-            Err(ProfileError::NotFound(msg)) => (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse::new(msg)),
-            )
-                .into_response(),
-            Err(ProfileError::DatabaseError(msg)) => {
-                tracing::error!(error = %msg, "profile get_me database error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!("Database error: {}", msg))),
-                )
-                    .into_response()
-            }
-            Err(ProfileError::Duplicate(msg)) | Err(ProfileError::ValidationError(msg)) => {
-                tracing::error!(error = %msg, "profile get_me database error");
-                (
-                StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::new(msg)),
-                )
-                    .into_response()
-            }
+        let resp = service.get_profile(auth_user.id).await?;
+        let etag = weak_etag(&resp.id, resp.timestamps.updated_at);
+
+        if if_none_match(&headers, &etag) {
+            return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
         }
+        Ok((StatusCode::OK, [(header::ETAG, etag)], Json(SuccessResponse::new(resp))).into_response())
     }
 
     pub async fn update_me(
         State(app_state): State<AppState>,
         Extension(auth_user): Extension<AuthUser>,
-        Json(req): Json<user::UpdatePersonal>,
-    ) -> impl IntoResponse {
+        ValidatedJson(req): ValidatedJson<user::UpdatePersonal>,
+    ) -> Result<impl IntoResponse, ProfileError> {
         let service = Self::create_service(&app_state);
-        match service.update_personal(auth_user.id, req).await {
-            Ok(resp) => (StatusCode::OK, Json(SuccessResponse::new(resp))).into_response(),
-            Err(ProfileError::NotFound(msg)) => (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse::new(msg)),
-            )
-                .into_response(),
-            Err(ProfileError::Duplicate(msg)) => (
-                StatusCode::CONFLICT,
-                Json(ErrorResponse::new(msg)),
-            )
-                .into_response(),
-            Err(ProfileError::ValidationError(msg)) => (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(msg)),
-            )
-                .into_response(),
-            Err(ProfileError::DatabaseError(msg)) => {
-                tracing::error!(error = %msg, "profile update_me database error");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!("Database error: {}", msg))),
-                )
-                    .into_response()
-            }
-        }
+        let resp = service.update_personal(auth_user.id, req).await?;
+        let etag = weak_etag(&resp.id, resp.timestamps.updated_at);
+        Ok((StatusCode::OK, [(header::ETAG, etag)], Json(SuccessResponse::new(resp))))
     }
 }
 
@@ -98,4 +63,7 @@ pub fn router() -> Router<AppState> {
         .route("/", put(ProfileController::update_me))
         // Apply function-based auth middleware which reads AppState from request extensions
         .layer(axum::middleware::from_fn(require_user_auth))
-}
\ No newline at end of file
+}
+
+// Field checks stay in ProfileService::update_personal
+impl Validate for user::UpdatePersonal {}
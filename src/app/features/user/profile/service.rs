@@ -1,8 +1,13 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
 use chrono::Utc;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use model::models::user::{self as user, repo::UserRepositoryTrait};
-use model::models::user::repo::UserRepository;
+
+use crate::shared::data::ErrorResponse;
+use crate::shared::utils::audit::{AuditEvent, AuditEventType};
+use crate::shared::utils::email::normalize_email;
 
 #[derive(Debug)]
 pub enum ProfileError {
@@ -25,13 +30,27 @@ impl std::fmt::Display for ProfileError {
 
 impl std::error::Error for ProfileError {}
 
+impl IntoResponse for ProfileError {
+    fn into_response(self) -> Response {
+        match self {
+            ProfileError::NotFound(msg) => ErrorResponse::response(StatusCode::NOT_FOUND, "NOT_FOUND", msg),
+            ProfileError::Duplicate(msg) => ErrorResponse::response(StatusCode::CONFLICT, "DUPLICATE", msg),
+            ProfileError::ValidationError(msg) => ErrorResponse::response(StatusCode::BAD_REQUEST, "VALIDATION_ERROR", msg),
+            ProfileError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "profile database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ProfileService {
-    user_repo: UserRepository,
+    user_repo: Arc<dyn UserRepositoryTrait>,
 }
 
 impl ProfileService {
-    pub fn new(user_repo: UserRepository) -> Self {
+    pub fn new(user_repo: Arc<dyn UserRepositoryTrait>) -> Self {
         Self { user_repo }
     }
 
@@ -75,10 +94,13 @@ impl ProfileService {
                 model::models::user::repo::UserRepositoryError::DatabaseError(msg) => ProfileError::DatabaseError(msg),
             })?;
 
+        let new_email = normalize_email(&req.email_address);
+        let email_changed = model.personal_email_address != new_email;
+
         // Apply changes
         model.personal_first_name = req.first_name;
         model.personal_second_name = req.second_name;
-        model.personal_email_address = req.email_address.to_lowercase();
+        model.personal_email_address = new_email;
         model.personal_profile_image = req.profile_image;
         model.personal_username = req.username;
         model.updated_at = Utc::now().into();
@@ -88,6 +110,11 @@ impl ProfileService {
             .user_repo
             .update(model)
             .await
+            .inspect_err(|_| {
+                if email_changed {
+                    AuditEvent::failure(AuditEventType::EmailChanged, "update failed").user(user_id).record();
+                }
+            })
             .map_err(|e| match e {
                 // Map duplicate email constraint if any
                 model::models::user::repo::UserRepositoryError::DatabaseError(msg) => {
@@ -101,7 +128,27 @@ impl ProfileService {
                 model::models::user::repo::UserRepositoryError::Duplicate(msg) => ProfileError::Duplicate(msg),
             })?;
 
+        if email_changed {
+            AuditEvent::success(AuditEventType::EmailChanged).user(user_id).record();
+        }
+
         let domain_user: user::User = updated.into();
         Ok(user::SecureUserResponse::from(domain_user))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn profile_errors_map_to_their_status_and_code() {
+        ErrorResponse::assert_mappings([
+            (ProfileError::NotFound("user".into()), StatusCode::NOT_FOUND, "NOT_FOUND"),
+            (ProfileError::Duplicate("username".into()), StatusCode::CONFLICT, "DUPLICATE"),
+            (ProfileError::ValidationError("bad".into()), StatusCode::BAD_REQUEST, "VALIDATION_ERROR"),
+            (ProfileError::DatabaseError("gone".into()), StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR"),
+        ])
+        .await;
+    }
+}
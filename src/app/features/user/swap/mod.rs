@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use repository::repositories::crypto::data::{CryptoError, SwapType};
+use serde::Deserialize;
+
+use crate::shared::{
+    data::SuccessResponse,
+    middlewares::auth::require_user_auth,
+    data::state::AppState,
+};
+
+mod service;
+use service::SwapService;
+
+pub struct SwapController;
+
+impl SwapController {
+    fn create_service(app_state: &AppState) -> SwapService {
+        SwapService::new((*app_state.repository.crypto).clone())
+    }
+
+    pub async fn quote(
+        State(app_state): State<AppState>,
+        Json(request): Json<SwapType>,
+    ) -> Result<impl IntoResponse, CryptoError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.quote(request).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+
+    pub async fn status(
+        State(app_state): State<AppState>,
+        Path(tx_hash): Path<String>,
+        Query(query): Query<SwapStatusQuery>,
+    ) -> Result<impl IntoResponse, CryptoError> {
+        let service = Self::create_service(&app_state);
+        let chain = query.chain.unwrap_or_else(|| "bsc".to_string());
+        let resp = service.status(&chain, &tx_hash).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SwapStatusQuery {
+    pub chain: Option<String>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::<AppState>::new()
+        .route("/quote", post(SwapController::quote))
+        .route("/status/:tx_hash", get(SwapController::status))
+        .layer(axum::middleware::from_fn(require_user_auth))
+}
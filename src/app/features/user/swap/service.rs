@@ -0,0 +1,26 @@
+use repository::repositories::crypto::{
+    data::{CryptoError, SwapQuote, SwapType, TransactionStatus},
+    CryptoRepository,
+};
+
+/// Swap failures are `CryptoError`s, answered through its `IntoResponse`
+#[derive(Clone)]
+pub struct SwapService {
+    crypto_repo: CryptoRepository,
+}
+
+impl SwapService {
+    pub fn new(crypto_repo: CryptoRepository) -> Self {
+        Self { crypto_repo }
+    }
+
+    // Price a swap against on-chain reserves without signing or broadcasting
+    pub async fn quote(&self, swap: SwapType) -> Result<SwapQuote, CryptoError> {
+        self.crypto_repo.quote_swap(swap).await
+    }
+
+    // Look up whether a previously submitted swap has confirmed
+    pub async fn status(&self, chain: &str, tx_hash: &str) -> Result<TransactionStatus, CryptoError> {
+        self.crypto_repo.get_swap_status(chain, tx_hash).await
+    }
+}
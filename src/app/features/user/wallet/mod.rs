@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+
+use crate::shared::{
+    data::{AuthUser, SuccessResponse},
+    middlewares::auth::require_user_auth,
+    data::state::AppState,
+};
+
+mod service;
+use service::{WalletError, WalletService};
+
+pub struct WalletController;
+
+impl WalletController {
+    fn create_service(app_state: &AppState) -> WalletService {
+        WalletService::new(
+            app_state.model.wallet.clone(),
+            app_state.repository.encryption.clone(),
+            (*app_state.repository.crypto).clone(),
+        )
+    }
+
+    pub async fn create_wallet(
+        State(app_state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+    ) -> Result<impl IntoResponse, WalletError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.create_wallet(auth_user.id).await?;
+        Ok((StatusCode::CREATED, Json(SuccessResponse::new(resp))))
+    }
+
+    /// Native and token balances of one of the caller's wallets across all chains; chains that
+    /// fail or time out are listed with an error instead of failing the request
+    pub async fn balances(
+        State(app_state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(address): Path<String>,
+    ) -> Result<impl IntoResponse, WalletError> {
+        let service = Self::create_service(&app_state);
+        let resp = service.balances(auth_user.id, &address).await?;
+        Ok((StatusCode::OK, Json(SuccessResponse::new(resp))))
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::<AppState>::new()
+        .route("/create", post(WalletController::create_wallet))
+        .route("/:address/balances", get(WalletController::balances))
+        .layer(axum::middleware::from_fn(require_user_auth))
+}
@@ -0,0 +1,134 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use std::sync::Arc;
+use chrono::Utc;
+use uuid::Uuid;
+
+use model::models::wallet::{self as wallet, entity as wallet_entity};
+use model::models::wallet::repo::{WalletRepository, WalletRepositoryError, WalletRepositoryTrait};
+use repository::repositories::crypto::{data::{CryptoError, WalletBalances}, CryptoRepository, CryptoRepositoryTrait};
+use repository::repositories::encryption::EncryptionRepositoryTrait;
+
+use crate::shared::data::ErrorResponse;
+
+#[derive(Debug)]
+pub enum WalletError {
+    /// Key generation or chain lookups; answered through `CryptoError`'s own response mapping
+    Crypto(CryptoError),
+    EncryptionFailed,
+    Duplicate(String),
+    NotFound(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for WalletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WalletError::Crypto(err) => write!(f, "{}", err),
+            WalletError::EncryptionFailed => write!(f, "Failed to encrypt wallet secrets"),
+            WalletError::Duplicate(msg) => write!(f, "Duplicate: {}", msg),
+            WalletError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            WalletError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WalletError {}
+
+impl From<CryptoError> for WalletError {
+    fn from(err: CryptoError) -> Self {
+        WalletError::Crypto(err)
+    }
+}
+
+impl IntoResponse for WalletError {
+    fn into_response(self) -> Response {
+        match self {
+            WalletError::Crypto(err) => err.into_response(),
+            WalletError::EncryptionFailed => {
+                tracing::error!("wallet secrets encryption failed");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "ENCRYPTION_FAILED", "unable to create wallet")
+            }
+            WalletError::Duplicate(msg) => ErrorResponse::response(StatusCode::CONFLICT, "DUPLICATE", msg),
+            WalletError::NotFound(msg) => ErrorResponse::response(StatusCode::NOT_FOUND, "WALLET_NOT_FOUND", msg),
+            WalletError::DatabaseError(msg) => {
+                tracing::error!(error = %msg, "wallet database error");
+                ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", format!("Database error: {}", msg))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WalletService {
+    wallet_repo: WalletRepository,
+    encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
+    crypto_repo: CryptoRepository,
+}
+
+impl WalletService {
+    pub fn new(
+        wallet_repo: WalletRepository,
+        encryption_repo: Arc<dyn EncryptionRepositoryTrait>,
+        crypto_repo: CryptoRepository,
+    ) -> Self {
+        Self { wallet_repo, encryption_repo, crypto_repo }
+    }
+
+    // Create a wallet for the user; the seed phrase is returned here and never again
+    pub async fn create_wallet(&self, user_id: Uuid) -> Result<wallet::CreateWalletResponse, WalletError> {
+        let created = self.crypto_repo.create_wallet()?;
+
+        // Encrypt secrets before they touch the database
+        let encrypted_private_key = self
+            .encryption_repo
+            .encrypt_data(&created.private_key)
+            .map_err(|_| WalletError::EncryptionFailed)?;
+        let encrypted_seed_phrase = self
+            .encryption_repo
+            .encrypt_data(&created.seed_phrase)
+            .map_err(|_| WalletError::EncryptionFailed)?;
+
+        let new_wallet = wallet_entity::Model {
+            id: Uuid::new_v4(),
+            user_id,
+            address: created.address,
+            encrypted_private_key,
+            encrypted_seed_phrase,
+            created_at: Utc::now().into(),
+            updated_at: Utc::now().into(),
+            deleted_at: None,
+        };
+
+        let saved = self
+            .wallet_repo
+            .create(new_wallet)
+            .await
+            .map_err(|e| match e {
+                WalletRepositoryError::Duplicate(msg) => WalletError::Duplicate(msg),
+                WalletRepositoryError::NotFound(msg) | WalletRepositoryError::DatabaseError(msg) => {
+                    WalletError::DatabaseError(msg)
+                }
+            })?;
+
+        Ok(wallet::CreateWalletResponse {
+            id: saved.id.to_string(),
+            address: saved.address,
+            seed_phrase: created.seed_phrase,
+            warning: "store this seed phrase securely, it is shown only once and cannot be recovered".to_string(),
+        })
+    }
+
+    // Balances of one of the user's wallets on every configured chain
+    pub async fn balances(&self, user_id: Uuid, address: &str) -> Result<WalletBalances, WalletError> {
+        let wallets = self
+            .wallet_repo
+            .list_by_user(user_id)
+            .await
+            .map_err(|e| WalletError::DatabaseError(e.to_string()))?;
+        if !wallets.iter().any(|w| w.address.eq_ignore_ascii_case(address)) {
+            return Err(WalletError::NotFound("wallet not found".to_string()));
+        }
+
+        Ok(self.crypto_repo.get_wallet_balances(address).await?)
+    }
+}
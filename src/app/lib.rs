@@ -0,0 +1,72 @@
+//! The API server as a library: the `main` binary serves `build_router`, and the integration
+//! tests in `tests/` drive the same router in-process
+
+use axum::http::{HeaderMap, Method, header};
+use axum::response::{IntoResponse, Response};
+use axum::{error_handling::HandleErrorLayer, Extension, Json, Router};
+use model::models::Models;
+use repository::repositories::Repositories;
+use shared::data::state::AppState;
+use shared::data::SuccessResponse;
+use shared::middlewares::{account_status::AccountStatusCache, content_negotiation, deprecation, load_shed};
+use shared::middlewares::content_negotiation::MediaType;
+use shared::utils::config::AppConfig;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::ServiceBuilder;
+use tower_http::compression::predicate::{DefaultPredicate, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::CorsLayer;
+
+pub mod features;
+pub mod shared;
+
+/// Liveness probe: plain `OK` unless the client asks for JSON
+async fn health_check(headers: HeaderMap) -> Response {
+    match content_negotiation::preferred_media_type(&headers) {
+        Some(MediaType::Json) => Json(SuccessResponse::new("OK")).into_response(),
+        _ => "OK".into_response(),
+    }
+}
+
+/// The full application router with its state and middleware, ready to serve or to drive
+/// in-process with `tower::ServiceExt::oneshot`
+pub fn build_router(cfg: &AppConfig, repositories: Repositories, models: Models) -> Router {
+    let cors = CorsLayer::new()
+        .allow_origin(tower_http::cors::Any)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, header::IF_NONE_MATCH])
+        .expose_headers([header::ETAG]);
+
+    let account_status = AccountStatusCache::from_env(models.user.clone());
+
+    let mut app = Router::new()
+        .route("/health", axum::routing::get(health_check))
+        .nest(deprecation::API_V1_PREFIX, features::router())
+        // Pre-versioning alias; new routes only need to be reachable under /api/v1
+        .nest("/api/", features::router().layer(axum::middleware::from_fn(deprecation::mark_deprecated)))
+        .layer(Extension(repositories.encryption.clone()))
+        .with_state(AppState::new(repositories, models))
+        .layer(axum::middleware::from_fn(content_negotiation::negotiate_errors))
+        // Gzip for clients that accept it; bodies under 1 KiB aren't worth the CPU
+        .layer(CompressionLayer::new().compress_when(DefaultPredicate::new().and(SizeAbove::new(1024))))
+        .layer(cors)
+        // Shed load instead of queueing unbounded work behind a saturated database pool. Router::layer
+        // wraps every route separately, so the limit must be global for it to cap the whole server
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(load_shed::handle_overload))
+                .load_shed()
+                .layer(GlobalConcurrencyLimitLayer::new(cfg.max_in_flight_requests)),
+        );
+    if let Some(account_status) = account_status {
+        app = app.layer(Extension(account_status));
+    }
+    app
+}
@@ -1,20 +1,20 @@
-use shared::data::state::AppState;
-use shared::utils::config::AppConfig;
-use shared::utils::logger;
-use axum::http::{Method, header};
-use axum::{Extension, Router};
 use dotenvy::dotenv;
 use model::migration::{Migrator, MigratorTrait};
 use model::models::Models;
+use node_server::build_router;
+use node_server::shared::utils::config::AppConfig;
 use repository::repositories::Repositories;
+use repository::repositories::encryption::data::validate_secrets;
+use node_server::shared::utils::{migrations, scheduler};
 use std::net::SocketAddr;
-use tower_http::cors::CorsLayer;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-pub mod features;
-pub mod shared;
-
-async fn health_check() -> &'static str {
-    "OK"
+async fn run_migrations(cfg: &AppConfig) -> Result<(), sea_orm::DbErr> {
+    let models = Models::new(&cfg.database_url).await?;
+    Migrator::up(&models.db, None).await?;
+    tracing::info!("migrations applied");
+    Ok(())
 }
 
 #[tokio::main]
@@ -22,39 +22,68 @@ async fn main() {
     let _ = dotenv();
     // Initialize global logger
     logger::init();
-    let cfg = AppConfig::from_env();
+    let cfg = match AppConfig::try_from_env() {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            tracing::error!("invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // `main migrate` applies pending migrations and exits, for running them once per deploy
+    if std::env::args().nth(1).as_deref() == Some("migrate") {
+        if let Err(e) = run_migrations(&cfg).await {
+            tracing::error!("Failed to run migrations: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Err(e) = validate_secrets(cfg.production) {
+        tracing::error!("refusing to start in production: {}", e);
+        std::process::exit(1);
+    }
     let models = match Models::new(&cfg.database_url).await {
         Ok(m) => m,
         Err(e) => {
-            tracing::info!("Failed to connect to the database: {}", e);
-            return;
+            tracing::error!("Failed to connect to the database: {}", e);
+            std::process::exit(1);
         }
     };
 
-    if let Err(e) = Migrator::up(&models.db, None).await {
-        tracing::info!("Failed to run migrations: {}", e);
-        return;
+    // Multi-replica deploys turn this off and run `main migrate` once instead, so replicas don't race
+    if let Err(e) = migrations::run_on_start(&cfg, &models.db).await {
+        tracing::error!("Failed to run migrations: {}", e);
+        std::process::exit(1);
     }
     let repositories = Repositories::new();
 
-    let cors = CorsLayer::new()
-        .allow_origin(tower_http::cors::Any)
-        .allow_methods([
-            Method::GET,
-            Method::POST,
-            Method::PUT,
-            Method::PATCH,
-            Method::DELETE,
-            Method::OPTIONS,
-        ])
-        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]);
+    // The broker is only needed for email dispatch, so check it in the background and just warn
+    let queue = repositories.queue.clone();
+    tokio::spawn(async move {
+        if let Err(e) = queue.check_connection().await {
+            tracing::warn!("message broker unreachable, emails will not be sent until it is: {}", e);
+        }
+    });
+
+    // Ctrl-C stops background tasks and lets in-flight requests finish before exiting
+    let shutdown = CancellationToken::new();
+    let signal = shutdown.clone();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        tracing::info!("shutdown requested");
+        signal.cancel();
+    });
+
+    if cfg.cleanup_interval_seconds > 0 {
+        scheduler::spawn_cleanup(
+            models.db.clone(),
+            Duration::from_secs(cfg.cleanup_interval_seconds),
+            shutdown.clone(),
+        );
+    }
 
-    let app = Router::new()
-        .route("/health", axum::routing::get(health_check))
-        .nest("/api/", features::router())
-        .layer(Extension(repositories.encryption.clone()))
-        .with_state(AppState::new(repositories, models))
-        .layer(cors);
+    let app = build_router(&cfg, repositories, models);
 
     let address = SocketAddr::from(([127, 0, 0, 1], 8000));
 
@@ -65,7 +94,9 @@ async fn main() {
     // Log active server port
     tracing::info!("running on port: {}", address.port());
 
-    axum::serve(tcp_listener, app)
+    // Peer address is the ClientContext fallback when proxy headers are absent or untrusted
+    axum::serve(tcp_listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown.cancelled_owned())
         .await
         .expect("Failed to start server");
 }
@@ -1,37 +1,26 @@
 pub mod state;
 
 use repository::repositories::encryption::data::{Claims, Sub};
+use axum::{http::StatusCode, response::{IntoResponse, Response}, Json};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum ModelStatus {
-    Success,
-    Error,
-    Pending,
-    Processing,
-}
-
+/// Error envelope returned by every handler: `{ "status": false, "code": "...", "message": "..." }`
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ErrorResponse {
     pub status: bool,
+    /// Stable machine-readable code (e.g. "EMAIL_EXISTS"); `message` is for humans and may change
+    pub code: String,
     pub message: String,
 }
 
+/// Success envelope returned by every handler: `{ "status": true, "data": ... }`
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SuccessResponse<T> {
     pub status: bool,
     pub data: T,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub message: Option<String>,
-    pub data: Option<T>,
-    pub status: ModelStatus,
-}
-
 impl<T> SuccessResponse<T> {
     pub fn new(data: T) -> Self {
         Self { status: true, data }
@@ -39,8 +28,32 @@ impl<T> SuccessResponse<T> {
 }
 
 impl ErrorResponse {
-    pub fn new(message: String) -> Self {
-        Self { status: false, message }
+    pub fn new(code: &str, message: String) -> Self {
+        Self { status: false, code: code.to_string(), message }
+    }
+
+    /// Build the full HTTP response for an error status
+    pub fn response(status: StatusCode, code: &str, message: impl Into<String>) -> Response {
+        (status, Json(Self::new(code, message.into()))).into_response()
+    }
+
+    /// Split a response built by `response` back into its status and envelope
+    #[cfg(test)]
+    pub(crate) async fn read(response: Response) -> (StatusCode, Self) {
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// Assert each error converts to an envelope with its expected status and code
+    #[cfg(test)]
+    pub(crate) async fn assert_mappings<'a, E: IntoResponse>(cases: impl IntoIterator<Item = (E, StatusCode, &'a str)>) {
+        for (err, status, code) in cases {
+            let (got, body) = Self::read(err.into_response()).await;
+            assert_eq!(got, status, "{}", code);
+            assert_eq!(body.code, code);
+            assert!(!body.status);
+        }
     }
 }
 
@@ -52,6 +65,44 @@ pub struct AuthUser {
     pub email_address: String,
 }
 
+/// A validated refresh token: the user it was issued to and its session id
+#[derive(Debug, Clone)]
+pub struct RefreshToken {
+    pub auth_user: AuthUser,
+    pub jti: Option<String>,
+}
+
+/// Admin identity carried in admin access tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthAdmin {
+    pub id: Uuid,
+    pub email_address: String,
+}
+
+impl AuthAdmin {
+    pub fn from_admin(admin: model::models::admin::entity::Model) -> Self {
+        Self {
+            id: admin.id,
+            email_address: admin.email_address,
+        }
+    }
+
+    /// The payload is a JSON string inside `sub`, which deserializes as either `Sub` variant
+    pub fn from_claims(claims: Claims) -> Result<AuthAdmin, String> {
+        let parsed = match &claims.sub {
+            Sub::Text(s) => serde_json::from_str::<AuthAdmin>(s),
+            Sub::Json(v) => match v.as_str() {
+                Some(s) => serde_json::from_str::<AuthAdmin>(s),
+                None => serde_json::from_value::<AuthAdmin>(v.clone()),
+            },
+        };
+        parsed.map_err(|err| {
+            tracing::error!(msg = "invalid admin token claims", err = ?err);
+            "invalid token claims".to_string()
+        })
+    }
+}
+
 impl AuthUser {
     pub fn from_user(user: model::models::user::Model) -> Self {
         Self {
@@ -62,12 +113,12 @@ impl AuthUser {
     }
 
     pub fn from_claims(claims: Claims) -> Result<AuthUser, String> {
-        return match &claims.sub {
+        match &claims.sub {
             Sub::Text(s) => match serde_json::from_str::<AuthUser>(s) {
                 Ok(auth_user) => Ok(auth_user),
                 Err(err) => {
                     tracing::error!(msg = "invalid string token claims", err = ?err);
-                    return Err("invalid token claims".to_string())
+                    Err("invalid token claims".to_string())
                 },
             },
             Sub::Json(v) => {
@@ -76,7 +127,7 @@ impl AuthUser {
                         Ok(auth_user) => Ok(auth_user),
                         Err(err) => {
                             tracing::error!(msg = "invalid string token claims", err = ?err);
-                            return Err("invalid token claims".to_string())
+                            Err("invalid token claims".to_string())
                         },
                     }
                 } else {
@@ -84,11 +135,33 @@ impl AuthUser {
                         Ok(auth_user) => Ok(auth_user),
                         Err(err) => {
                             tracing::error!(msg = "invalid token claims", err = ?err);
-                            return Err("invalid token claims".to_string())
+                            Err("invalid token claims".to_string())
                         },
                     }
                 }
             },
-        };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(value: serde_json::Value) -> Vec<String> {
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn envelopes_serialize_to_the_documented_keys() {
+        let success = serde_json::to_value(SuccessResponse::new(serde_json::json!({ "id": 1 }))).unwrap();
+        assert_eq!(keys(success.clone()), ["data", "status"]);
+        assert_eq!(success["status"], true);
+
+        let error = serde_json::to_value(ErrorResponse::new("NOT_FOUND", "missing".to_string())).unwrap();
+        assert_eq!(keys(error.clone()), ["code", "message", "status"]);
+        assert_eq!(error["status"], false);
+    }
+}
@@ -1,10 +1,13 @@
 use model::models;
 use repository::repositories;
+use std::time::Instant;
 
 #[derive(Clone)]
 pub struct AppState {
     pub repository: repositories::Repositories,
     pub model: models::Models,
+    /// When the state was built, i.e. server start, for uptime reporting
+    pub started_at: Instant,
 }
 
 impl AppState {
@@ -12,6 +15,6 @@ impl AppState {
         repository: repositories::Repositories,
         model: models::Models,
     ) -> Self {
-        Self { repository, model }
+        Self { repository, model, started_at: Instant::now() }
     }
 }
\ No newline at end of file
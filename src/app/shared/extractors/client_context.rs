@@ -0,0 +1,159 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, HeaderMap},
+};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+/// Client address, user agent and request id of the current request, for audit logging,
+/// rate limiting and correlating queued work
+#[derive(Debug, Clone, Default)]
+pub struct ClientContext {
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl ClientContext {
+    /// Resolve the client from forwarding headers set by the `trusted_hops` proxies in front of the
+    /// app, or the peer address when there are none (0) or the headers don't reach that far
+    pub fn from_parts(headers: &HeaderMap, peer: Option<SocketAddr>, trusted_hops: usize) -> Self {
+        let forwarded = match trusted_hops {
+            0 => None,
+            hops => match headers.get("x-forwarded-for") {
+                Some(_) => forwarded_for(headers, hops),
+                None => real_ip(headers),
+            },
+        };
+
+        let user_agent = headers
+            .get(axum::http::header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        // Set by the request_id middleware on every routed request
+        let request_id = headers
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        Self {
+            ip: forwarded.or(peer.map(|addr| addr.ip())),
+            user_agent,
+            request_id,
+        }
+    }
+}
+
+/// How many proxies in front of the app append to `X-Forwarded-For`: 0 unless `TRUST_PROXY_HEADERS`
+/// is set, then `TRUSTED_PROXY_HOPS` (default 1)
+fn trusted_proxy_hops() -> usize {
+    let trusted = std::env::var("TRUST_PROXY_HEADERS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    if !trusted {
+        return 0;
+    }
+    std::env::var("TRUSTED_PROXY_HOPS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1)
+}
+
+/// The `hops`-th address from the right of `X-Forwarded-For` ("client, proxy1, proxy2"). Each proxy
+/// appends the address it saw, so anything left of the trusted ones may be made up by the client
+fn forwarded_for(headers: &HeaderMap, hops: usize) -> Option<IpAddr> {
+    // Repeated headers are one comma-separated list, in order
+    let value = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(",");
+    let chain: Vec<&str> = value.split(',').map(str::trim).collect();
+    chain.len().checked_sub(hops).and_then(|i| chain[i].parse().ok())
+}
+
+fn real_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+
+        Ok(ClientContext::from_parts(&parts.headers, peer, trusted_proxy_hops()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_map(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn peer() -> Option<SocketAddr> {
+        Some("10.0.0.1:443".parse().unwrap())
+    }
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn uses_the_peer_address_when_headers_are_untrusted() {
+        let headers = header_map(&[("x-forwarded-for", "203.0.113.7"), ("x-real-ip", "203.0.113.8")]);
+        assert_eq!(ClientContext::from_parts(&headers, peer(), 0).ip, ip("10.0.0.1"));
+        assert_eq!(ClientContext::from_parts(&HeaderMap::new(), peer(), 1).ip, ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn ignores_client_supplied_hops_left_of_the_trusted_proxy() {
+        // The client sent "1.2.3.4"; the proxy appended the address it actually saw
+        let headers = header_map(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7")]);
+        assert_eq!(ClientContext::from_parts(&headers, peer(), 1).ip, ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn skips_every_trusted_hop_of_a_multi_hop_chain() {
+        let headers = header_map(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7, 10.0.0.9")]);
+        assert_eq!(ClientContext::from_parts(&headers, peer(), 2).ip, ip("203.0.113.7"));
+
+        let headers = header_map(&[("x-forwarded-for", "1.2.3.4"), ("x-forwarded-for", "203.0.113.7, 10.0.0.9")]);
+        assert_eq!(ClientContext::from_parts(&headers, peer(), 2).ip, ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn falls_back_to_the_peer_when_the_chain_is_shorter_than_the_trusted_hops() {
+        let headers = header_map(&[("x-forwarded-for", "203.0.113.7")]);
+        assert_eq!(ClientContext::from_parts(&headers, peer(), 2).ip, ip("10.0.0.1"));
+
+        let headers = header_map(&[("x-forwarded-for", "not-an-ip")]);
+        assert_eq!(ClientContext::from_parts(&headers, peer(), 1).ip, ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn uses_x_real_ip_without_x_forwarded_for() {
+        let headers = header_map(&[("x-real-ip", " 203.0.113.8 ")]);
+        assert_eq!(ClientContext::from_parts(&headers, peer(), 1).ip, ip("203.0.113.8"));
+    }
+}
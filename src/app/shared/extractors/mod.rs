@@ -0,0 +1,4 @@
+pub mod client_context;
+pub mod validated_json;
+pub mod pagination;
+pub mod org_context;
@@ -0,0 +1,64 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::Response,
+};
+use uuid::Uuid;
+
+use model::models::organization_user::repo::{OrganizationUserRepositoryError, OrganizationUserRepositoryTrait};
+use crate::shared::data::{state::AppState, AuthUser, ErrorResponse};
+
+/// Header naming the organization a request acts on
+pub const ORGANIZATION_HEADER: &str = "x-organization-id";
+
+/// Organization selected by `X-Organization-Id`, checked against the authenticated user's
+/// memberships. Only usable behind the user auth middleware
+#[derive(Debug, Clone)]
+pub struct OrgContext {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    /// The user's role within the organization
+    pub role: String,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for OrgContext {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let auth_user = parts.extensions.get::<AuthUser>().cloned().ok_or_else(|| {
+            ErrorResponse::response(StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "authentication required")
+        })?;
+
+        let raw = parts
+            .headers
+            .get(ORGANIZATION_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| {
+                ErrorResponse::response(StatusCode::BAD_REQUEST, "MISSING_ORGANIZATION", "X-Organization-Id header is required")
+            })?;
+        let organization_id = Uuid::parse_str(raw).map_err(|_| {
+            ErrorResponse::response(StatusCode::BAD_REQUEST, "INVALID_ORGANIZATION", "X-Organization-Id must be a UUID")
+        })?;
+
+        match state.model.organization_user.get_membership(organization_id, auth_user.id).await {
+            Ok(membership) => Ok(OrgContext {
+                organization_id,
+                user_id: auth_user.id,
+                role: membership.role,
+            }),
+            Err(OrganizationUserRepositoryError::NotFound(_)) => Err(ErrorResponse::response(
+                StatusCode::FORBIDDEN,
+                "NOT_ORGANIZATION_MEMBER",
+                "you are not a member of this organization",
+            )),
+            Err(e) => {
+                tracing::error!(error = %e, "organization membership lookup failed");
+                Err(ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", "unable to verify organization membership"))
+            }
+        }
+    }
+}
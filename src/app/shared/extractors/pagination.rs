@@ -0,0 +1,83 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use model::shared::PaginationOptions;
+
+use crate::shared::data::ErrorResponse;
+
+/// `?page=&limit=&sort_by=&sort_order=` with `limit` clamped to 1..=100, `page` to ≥ 1
+/// and `sort_order` restricted to `asc`/`desc`
+#[derive(Debug)]
+pub struct Pagination(pub PaginationOptions);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Pagination
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(options) = Query::<PaginationOptions>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| {
+                (StatusCode::BAD_REQUEST, Json(ErrorResponse::new("INVALID_QUERY", rejection.body_text()))).into_response()
+            })?;
+
+        let options = options.clamped();
+        if let Some(order) = options.sort_order.as_deref() {
+            if order != "asc" && order != "desc" {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::new("INVALID_QUERY", "sort_order must be asc or desc".to_string())),
+                )
+                    .into_response());
+            }
+        }
+
+        Ok(Pagination(options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    async fn extract(query: &str) -> Result<PaginationOptions, StatusCode> {
+        let (mut parts, _) = Request::builder().uri(format!("/users?{}", query)).body(()).unwrap().into_parts();
+        Pagination::from_request_parts(&mut parts, &())
+            .await
+            .map(|Pagination(options)| options)
+            .map_err(|response| response.status())
+    }
+
+    #[tokio::test]
+    async fn limit_and_page_are_clamped() {
+        let options = extract("limit=1000&page=0").await.unwrap();
+        assert_eq!(options.limit, Some(PaginationOptions::MAX_LIMIT));
+        assert_eq!(options.page, Some(1));
+
+        let options = extract("limit=0&page=-3").await.unwrap();
+        assert_eq!(options.limit, Some(1));
+        assert_eq!(options.page, Some(1));
+    }
+
+    #[tokio::test]
+    async fn missing_values_get_the_defaults() {
+        let options = extract("").await.unwrap();
+        assert_eq!(options.limit, PaginationOptions::default().limit);
+        assert_eq!(options.page, Some(1));
+    }
+
+    #[tokio::test]
+    async fn bad_values_are_rejected() {
+        assert_eq!(extract("limit=ten").await.unwrap_err(), StatusCode::BAD_REQUEST);
+        assert_eq!(extract("sort_order=sideways").await.unwrap_err(), StatusCode::BAD_REQUEST);
+        assert!(extract("sort_order=desc").await.is_ok());
+    }
+}
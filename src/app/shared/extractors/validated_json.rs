@@ -0,0 +1,52 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::shared::data::ErrorResponse;
+use crate::shared::utils::email::normalize_email;
+
+/// Request-body checks run by `ValidatedJson` after deserialization; the default accepts everything
+pub trait Validate {
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// `Json` that answers parse and validation failures with an `ErrorResponse` body instead of plain text
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        // Syntax errors map to 400, type/shape errors to 422, wrong content type to 415
+        let Json(value) = Json::<T>::from_request(req, state).await.map_err(|rejection| {
+            (rejection.status(), Json(ErrorResponse::new("INVALID_JSON", rejection.body_text()))).into_response()
+        })?;
+
+        if let Err(msg) = value.validate() {
+            return Err((StatusCode::UNPROCESSABLE_ENTITY, Json(ErrorResponse::new("VALIDATION_ERROR", msg))).into_response());
+        }
+
+        Ok(ValidatedJson(value))
+    }
+}
+
+/// Shared check for request fields that must be a plausible email address
+pub fn validate_email(email: &str) -> Result<(), String> {
+    let email = normalize_email(email);
+    match email.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && domain.contains('.') => Ok(()),
+        _ => Err("email_address is invalid".to_string()),
+    }
+}
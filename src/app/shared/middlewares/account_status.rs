@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use model::models::user::repo::{UserRepository, UserRepositoryError, UserRepositoryTrait};
+use uuid::Uuid;
+
+/// Account state a still-valid access token is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountStatus {
+    Active,
+    Banned,
+    /// Soft-deleted or no longer in the database
+    Missing,
+}
+
+/// Short-lived cache of account status, so `require_user_auth` can reject tokens of banned
+/// or deleted users without a database round trip on every request.
+///
+/// Enabled by default; set `AUTH_ACCOUNT_CHECK=false` to keep tokens fully stateless.
+/// Entries live for `AUTH_ACCOUNT_CHECK_TTL_SECONDS` (default 30).
+#[derive(Clone)]
+pub struct AccountStatusCache {
+    user_repo: UserRepository,
+    ttl: Duration,
+    entries: Arc<RwLock<HashMap<Uuid, (Instant, AccountStatus)>>>,
+}
+
+impl AccountStatusCache {
+    pub fn new(user_repo: UserRepository, ttl: Duration) -> Self {
+        Self {
+            user_repo,
+            ttl,
+            entries: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Build the cache from the environment, or `None` when the check is turned off
+    pub fn from_env(user_repo: UserRepository) -> Option<Self> {
+        let enabled = std::env::var("AUTH_ACCOUNT_CHECK")
+            .map(|v| !v.eq_ignore_ascii_case("false"))
+            .unwrap_or(true);
+        if !enabled {
+            return None;
+        }
+
+        let ttl = std::env::var("AUTH_ACCOUNT_CHECK_TTL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30);
+        Some(Self::new(user_repo, Duration::from_secs(ttl)))
+    }
+
+    pub async fn status(&self, user_id: Uuid) -> Result<AccountStatus, UserRepositoryError> {
+        if let Some((fetched_at, status)) = self.entries.read().ok().and_then(|e| e.get(&user_id).copied()) {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(status);
+            }
+        }
+
+        let status = match self.user_repo.get_by_id(user_id).await {
+            Ok(user) if user.deleted_at.is_some() => AccountStatus::Missing,
+            Ok(user) if user.peripheral_is_banned => AccountStatus::Banned,
+            Ok(_) => AccountStatus::Active,
+            Err(UserRepositoryError::NotFound(_)) => AccountStatus::Missing,
+            Err(e) => return Err(e),
+        };
+
+        if let Ok(mut entries) = self.entries.write() {
+            // Drop stale entries so the map doesn't grow with every user ever seen
+            let ttl = self.ttl;
+            entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < ttl);
+            entries.insert(user_id, (Instant::now(), status));
+        }
+        Ok(status)
+    }
+
+    /// Forget a cached status, e.g. right after an admin bans or unbans the user
+    pub fn invalidate(&self, user_id: Uuid) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.remove(&user_id);
+        }
+    }
+}
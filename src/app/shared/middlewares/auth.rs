@@ -7,10 +7,11 @@ use axum::{
     extract::Request,
 };
 
-use crate::shared::data::{AuthUser, state::AppState};
+use crate::shared::data::{AuthAdmin, AuthUser, RefreshToken, state::AppState};
 use crate::shared::data::ErrorResponse;
+use crate::shared::middlewares::account_status::{AccountStatus, AccountStatusCache};
 
-use repository::repositories::encryption::{EncryptionRepository, EncryptionRepositoryTrait, data::{Claims, Token, Sub}};
+use repository::repositories::encryption::{EncryptionRepositoryTrait, data::{Claims, Token}};
 use std::sync::Arc;
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
@@ -19,85 +20,78 @@ use async_trait::async_trait;
 use axum::response::IntoResponse;
 
 fn unauthorized(message: &str) -> Response {
-    let body = axum::Json(ErrorResponse::new(message.to_string()));
+    let body = axum::Json(ErrorResponse::new("UNAUTHORIZED", message.to_string()));
     (StatusCode::UNAUTHORIZED, body).into_response()
 }
 
-pub async fn require_user_auth(mut req: Request, next: Next) -> Result<Response, Infallible> {
-    // Prefer EncryptionRepository from request extensions; fall back to AppState
-    let encryption: Arc<EncryptionRepository> = if let Some(enc) = req.extensions().get::<Arc<EncryptionRepository>>() {
-        enc.clone()
-    } else if let Some(app_state) = req.extensions().get::<AppState>() {
-        app_state.repository.encryption.clone()
-    } else {
-        return Ok(unauthorized("missing encryption repository"));
-    };
-
-    // Get Authorization header
-    let headers: &HeaderMap = req.headers();
+/// The token from `Authorization: Bearer <token>`, with surrounding whitespace and quotes removed;
+/// a client-safe message on failure
+pub fn extract_bearer(headers: &HeaderMap) -> Result<&str, &'static str> {
     let Some(auth_header_value) = headers.get(axum::http::header::AUTHORIZATION) else {
-        return Ok(unauthorized("missing authorization header"));
+        return Err("missing authorization header");
     };
-
-    let auth_str = match auth_header_value.to_str() {
-        Ok(s) => s,
-        Err(_) => return Ok(unauthorized("invalid authorization header")),
+    let Ok(auth_str) = auth_header_value.to_str() else {
+        return Err("invalid authorization header");
     };
-    // Expect Bearer token
     let Some(token) = auth_str.strip_prefix("Bearer ") else {
-        return Ok(unauthorized("invalid bearer token"));
+        return Err("invalid bearer token");
     };
+    Ok(token.trim().trim_matches('"'))
+}
 
-    // Normalize token: trim whitespace and surrounding quotes if present
-    let token = token.trim();
-    let token = token.trim_matches('"');
-
-    // tracing::info!("token {}", token);
-    // Decode user access token
-    let claim = match encryption.decode_token(&token, Token::user_access_token()) {
-        Ok(v) => v,
+/// Reject a valid token whose account was banned or deleted after it was issued.
+///
+/// Only runs when an `AccountStatusCache` extension is installed; returns the response to send on rejection.
+async fn check_account_status(cache: Option<AccountStatusCache>, auth_user: &AuthUser) -> Option<Response> {
+    let cache = cache?;
+    match cache.status(auth_user.id).await {
+        Ok(AccountStatus::Active) => None,
+        Ok(AccountStatus::Banned) => Some(ErrorResponse::response(StatusCode::FORBIDDEN, "ACCOUNT_BANNED", "Account is banned")),
+        Ok(AccountStatus::Missing) => Some(unauthorized("account no longer exists")),
         Err(err) => {
-            tracing::error!(msg = "invalid or expired token", err = ?err);
-            return Ok(unauthorized("invalid or expired token"))
-        },
-    };
-
-    // Decode Claims: handle both pasted JSON string and JSON value
-    let claims: Claims = if let Some(s) = claim.as_str() {
-        match serde_json::from_str::<Claims>(s) {
-            Ok(c) => c,
-            Err(err) => {
-                tracing::error!(msg = "invalid token claims string", err = ?err);
-                return Ok(unauthorized("invalid token claims"))
-            }
+            tracing::error!(msg = "account status check failed", err = %err);
+            Some(ErrorResponse::response(StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", "Failed to verify account status"))
         }
+    }
+}
+
+pub async fn require_user_auth(mut req: Request, next: Next) -> Result<Response, Infallible> {
+    // Prefer the encryption repository from request extensions; fall back to AppState
+    let encryption: Arc<dyn EncryptionRepositoryTrait> = if let Some(enc) = req.extensions().get::<Arc<dyn EncryptionRepositoryTrait>>() {
+        enc.clone()
+    } else if let Some(app_state) = req.extensions().get::<AppState>() {
+        app_state.repository.encryption.clone()
     } else {
-        match serde_json::from_value::<Claims>(claim) {
-            Ok(c) => c,
-            Err(err) => {
-                tracing::error!(msg = "invalid token claims value", err = ?err);
-                return Ok(unauthorized("invalid token claims"))
-            }
-        }
+        return Ok(unauthorized("missing encryption repository"));
     };
 
-    let auth_user: AuthUser = match AuthUser::from_claims(claims) {
-        Ok(u) => u,
-        Err(err) => {
-            tracing::error!(msg = "invalid token claims", err = ?err);
-            return Ok(unauthorized("invalid token claims"))
-        },
+    let token = match extract_bearer(req.headers()) {
+        Ok(token) => token,
+        Err(message) => return Ok(unauthorized(message)),
     };
 
+    let auth_user = match decode_access_token(encryption.as_ref(), token) {
+        Ok(auth_user) => auth_user,
+        Err(message) => return Ok(unauthorized(message)),
+    };
+
+    if let Some(rejection) = check_account_status(req.extensions().get::<AccountStatusCache>().cloned(), &auth_user).await {
+        return Ok(rejection);
+    }
+
     // Attach to request extensions for downstream handlers
     req.extensions_mut().insert(auth_user);
 
     Ok(next.run(req).await)
 }
 
+/// Validate a refresh token from the `Authorization` header and inject it as a `RefreshToken`.
+///
+/// A request without the header is passed through untouched so the handler can fall back
+/// to a refresh token sent in the JSON body.
 pub async fn require_refresh_auth(mut req: Request, next: Next) -> Result<Response, Infallible> {
-    // Prefer EncryptionRepository from request extensions; fall back to AppState
-    let encryption: Arc<EncryptionRepository> = if let Some(enc) = req.extensions().get::<Arc<EncryptionRepository>>() {
+    // Prefer the encryption repository from request extensions; fall back to AppState
+    let encryption: Arc<dyn EncryptionRepositoryTrait> = if let Some(enc) = req.extensions().get::<Arc<dyn EncryptionRepositoryTrait>>() {
         enc.clone()
     } else if let Some(app_state) = req.extensions().get::<AppState>() {
         app_state.repository.encryption.clone()
@@ -105,59 +99,93 @@ pub async fn require_refresh_auth(mut req: Request, next: Next) -> Result<Respon
         return Ok(unauthorized("missing encryption repository"));
     };
 
-    // Get Authorization header
-    let headers: &HeaderMap = req.headers();
-    let Some(auth_header_value) = headers.get(axum::http::header::AUTHORIZATION) else {
-        return Ok(unauthorized("missing authorization header"));
+    if !req.headers().contains_key(axum::http::header::AUTHORIZATION) {
+        return Ok(next.run(req).await);
+    }
+    let token = match extract_bearer(req.headers()) {
+        Ok(token) => token,
+        Err(message) => return Ok(unauthorized(message)),
     };
 
-    let auth_str = match auth_header_value.to_str() {
-        Ok(s) => s,
-        Err(_) => return Ok(unauthorized("invalid authorization header")),
+    let refresh_token = match decode_refresh_token(encryption.as_ref(), token) {
+        Ok(t) => t,
+        Err(message) => return Ok(unauthorized(message)),
     };
 
-    // Expect Bearer token
-    let Some(token) = auth_str.strip_prefix("Bearer ") else {
-        return Ok(unauthorized("invalid bearer token"));
-    };
+    // Attach to request extensions for downstream handlers
+    req.extensions_mut().insert(refresh_token);
+
+    Ok(next.run(req).await)
+}
+
+/// Decode a user access token into its `AuthUser`, returning a client-safe message on failure.
+/// Shared by `require_user_auth` and the `AuthUser` extractor so both accept exactly the same tokens
+pub fn decode_access_token(encryption: &dyn EncryptionRepositoryTrait, token: &str) -> Result<AuthUser, &'static str> {
+    let claim = encryption.decode_token(token, Token::user_access_token()).map_err(|err| {
+        tracing::error!(msg = "invalid or expired token", err = ?err);
+        "invalid or expired token"
+    })?;
+
+    // Claims arrive either as a JSON value or, from some services, as a JSON string
+    let claims: Claims = match claim.as_str() {
+        Some(s) => serde_json::from_str(s),
+        None => serde_json::from_value(claim),
+    }
+    .map_err(|err| {
+        tracing::error!(msg = "invalid token claims", err = ?err);
+        "invalid token claims"
+    })?;
 
+    AuthUser::from_claims(claims).map_err(|_| "invalid token claims")
+}
+
+/// Decode a user refresh token into a `RefreshToken`, returning a client-safe message on failure
+pub fn decode_refresh_token(encryption: &dyn EncryptionRepositoryTrait, token: &str) -> Result<RefreshToken, &'static str> {
     // Normalize token: trim whitespace and surrounding quotes if present
-    let token = token.trim();
-    let token = token.trim_matches('"');
+    let token = token.trim().trim_matches('"');
+
+    let claim = encryption
+        .decode_token(token, Token::user_refresh_token())
+        .map_err(|_| "invalid or expired token")?;
+
+    let claims: Claims = serde_json::from_value(claim).map_err(|_| "invalid token claims")?;
+    let jti = claims.jti.clone();
+    let auth_user = AuthUser::from_claims(claims).map_err(|_| "invalid token claims")?;
+    Ok(RefreshToken { auth_user, jti })
+}
+
+pub async fn require_admin_auth(mut req: Request, next: Next) -> Result<Response, Infallible> {
+    // Prefer the encryption repository from request extensions; fall back to AppState
+    let encryption: Arc<dyn EncryptionRepositoryTrait> = if let Some(enc) = req.extensions().get::<Arc<dyn EncryptionRepositoryTrait>>() {
+        enc.clone()
+    } else if let Some(app_state) = req.extensions().get::<AppState>() {
+        app_state.repository.encryption.clone()
+    } else {
+        return Ok(unauthorized("missing encryption repository"));
+    };
 
-    // Decode refresh token
-    let claim = match encryption.decode_token(&token, Token::user_refresh_token()) {
+    let token = match extract_bearer(req.headers()) {
+        Ok(token) => token,
+        Err(message) => return Ok(unauthorized(message)),
+    };
+
+    // Admin tokens are signed with their own key and audience, so user tokens never pass here
+    let claim = match encryption.decode_token(token, Token::admin_access_token()) {
         Ok(v) => v,
-        Err(_) => return Ok(unauthorized("invalid or expired token")),
+        Err(err) => {
+            tracing::error!(msg = "invalid or expired admin token", err = ?err);
+            return Ok(unauthorized("invalid or expired token"));
+        }
     };
 
-    // Parse Claims then extract AuthUser from sub
-    let claims: Claims = match serde_json::from_value(claim) {
-        Ok(c) => c,
-        Err(_) => return Ok(unauthorized("invalid token claims")),
+    let Ok(claims) = serde_json::from_value::<Claims>(claim) else {
+        return Ok(unauthorized("invalid token claims"));
     };
-    let auth_user: AuthUser = match &claims.sub {
-        Sub::Text(s) => match serde_json::from_str::<AuthUser>(s) {
-            Ok(u) => u,
-            Err(_) => return Ok(unauthorized("invalid token claims")),
-        },
-        Sub::Json(v) => {
-            if let Some(s) = v.as_str() {
-                match serde_json::from_str::<AuthUser>(s) {
-                    Ok(u) => u,
-                    Err(_) => return Ok(unauthorized("invalid token claims")),
-                }
-            } else {
-                match serde_json::from_value::<AuthUser>(v.clone()) {
-                    Ok(u) => u,
-                    Err(_) => return Ok(unauthorized("invalid token claims")),
-                }
-            }
-        },
+    let Ok(auth_admin) = AuthAdmin::from_claims(claims) else {
+        return Ok(unauthorized("invalid token claims"));
     };
 
-    // Attach to request extensions for downstream handlers
-    req.extensions_mut().insert(auth_user);
+    req.extensions_mut().insert(auth_admin);
 
     Ok(next.run(req).await)
 }
@@ -168,53 +196,71 @@ impl FromRequestParts<AppState> for AuthUser {
     type Rejection = Response;
 
     async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
-        // Get Authorization header
-        let Some(auth_header_value) = parts.headers.get(axum::http::header::AUTHORIZATION) else {
-            return Err(unauthorized("missing authorization header"));
-        };
-
-        let auth_str = match auth_header_value.to_str() {
-            Ok(s) => s,
-            Err(_) => return Err(unauthorized("invalid authorization header")),
-        };
-
-        // Expect Bearer token
-        let Some(token) = auth_str.strip_prefix("Bearer ") else {
-            return Err(unauthorized("invalid bearer token"));
-        };
-
-        // Decode user access token using application state
-        let encryption = &state.repository.encryption;
-        let claim = match encryption.decode_token(&token, Token::user_access_token()) {
-            Ok(v) => v,
-            Err(_) => return Err(unauthorized("invalid or expired token")),
-        };
-
-        // Parse Claims then extract AuthUser from sub
-        let claims: Claims = match serde_json::from_value(claim) {
-            Ok(c) => c,
-            Err(_) => return Err(unauthorized("invalid token claims")),
-        };
-        let auth_user: AuthUser = match &claims.sub {
-            Sub::Text(s) => match serde_json::from_str::<AuthUser>(s) {
-                Ok(u) => u,
-                Err(_) => return Err(unauthorized("invalid token claims")),
-            },
-            Sub::Json(v) => {
-                if let Some(s) = v.as_str() {
-                    match serde_json::from_str::<AuthUser>(s) {
-                        Ok(u) => u,
-                        Err(_) => return Err(unauthorized("invalid token claims")),
-                    }
-                } else {
-                    match serde_json::from_value::<AuthUser>(v.clone()) {
-                        Ok(u) => u,
-                        Err(_) => return Err(unauthorized("invalid token claims")),
-                    }
-                }
-            },
-        };
+        // Already decoded and status-checked by require_user_auth or an earlier extraction
+        if let Some(auth_user) = parts.extensions.get::<AuthUser>() {
+            return Ok(auth_user.clone());
+        }
+
+        let token = extract_bearer(&parts.headers).map_err(unauthorized)?;
 
+        let auth_user = decode_access_token(state.repository.encryption.as_ref(), token).map_err(unauthorized)?;
+
+        if let Some(rejection) = check_account_status(parts.extensions.get::<AccountStatusCache>().cloned(), &auth_user).await {
+            return Err(rejection);
+        }
+
+        parts.extensions.insert(auth_user.clone());
         Ok(auth_user)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::AUTHORIZATION;
+    use repository::repositories::encryption::{EncryptionRepository, EncryptionRepositoryExt};
+    use uuid::Uuid;
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, value.parse().unwrap());
+        headers
+    }
+
+    fn user() -> AuthUser {
+        AuthUser { id: Uuid::new_v4(), first_name: "Ada".to_string(), email_address: "ada@example.com".to_string() }
+    }
+
+    #[test]
+    fn extract_bearer_strips_padding_and_quotes() {
+        assert_eq!(extract_bearer(&headers("Bearer abc.def")), Ok("abc.def"));
+        assert_eq!(extract_bearer(&headers("Bearer   abc.def  ")), Ok("abc.def"));
+        assert_eq!(extract_bearer(&headers("Bearer \"abc.def\"")), Ok("abc.def"));
+        assert_eq!(extract_bearer(&headers("Bearer  \"abc.def\" ")), Ok("abc.def"));
+    }
+
+    #[test]
+    fn extract_bearer_rejects_missing_or_malformed_headers() {
+        assert_eq!(extract_bearer(&HeaderMap::new()), Err("missing authorization header"));
+        assert_eq!(extract_bearer(&headers("abc.def")), Err("invalid bearer token"));
+        assert_eq!(extract_bearer(&headers("Token abc.def")), Err("invalid bearer token"));
+        assert_eq!(extract_bearer(&headers("bearer abc.def")), Err("invalid bearer token"));
+
+        let mut non_ascii = HeaderMap::new();
+        non_ascii.insert(AUTHORIZATION, axum::http::HeaderValue::from_bytes(b"Bearer \xff").unwrap());
+        assert_eq!(extract_bearer(&non_ascii), Err("invalid authorization header"));
+    }
+
+    #[test]
+    fn decode_access_token_accepts_only_user_access_tokens() {
+        let encryption = EncryptionRepository::from_env();
+        let user = user();
+
+        let access = encryption.create_token(user.clone(), Token::user_access_token()).unwrap();
+        assert_eq!(decode_access_token(&encryption, &access).unwrap().id, user.id);
+
+        let refresh = encryption.create_token(user, Token::user_refresh_token()).unwrap();
+        assert_eq!(decode_access_token(&encryption, &refresh).unwrap_err(), "invalid or expired token");
+        assert_eq!(decode_access_token(&encryption, "garbage").unwrap_err(), "invalid or expired token");
+    }
+}
@@ -0,0 +1,73 @@
+use axum::body::{to_bytes, Body};
+use axum::extract::Request;
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::shared::data::ErrorResponse;
+
+/// Error bodies are small; anything larger isn't an `ErrorResponse` and is left alone
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// Media types the API can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    Json,
+    PlainText,
+}
+
+/// The client's preferred type among JSON and plain text by `Accept` q-values, earlier entries
+/// winning ties; `None` when `Accept` is missing or names neither
+pub fn preferred_media_type(headers: &HeaderMap) -> Option<MediaType> {
+    let mut best: Option<(MediaType, f32)> = None;
+    let entries = headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','));
+    for entry in entries {
+        let mut params = entry.split(';').map(str::trim);
+        let media_type = match params.next().unwrap_or_default().to_ascii_lowercase().as_str() {
+            "application/json" => MediaType::Json,
+            "text/plain" => MediaType::PlainText,
+            _ => continue,
+        };
+        let q = params
+            .find_map(|p| p.strip_prefix("q="))
+            .and_then(|q| q.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if q > 0.0 && best.is_none_or(|(_, best_q)| q > best_q) {
+            best = Some((media_type, q));
+        }
+    }
+    best.map(|(media_type, _)| media_type)
+}
+
+/// Rewrite JSON `ErrorResponse` bodies as their plain message for clients that prefer `text/plain`;
+/// everyone else keeps the JSON envelope
+pub async fn negotiate_errors(req: Request, next: Next) -> Response {
+    let plain = preferred_media_type(req.headers()) == Some(MediaType::PlainText);
+    let res = next.run(req).await;
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    if !plain || !is_json || !(res.status().is_client_error() || res.status().is_server_error()) {
+        return res;
+    }
+
+    let (mut parts, body) = res.into_parts();
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let Ok(error) = serde_json::from_slice::<ErrorResponse>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"));
+    parts.headers.append(header::VARY, HeaderValue::from_static("accept"));
+    Response::from_parts(parts, Body::from(error.message))
+}
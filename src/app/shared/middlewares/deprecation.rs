@@ -0,0 +1,23 @@
+use axum::extract::{OriginalUri, Request};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Current API prefix; the unversioned `/api/` routes are an alias kept for older clients
+pub const API_V1_PREFIX: &str = "/api/v1";
+
+/// Mark responses from the unversioned `/api/` alias as deprecated and point at the `/api/v1/` route
+pub async fn mark_deprecated(req: Request, next: Next) -> Response {
+    let successor = req
+        .extensions()
+        .get::<OriginalUri>()
+        .and_then(|OriginalUri(uri)| uri.path().strip_prefix("/api"))
+        .map(|rest| format!("<{}{}>; rel=\"successor-version\"", API_V1_PREFIX, rest));
+
+    let mut res = next.run(req).await;
+    res.headers_mut().insert("deprecation", HeaderValue::from_static("true"));
+    if let Some(link) = successor.and_then(|link| HeaderValue::from_str(&link).ok()) {
+        res.headers_mut().insert(axum::http::header::LINK, link);
+    }
+    res
+}
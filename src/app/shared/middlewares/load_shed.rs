@@ -0,0 +1,15 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}, BoxError};
+use tower::load_shed::error::Overloaded;
+
+use crate::shared::data::ErrorResponse;
+
+/// Error handler for the load-shedding stack in build_router: a full concurrency limit becomes a 503
+/// right away rather than a request queued behind the others
+pub async fn handle_overload(err: BoxError) -> Response {
+    if err.is::<Overloaded>() {
+        tracing::warn!("request shed, concurrency limit reached");
+        return ErrorResponse::response(StatusCode::SERVICE_UNAVAILABLE, "OVERLOADED", "server is busy, try again shortly");
+    }
+    tracing::error!(error = %err, "unhandled middleware error");
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
@@ -2,7 +2,7 @@ use axum::{response::Response};
 use axum::middleware::Next;
 use axum::extract::Request;
 use std::time::Instant;
-use tracing::info;
+use tracing::{info, Instrument};
 
 pub async fn structured_logger(req: Request, next: Next) -> Result<Response, std::convert::Infallible> {
     let start = Instant::now();
@@ -12,18 +12,22 @@ pub async fn structured_logger(req: Request, next: Next) -> Result<Response, std
     // Capture request ID if present
     let request_id = req.extensions().get::<String>().cloned().unwrap_or_default();
 
-    let res = next.run(req).await;
+    // Everything logged while handling the request inherits its request_id
+    let span = tracing::info_span!("request", request_id = %request_id);
+
+    let res = next.run(req).instrument(span.clone()).await;
     let status = res.status().as_u16();
     let latency_ms = start.elapsed().as_millis();
 
-    info!(
-        request_id = %request_id,
-        method = %method,
-        path = %uri,
-        status = %status,
-        latency_ms = %latency_ms,
-        "HTTP request"
-    );
+    span.in_scope(|| {
+        info!(
+            method = %method,
+            path = %uri,
+            status = %status,
+            latency_ms = %latency_ms,
+            "HTTP request"
+        );
+    });
 
     Ok(res)
-}
\ No newline at end of file
+}
@@ -1,4 +1,9 @@
 pub mod request_id;
 pub mod logging;
 pub mod recovery;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod account_status;
+pub mod load_shed;
+pub mod content_negotiation;
+pub mod deprecation;
+
@@ -1,3 +1,4 @@
 pub mod middlewares;
 pub mod data;
+pub mod extractors;
 pub mod utils;
\ No newline at end of file
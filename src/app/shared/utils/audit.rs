@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::shared::extractors::client_context::ClientContext;
+
+/// Tracing target audit events are emitted on, so they can be routed or filtered separately
+pub const AUDIT_TARGET: &str = "audit";
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventType {
+    SignUp,
+    SignIn,
+    TokenRefresh,
+    PasswordResetRequested,
+    PasswordResetCodeVerified,
+    PasswordReset,
+    EmailChanged,
+    SessionRevoked,
+}
+
+impl AuditEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventType::SignUp => "sign_up",
+            AuditEventType::SignIn => "sign_in",
+            AuditEventType::TokenRefresh => "token_refresh",
+            AuditEventType::PasswordResetRequested => "password_reset_requested",
+            AuditEventType::PasswordResetCodeVerified => "password_reset_code_verified",
+            AuditEventType::PasswordReset => "password_reset",
+            AuditEventType::EmailChanged => "email_changed",
+            AuditEventType::SessionRevoked => "session_revoked",
+        }
+    }
+}
+
+/// A single security-sensitive event
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    pub event: AuditEventType,
+    pub user_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub success: bool,
+    pub reason: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    pub fn new(event: AuditEventType, success: bool) -> Self {
+        Self {
+            event,
+            user_id: None,
+            ip: None,
+            user_agent: None,
+            success,
+            reason: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn success(event: AuditEventType) -> Self {
+        Self::new(event, true)
+    }
+
+    pub fn failure(event: AuditEventType, reason: &str) -> Self {
+        let mut audit = Self::new(event, false);
+        audit.reason = Some(reason.to_string());
+        audit
+    }
+
+    pub fn user(mut self, user_id: Uuid) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn client(mut self, client: &ClientContext) -> Self {
+        self.ip = client.ip.map(|ip| ip.to_string());
+        self.user_agent = client.user_agent.clone();
+        self
+    }
+
+    /// Emit the event on the audit target; failures are logged at warn level
+    pub fn record(self) {
+        let user_id = self.user_id.map(|id| id.to_string()).unwrap_or_default();
+        let ip = self.ip.as_deref().unwrap_or_default();
+        let user_agent = self.user_agent.as_deref().unwrap_or_default();
+        let reason = self.reason.as_deref().unwrap_or_default();
+        let timestamp = self.timestamp.to_rfc3339();
+
+        if self.success {
+            tracing::info!(
+                target: AUDIT_TARGET,
+                event = self.event.as_str(),
+                user_id = %user_id,
+                ip = %ip,
+                user_agent = %user_agent,
+                success = self.success,
+                timestamp = %timestamp,
+                "audit event"
+            );
+        } else {
+            tracing::warn!(
+                target: AUDIT_TARGET,
+                event = self.event.as_str(),
+                user_id = %user_id,
+                ip = %ip,
+                user_agent = %user_agent,
+                success = self.success,
+                reason = %reason,
+                timestamp = %timestamp,
+                "audit event"
+            );
+        }
+    }
+}
@@ -0,0 +1,22 @@
+/// Canonical form of an email address for storage and lookup.
+///
+/// Trims, lowercases and drops any whitespace inside the address, so `" Alice@X.com "`
+/// and `"alice@x.com"` always refer to the same account.
+pub fn normalize_email(email: &str) -> String {
+    email
+        .split_whitespace()
+        .collect::<String>()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_mixed_case_address_normalizes_to_lowercase() {
+        assert_eq!(normalize_email(" Alice@X.com "), "alice@x.com");
+        assert_eq!(normalize_email("alice@x.com"), "alice@x.com");
+        assert_eq!(normalize_email("\tALICE @x.COM\n"), "alice@x.com");
+    }
+}
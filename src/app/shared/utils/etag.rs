@@ -0,0 +1,17 @@
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Utc};
+
+/// Weak validator for a resource that bumps `updated_at` on every write
+pub fn weak_etag(id: &str, updated_at: DateTime<Utc>) -> String {
+    format!("W/\"{}-{}\"", id, updated_at.timestamp_micros())
+}
+
+/// Whether `If-None-Match` already names `etag` (weak comparison, so `W/` prefixes are ignored)
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(value) = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let expected = opaque(etag);
+    value.split(',').any(|tag| tag.trim() == "*" || opaque(tag) == expected)
+}
@@ -0,0 +1,14 @@
+use model::migration::{Migrator, MigratorTrait};
+use sea_orm::{DatabaseConnection, DbErr};
+
+use super::config::AppConfig;
+
+/// Apply pending migrations while booting unless `MIGRATE_ON_START=false`; returns whether they ran
+pub async fn run_on_start(cfg: &AppConfig, db: &DatabaseConnection) -> Result<bool, DbErr> {
+    if !cfg.migrate_on_start {
+        tracing::info!("MIGRATE_ON_START=false, skipping migrations");
+        return Ok(false);
+    }
+    Migrator::up(db, None).await?;
+    Ok(true)
+}
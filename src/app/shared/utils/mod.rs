@@ -1,2 +1,8 @@
+pub mod audit;
 pub mod config;
-pub mod logger;
\ No newline at end of file
+pub mod email;
+pub mod etag;
+pub mod migrations;
+pub mod password_policy;
+pub mod refresh_cookie;
+pub mod scheduler;
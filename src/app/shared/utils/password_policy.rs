@@ -0,0 +1,111 @@
+use repository::repositories::encryption::EncryptionRepositoryTrait;
+
+/// Minimum length accepted for new passwords
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Reject passwords that are too short or lack a letter or a digit
+pub fn check_strength(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+    if !password.chars().any(|c| c.is_alphabetic()) || !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err("password must contain letters and digits".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum PasswordPolicyError {
+    Mismatch,
+    Weak(String),
+    ReusedCurrent,
+    HashFailed,
+}
+
+impl std::fmt::Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PasswordPolicyError::Mismatch => write!(f, "Passwords do not match"),
+            PasswordPolicyError::Weak(msg) => write!(f, "Weak password: {}", msg),
+            PasswordPolicyError::ReusedCurrent => write!(f, "New password must differ from the current one"),
+            PasswordPolicyError::HashFailed => write!(f, "Failed to hash password"),
+        }
+    }
+}
+
+impl std::error::Error for PasswordPolicyError {}
+
+/// Rules every new password goes through, shared by the reset and change flows
+pub struct PasswordPolicy<'a> {
+    encryption_repo: &'a dyn EncryptionRepositoryTrait,
+}
+
+impl<'a> PasswordPolicy<'a> {
+    pub fn new(encryption_repo: &'a dyn EncryptionRepositoryTrait) -> Self {
+        Self { encryption_repo }
+    }
+
+    /// Confirmation and strength checks; cheap, so flows run it before touching the database
+    pub fn check(new: &str, confirm: &str) -> Result<(), PasswordPolicyError> {
+        if new != confirm {
+            return Err(PasswordPolicyError::Mismatch);
+        }
+        check_strength(new).map_err(PasswordPolicyError::Weak)
+    }
+
+    /// Run `check`, reject a password equal to `current_hash` when one is given, and return the new hash
+    pub fn validate_and_hash(
+        &self,
+        new: &str,
+        confirm: &str,
+        current_hash: Option<&str>,
+    ) -> Result<String, PasswordPolicyError> {
+        Self::check(new, confirm)?;
+
+        if let Some(current_hash) = current_hash {
+            // An unparseable stored hash can't match, so it doesn't block the change
+            if self.encryption_repo.verify_password(current_hash, new).unwrap_or(false) {
+                return Err(PasswordPolicyError::ReusedCurrent);
+            }
+        }
+
+        self.encryption_repo
+            .hash_password(new)
+            .map_err(|_| PasswordPolicyError::HashFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repository::repositories::encryption::mock::MockEncryption;
+
+    #[test]
+    fn mismatched_confirmation_is_rejected_first() {
+        let encryption = MockEncryption::new();
+        let result = PasswordPolicy::new(&encryption).validate_and_hash("Correct-Horse-42", "Correct-Horse-43", None);
+        assert!(matches!(result, Err(PasswordPolicyError::Mismatch)));
+    }
+
+    #[test]
+    fn weak_passwords_are_rejected() {
+        let encryption = MockEncryption::new();
+        let policy = PasswordPolicy::new(&encryption);
+        for weak in ["Short1", "no-digits-here", "1234567890"] {
+            assert!(matches!(policy.validate_and_hash(weak, weak, None), Err(PasswordPolicyError::Weak(_))), "{}", weak);
+        }
+    }
+
+    #[test]
+    fn reusing_the_current_password_is_rejected() {
+        let encryption = MockEncryption::new();
+        let current = encryption.hash_password("Correct-Horse-42").unwrap();
+        let policy = PasswordPolicy::new(&encryption);
+
+        let reused = policy.validate_and_hash("Correct-Horse-42", "Correct-Horse-42", Some(&current));
+        assert!(matches!(reused, Err(PasswordPolicyError::ReusedCurrent)));
+
+        let hash = policy.validate_and_hash("Battery-Staple-7", "Battery-Staple-7", Some(&current)).unwrap();
+        assert!(encryption.verify_password(&hash, "Battery-Staple-7").unwrap());
+    }
+}
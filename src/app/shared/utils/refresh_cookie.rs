@@ -0,0 +1,34 @@
+use axum::http::{header, HeaderMap, HeaderValue};
+
+/// Cookie carrying the refresh token when `REFRESH_TOKEN_COOKIE` is on
+pub const REFRESH_COOKIE_NAME: &str = "refresh_token";
+
+/// `REFRESH_TOKEN_COOKIE=true`: sign-in, sign-up and refresh set the refresh token as an HttpOnly
+/// cookie and leave it out of the JSON body, so scripts on the page can never read it
+pub fn enabled() -> bool {
+    std::env::var("REFRESH_TOKEN_COOKIE")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+}
+
+/// `Set-Cookie` value for `token`, expiring with it
+pub fn set_cookie(token: &str, max_age_seconds: i64) -> Option<HeaderValue> {
+    HeaderValue::from_str(&format!(
+        "{}={}; HttpOnly; Secure; SameSite=Strict; Path=/api; Max-Age={}",
+        REFRESH_COOKIE_NAME, token, max_age_seconds
+    ))
+    .ok()
+}
+
+/// Refresh token sent back in the request's `Cookie` header, if any
+pub fn from_headers(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == REFRESH_COOKIE_NAME)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use model::cleanup;
+use sea_orm::DatabaseConnection;
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+/// Run the cleanup purges every `period` until `shutdown` is cancelled. The first run is one
+/// period after start so it doesn't compete with startup work
+pub fn spawn_cleanup(db: DatabaseConnection, period: Duration, shutdown: CancellationToken) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = interval_at(Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticker.tick() => run_cleanup(&db).await,
+            }
+        }
+        tracing::info!("cleanup scheduler stopped");
+    })
+}
+
+/// One pass of every purge; a failing purge is logged and doesn't stop the others
+pub async fn run_cleanup(db: &DatabaseConnection) {
+    let now = chrono::Utc::now();
+
+    match cleanup::delete_expired_sessions(db, now).await {
+        Ok(count) => tracing::info!(count, "deleted expired sessions"),
+        Err(e) => tracing::error!(error = %e, "failed to delete expired sessions"),
+    }
+    match cleanup::clear_expired_reset_codes(db, now).await {
+        Ok(count) => tracing::info!(count, "cleared expired reset codes"),
+        Err(e) => tracing::error!(error = %e, "failed to clear expired reset codes"),
+    }
+}
@@ -1,7 +1,24 @@
-pub mod bsc;
+pub mod protocol;
+pub mod service;
+pub mod tokens;
 
 use axum::Router;
 
-pub fn router() -> Router {
-    Router::new().nest("/bsc", bsc::router())
+use crate::shared::data::state::AppState;
+
+/// Chain-agnostic token stream; `/bsc/:token_address` keeps working since bsc is always configured
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:chain/:token_address",
+            axum::routing::get(service::handle_token_websocket),
+        )
+        .route(
+            "/:chain/:token_address/metadata",
+            axum::routing::get(tokens::get_token_metadata),
+        )
+        .route(
+            "/:chain/:token_address/pairs",
+            axum::routing::get(tokens::get_token_pairs),
+        )
 }
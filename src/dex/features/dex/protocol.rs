@@ -0,0 +1,150 @@
+use std::borrow::Cow;
+use std::io::Write;
+
+use axum::extract::ws::{CloseFrame, Message};
+use flate2::{write::DeflateEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+/// Why the server ended a dex stream, sent to the client as the close frame.
+///
+/// Codes sit in the 4000-4999 range RFC 6455 leaves to applications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DexCloseReason {
+    UnsupportedChain,
+    ConnectionFailed,
+    NoLiquidity,
+    InvalidAddress,
+    RateLimited,
+}
+
+impl DexCloseReason {
+    pub fn code(&self) -> u16 {
+        match self {
+            DexCloseReason::UnsupportedChain => 4000,
+            DexCloseReason::ConnectionFailed => 4001,
+            DexCloseReason::NoLiquidity => 4002,
+            DexCloseReason::InvalidAddress => 4003,
+            DexCloseReason::RateLimited => 4029,
+        }
+    }
+
+    pub fn reason(&self) -> &'static str {
+        match self {
+            DexCloseReason::UnsupportedChain => "unsupported_chain",
+            DexCloseReason::ConnectionFailed => "connection_failed",
+            DexCloseReason::NoLiquidity => "no_liquidity",
+            DexCloseReason::InvalidAddress => "invalid_address",
+            DexCloseReason::RateLimited => "rate_limited",
+        }
+    }
+
+    pub fn frame(&self) -> CloseFrame<'static> {
+        CloseFrame {
+            code: self.code(),
+            reason: Cow::Borrowed(self.reason()),
+        }
+    }
+
+    /// Map a token-data fetch error to a reason to end the stream; `None` means it's worth retrying
+    pub fn from_fetch_error(error: &str) -> Option<Self> {
+        let error = error.to_lowercase();
+        if error.contains("no liquidity") {
+            Some(DexCloseReason::NoLiquidity)
+        } else if error.contains("429") || error.contains("rate limit") || error.contains("too many requests") {
+            Some(DexCloseReason::RateLimited)
+        } else {
+            None
+        }
+    }
+}
+
+/// Query options accepted on the websocket upgrade, e.g. `?encoding=deflate`
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamOptions {
+    #[serde(default)]
+    pub encoding: PayloadEncoding,
+}
+
+/// How payload frames are encoded; JSON text frames unless the client asks otherwise
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadEncoding {
+    #[default]
+    Json,
+    /// Raw-deflate compressed JSON in binary frames
+    Deflate,
+}
+
+impl PayloadEncoding {
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Message, std::io::Error> {
+        let json = serde_json::to_vec(value)?;
+        match self {
+            PayloadEncoding::Json => Ok(Message::Text(String::from_utf8_lossy(&json).into_owned())),
+            PayloadEncoding::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(&json)?;
+                Ok(Message::Binary(encoder.finish()?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::dex::service::TokenDataMessage;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn close_codes_are_in_the_application_range() {
+        let reasons = [
+            DexCloseReason::UnsupportedChain,
+            DexCloseReason::ConnectionFailed,
+            DexCloseReason::NoLiquidity,
+            DexCloseReason::InvalidAddress,
+            DexCloseReason::RateLimited,
+        ];
+        for reason in reasons {
+            let frame = reason.frame();
+            assert!((4000..5000).contains(&frame.code), "{:?}", reason);
+            assert_eq!(frame.reason, reason.reason());
+        }
+    }
+
+    #[test]
+    fn fetch_errors_map_to_terminal_reasons() {
+        assert_eq!(DexCloseReason::from_fetch_error("No liquidity for pair"), Some(DexCloseReason::NoLiquidity));
+        assert_eq!(DexCloseReason::from_fetch_error("HTTP 429 from provider"), Some(DexCloseReason::RateLimited));
+        assert_eq!(DexCloseReason::from_fetch_error("Too Many Requests"), Some(DexCloseReason::RateLimited));
+        assert_eq!(DexCloseReason::from_fetch_error("connection refused"), None);
+    }
+    #[test]
+    fn deflate_encoding_decodes_back_to_the_same_message() {
+        let message = TokenDataMessage {
+            price_usd: "2.345".to_string(),
+            price_change_24h: 0.0,
+            volume_24h: "0".to_string(),
+            liquidity_usd: "1200000".to_string(),
+            market_cap: "690000000".to_string(),
+            timestamp: 1_700_000_000,
+        };
+
+        let bytes = match PayloadEncoding::Deflate.encode(&message).unwrap() {
+            Message::Binary(bytes) => bytes,
+            other => panic!("expected a binary frame, got {:?}", other),
+        };
+        let mut json = String::new();
+        DeflateDecoder::new(bytes.as_slice()).read_to_string(&mut json).unwrap();
+        let decoded: TokenDataMessage = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&message).unwrap());
+    }
+
+    #[test]
+    fn json_is_the_default_encoding() {
+        let options: StreamOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options.encoding, PayloadEncoding::Json);
+        assert!(matches!(PayloadEncoding::Json.encode(&"x").unwrap(), Message::Text(_)));
+    }
+}
@@ -0,0 +1,416 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures::{stream::SplitSink, SinkExt, StreamExt};
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use repository::repositories::crypto::blockchain_client::{is_native_token, TokenPrice};
+use repository::repositories::crypto::data::Wallet;
+use repository::repositories::crypto::BlockchainClient;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{interval_at, Duration, Instant};
+use tracing::Instrument;
+
+use crate::shared::config::{BlockchainConfig, ChainConfig};
+use crate::shared::data::{state::AppState, ErrorResponse};
+use crate::shared::format::{format_significant, significant_digits};
+use crate::shared::metadata_cache::{MetadataCache, MetadataCaches};
+
+use super::protocol::{DexCloseReason, PayloadEncoding, StreamOptions};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenDataMessage {
+    pub price_usd: String,
+    pub price_change_24h: f64,
+    pub volume_24h: String,
+    pub liquidity_usd: String,
+    pub market_cap: String,
+    pub timestamp: i64,
+}
+
+/// WebSocket handler for real-time token data on any configured chain
+/// Path: /dex/{chain}/{token_address} (`native` for the chain's coin), `?encoding=deflate` for compressed binary frames
+pub async fn handle_token_websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((chain_id, token_address)): Path<(String, String)>,
+    Query(options): Query<StreamOptions>,
+) -> Response {
+    // CORS doesn't apply to websocket upgrades, so refuse pages from other sites before upgrading
+    let origin = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok());
+    if !state.ws_origins.allows(origin) {
+        tracing::warn!(origin = ?origin, "websocket upgrade from a disallowed origin");
+        return ErrorResponse::response(StatusCode::FORBIDDEN, "ORIGIN_NOT_ALLOWED", "origin not allowed");
+    }
+
+    let chain_id = chain_id.to_lowercase();
+    tracing::info!(
+        "WebSocket connection request for {} token: {}",
+        chain_id,
+        token_address
+    );
+    // Every log line of the connection carries the chain and token it belongs to
+    let span = tracing::info_span!("ws", token = %token_address, chain = %chain_id);
+    ws.on_upgrade(move |socket| {
+        handle_socket(socket, state.blockchain, state.metadata, chain_id, token_address, options.encoding).instrument(span)
+    })
+    .into_response()
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    config: Arc<BlockchainConfig>,
+    metadata: Arc<MetadataCaches>,
+    chain_id: String,
+    token_address: String,
+    encoding: PayloadEncoding,
+) {
+    let (mut sender, mut receiver) = socket.split();
+
+    // Reject malformed addresses before any RPC work, with the checksummed form used from here on
+    let token_address = match normalize_token_address(&token_address) {
+        Some(address) => address,
+        None => {
+            tracing::warn!("Invalid token address: {}", token_address);
+            close_with(&mut sender, DexCloseReason::InvalidAddress).await;
+            return;
+        }
+    };
+
+    let chain = match config.get_chain(&chain_id) {
+        Some(chain) => chain,
+        None => {
+            tracing::error!("Unsupported chain: {}", chain_id);
+            close_with(&mut sender, DexCloseReason::UnsupportedChain).await;
+            return;
+        }
+    };
+
+    let cache = MetadataCache::for_chain(&metadata, &chain.chain_id);
+
+    // Create blockchain client
+    let client = match BlockchainClient::new(&chain.rpc_url).await {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Failed to create blockchain client: {}", e);
+            close_with(&mut sender, DexCloseReason::ConnectionFailed).await;
+            return;
+        }
+    };
+
+    // Send a snapshot right away instead of making the client wait for the first update
+    let sent = match fetch_token_data(&client, &cache, &token_address, chain).await {
+        Ok(token_data) => send_payload(&mut sender, &token_data, encoding).await,
+        Err(e) => {
+            tracing::error!("Failed to fetch initial token data: {}", e);
+            if let Some(reason) = DexCloseReason::from_fetch_error(&e.to_string()) {
+                close_with(&mut sender, reason).await;
+                return;
+            }
+            send_payload(
+                &mut sender,
+                &serde_json::json!({ "error": "Failed to fetch token data" }),
+                encoding,
+            )
+            .await
+        }
+    };
+    if sent.is_err() {
+        tracing::info!("Client disconnected");
+        return;
+    }
+
+    // Periodic updates every 3 seconds, starting one period after the snapshot
+    let period = Duration::from_secs(3);
+    let mut update_interval = interval_at(Instant::now() + period, period);
+
+    // Clone token_address for the spawned task
+    let token_address_clone = token_address.clone();
+
+    // Main loop handling both updates and incoming messages
+    loop {
+        tokio::select! {
+            _ = update_interval.tick() => {
+                // Fetch token data
+                let token_data = match fetch_token_data(&client, &cache, &token_address_clone, chain).await {
+                    Ok(data) => data,
+                    Err(e) => {
+                        tracing::error!("Failed to fetch token data: {}", e);
+                        if let Some(reason) = DexCloseReason::from_fetch_error(&e.to_string()) {
+                            close_with(&mut sender, reason).await;
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                // Send update to client
+                if send_payload(&mut sender, &token_data, encoding).await.is_err() {
+                    tracing::info!("Client disconnected");
+                    break;
+                }
+            }
+
+            msg = receiver.next() => {
+                match msg {
+                    Some(Ok(Message::Close(_))) => {
+                        tracing::info!("Client closed connection");
+                        break;
+                    }
+                    Some(Ok(Message::Ping(data))) => {
+                        let pong = sender.send(Message::Pong(data)).await;
+                        if pong.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        tracing::error!("WebSocket error: {}", e);
+                        break;
+                    }
+                    None => {
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    tracing::info!("WebSocket connection closed for token: {}", token_address);
+}
+
+/// Tell the client why the stream is ending; the socket is going away either way, so send errors are ignored
+async fn close_with(sender: &mut SplitSink<WebSocket, Message>, reason: DexCloseReason) {
+    let _ = sender.send(Message::Close(Some(reason.frame()))).await;
+}
+
+/// Encode `value` for the client and send it; encoding failures are logged and skipped
+async fn send_payload<T: Serialize>(
+    sender: &mut SplitSink<WebSocket, Message>,
+    value: &T,
+    encoding: PayloadEncoding,
+) -> Result<(), axum::Error> {
+    match encoding.encode(value) {
+        Ok(message) => sender.send(message).await,
+        Err(e) => {
+            tracing::error!("Failed to serialize websocket payload: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Checksummed form of an EVM token address, `None` when it's malformed. The native sentinel
+/// is passed through as-is
+pub(super) fn normalize_token_address(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if is_native_token(raw) {
+        return Some(raw.to_lowercase());
+    }
+    // Every chain the dex serves is EVM-compatible, so the ethereum rules apply to all of them
+    if !Wallet::validate_address(raw, "ethereum").unwrap_or(false) {
+        return None;
+    }
+    raw.parse::<Address>().ok().map(|address| to_checksum(&address, None))
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+async fn fetch_token_data(
+    client: &BlockchainClient,
+    cache: &MetadataCache,
+    token_address: &str,
+    chain: &ChainConfig,
+) -> Result<TokenDataMessage, Box<dyn std::error::Error + Send + Sync>> {
+    if is_native_token(token_address) {
+        let price_data = client
+            .calculate_native_price(
+                &chain.dex_contracts.v2_factory,
+                &chain.wrapped_native_address,
+                &chain.stable_token_address,
+                chain.stable_token_decimals,
+                &chain.native_symbol,
+            )
+            .await?;
+        return Ok(token_data_message(&price_data));
+    }
+
+    // Metadata is shared across connections; on a miss it's fetched in the same batch as the price.
+    // Total supply is served from the cache too, so market cap can lag a mint/burn by up to the TTL
+    let address: Address = token_address.parse()?;
+    let cached = cache.get(&address);
+    let _permit = match cached {
+        Some(_) => None,
+        None => cache.fetch_permit().await,
+    };
+
+    let price_data = client
+        .calculate_token_price(
+            token_address,
+            &chain.dex_contracts.v2_factory,
+            &chain.wrapped_native_address,
+            &chain.stable_token_address,
+            chain.stable_token_decimals,
+            cached.as_ref(),
+        )
+        .await?;
+    if cached.is_none() {
+        cache.insert(address, price_data.metadata.clone());
+    }
+
+    Ok(token_data_message(&price_data))
+}
+
+fn token_data_message(price_data: &TokenPrice) -> TokenDataMessage {
+    // Calculate market cap (price * total supply); zero for the native coin, whose supply isn't read
+    let metadata = &price_data.metadata;
+    let total_supply_f64 =
+        metadata.total_supply.as_u128() as f64 / 10f64.powi(metadata.decimals as i32);
+    let market_cap = price_data.price_usd * total_supply_f64;
+    let digits = significant_digits();
+
+    TokenDataMessage {
+        price_usd: format_significant(price_data.price_usd, digits),
+        price_change_24h: 0.0,
+        volume_24h: "0".to_string(),
+        liquidity_usd: format_significant(price_data.liquidity_usd, digits),
+        market_cap: format_significant(market_cap, digits),
+        timestamp: chrono::Utc::now().timestamp(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::config::OriginAllowlist;
+    use crate::test_util;
+    use ethers::abi::Token;
+    use ethers::types::U256;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::{connect_async, tungstenite};
+
+    const CAKE: &str = "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82";
+
+    /// First frame the server sends on a stream
+    async fn first_frame(path: &str) -> tungstenite::Message {
+        let address = test_util::serve(test_util::state(OriginAllowlist::default())).await;
+        let (mut socket, _) = connect_async(format!("ws://{}/api/dex/{}", address, path)).await.unwrap();
+        socket.next().await.expect("a frame").expect("a valid frame")
+    }
+
+    fn close_code(frame: tungstenite::Message) -> (u16, String) {
+        match frame {
+            tungstenite::Message::Close(Some(close)) => (close.code.into(), close.reason.into_owned()),
+            other => panic!("expected a close frame, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn unconfigured_chain_is_closed_with_unsupported_chain() {
+        let (code, reason) = close_code(first_frame(&format!("ethereum/{}", CAKE)).await);
+        assert_eq!(code, DexCloseReason::UnsupportedChain.code());
+        assert_eq!(reason, "unsupported_chain");
+    }
+
+    #[tokio::test]
+    async fn first_frame_arrives_well_before_the_update_period() {
+        let frame = tokio::time::timeout(Duration::from_secs(1), first_frame(&format!("bsc/{}", CAKE)))
+            .await
+            .expect("a frame before the first 3s tick");
+
+        // The RPC is unreachable, so the snapshot is the error payload rather than silence
+        let payload: serde_json::Value = match frame {
+            tungstenite::Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("expected a text frame, got {:?}", other),
+        };
+        assert_eq!(payload["error"], "Failed to fetch token data");
+    }
+
+    #[tokio::test]
+    async fn deflate_flag_switches_to_binary_frames() {
+        let frame = first_frame(&format!("bsc/{}?encoding=deflate", CAKE)).await;
+        assert!(matches!(frame, tungstenite::Message::Binary(_)), "{:?}", frame);
+    }
+
+    #[tokio::test]
+    async fn connection_logs_run_inside_the_ws_span() {
+        let logs = test_util::CapturedLogs::start();
+        first_frame(&format!("bsc/{}", CAKE)).await;
+
+        let contents = logs.contents();
+        let span = format!("ws{{token={} chain=bsc}}", CAKE);
+        assert!(contents.lines().any(|line| line.contains(&span) && line.contains("Failed to fetch initial token data")), "{}", contents);
+        // fetch_token_data is instrumented as a child span of the connection
+        assert!(contents.contains(&format!("{}:fetch_token_data", span)), "{}", contents);
+    }
+
+    #[tokio::test]
+    async fn native_sentinel_is_priced_without_erc20_calls() {
+        let chain = BlockchainConfig::new().chains.remove("bsc").unwrap();
+        let wbnb: Address = chain.wrapped_native_address.parse().unwrap();
+        let busd: Address = chain.stable_token_address.parse().unwrap();
+        let ether = U256::exp10(18);
+        // 1,000 WBNB against 600,000 BUSD
+        let rpc = test_util::FakeRpc::start(vec![
+            ("getPair(address,address)", vec![Token::Address(Address::repeat_byte(0x11))]),
+            ("getReserves()", vec![Token::Uint(ether * 1_000), Token::Uint(ether * 600_000), Token::Uint(U256::zero())]),
+            ("token0()", vec![Token::Address(wbnb)]),
+            ("token1()", vec![Token::Address(busd)]),
+        ])
+        .await;
+        let client = BlockchainClient::new(&rpc.url).await.unwrap();
+
+        let message = fetch_token_data(&client, &MetadataCache::new(Duration::from_secs(60), 1), "native", &chain)
+            .await
+            .unwrap();
+        assert_eq!(message.price_usd, "600");
+        assert_eq!(message.market_cap, "0");
+
+        // Only the pair is read; an ERC20 metadata call would show up as an unanswered eth_call
+        assert_eq!(rpc.calls(), ["getPair(address,address)", "getReserves()", "token0()", "token1()"]);
+    }
+
+    #[tokio::test]
+    async fn malformed_address_is_closed_before_any_rpc_call() {
+        let rpc = test_util::FakeRpc::start(Vec::new()).await;
+        let address = test_util::serve(test_util::state_with_rpc(OriginAllowlist::default(), &rpc.url)).await;
+        let (mut socket, _) = connect_async(format!("ws://{}/api/dex/bsc/notanaddress", address)).await.unwrap();
+
+        let (code, reason) = close_code(socket.next().await.expect("a frame").expect("a valid frame"));
+        assert_eq!(code, DexCloseReason::InvalidAddress.code());
+        assert_eq!(reason, "invalid_address");
+        assert!(rpc.calls().is_empty(), "{:?}", rpc.calls());
+    }
+
+    #[test]
+    fn token_addresses_are_checksummed_or_rejected() {
+        assert_eq!(normalize_token_address(&CAKE.to_lowercase()).as_deref(), Some(CAKE));
+        assert_eq!(normalize_token_address(&format!(" {} ", CAKE)).as_deref(), Some(CAKE));
+        assert_eq!(normalize_token_address("NATIVE").as_deref(), Some("native"));
+        for malformed in ["notanaddress", "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE", ""] {
+            assert_eq!(normalize_token_address(malformed), None, "{}", malformed);
+        }
+    }
+
+    #[tokio::test]
+    async fn upgrades_from_a_disallowed_origin_are_forbidden() {
+        let address = test_util::serve(test_util::state(OriginAllowlist::new(["https://app.example.com"]))).await;
+        let upgrade = |origin: &'static str| {
+            let mut request = format!("ws://{}/api/dex/bsc/{}", address, CAKE).into_client_request().unwrap();
+            request.headers_mut().insert("origin", origin.parse().unwrap());
+            connect_async(request)
+        };
+
+        match upgrade("https://evil.example.com").await {
+            Err(tungstenite::Error::Http(response)) => assert_eq!(response.status(), 403),
+            other => panic!("expected a 403, got {:?}", other.map(|(_, response)| response.status())),
+        }
+        // Listed origins match regardless of case and trailing slash
+        upgrade("HTTPS://App.Example.com/").await.expect("the allowed origin upgrades");
+    }
+}
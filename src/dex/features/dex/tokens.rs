@@ -0,0 +1,296 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use ethers::types::Address;
+use ethers::utils::to_checksum;
+use futures::future::join_all;
+use repository::repositories::crypto::blockchain_client::{is_native_token, TokenMetadata};
+use repository::repositories::crypto::BlockchainClient;
+use serde::Serialize;
+
+use crate::shared::config::{BlockchainConfig, ChainConfig, QuoteToken};
+use crate::shared::data::{state::AppState, ErrorResponse, SuccessResponse};
+use crate::shared::metadata_cache::MetadataCache;
+
+use super::service::normalize_token_address;
+
+#[derive(Debug)]
+pub enum TokenLookupError {
+    UnsupportedChain(String),
+    InvalidAddress(String),
+    NotAContract(String),
+    NetworkError(String),
+}
+
+impl std::fmt::Display for TokenLookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TokenLookupError::UnsupportedChain(chain) => write!(f, "Unsupported chain: {}", chain),
+            TokenLookupError::InvalidAddress(address) => write!(f, "Invalid token address: {}", address),
+            TokenLookupError::NotAContract(address) => write!(f, "No token contract at {}", address),
+            TokenLookupError::NetworkError(msg) => write!(f, "Network error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TokenLookupError {}
+
+impl IntoResponse for TokenLookupError {
+    fn into_response(self) -> Response {
+        let message = self.to_string();
+        match self {
+            TokenLookupError::UnsupportedChain(_) => ErrorResponse::response(StatusCode::NOT_FOUND, "UNSUPPORTED_CHAIN", message),
+            TokenLookupError::InvalidAddress(_) => ErrorResponse::response(StatusCode::BAD_REQUEST, "INVALID_ADDRESS", message),
+            TokenLookupError::NotAContract(_) => ErrorResponse::response(StatusCode::NOT_FOUND, "TOKEN_NOT_FOUND", message),
+            TokenLookupError::NetworkError(msg) => {
+                tracing::error!(error = %msg, "token lookup failed");
+                ErrorResponse::response(StatusCode::BAD_GATEWAY, "NETWORK_ERROR", "unable to reach the chain")
+            }
+        }
+    }
+}
+
+/// Token metadata as returned over REST; total supply is a decimal string since it overflows JSON numbers
+#[derive(Debug, Serialize)]
+pub struct TokenMetadataResponse {
+    pub address: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: String,
+}
+
+impl TokenMetadataResponse {
+    fn new(address: String, metadata: TokenMetadata) -> Self {
+        Self {
+            address,
+            name: metadata.name,
+            symbol: metadata.symbol,
+            decimals: metadata.decimals,
+            total_supply: metadata.total_supply.to_string(),
+        }
+    }
+}
+
+/// Name, symbol, decimals and total supply of a token, for clients that don't need the live stream
+/// Path: /dex/{chain}/{token_address}/metadata
+pub async fn get_token_metadata(
+    State(state): State<AppState>,
+    Path((chain_id, token_address)): Path<(String, String)>,
+) -> Result<Json<SuccessResponse<TokenMetadataResponse>>, TokenLookupError> {
+    let (chain, token_address) = resolve(&state.blockchain, &chain_id, &token_address)?;
+
+    // The native coin has no contract; report it the way the price stream does
+    if is_native_token(&token_address) {
+        let metadata = TokenMetadata {
+            name: chain.native_symbol.clone(),
+            symbol: chain.native_symbol.clone(),
+            decimals: 18,
+            total_supply: Default::default(),
+        };
+        return Ok(Json(SuccessResponse::new(TokenMetadataResponse::new(token_address, metadata))));
+    }
+
+    let address: Address = token_address
+        .parse()
+        .map_err(|_| TokenLookupError::InvalidAddress(token_address.clone()))?;
+    let cache = MetadataCache::for_chain(&state.metadata, &chain.chain_id);
+    if let Some(metadata) = cache.get(&address) {
+        return Ok(Json(SuccessResponse::new(TokenMetadataResponse::new(token_address, metadata))));
+    }
+
+    let client = connect(chain).await?;
+    // ERC20 reads fall back to placeholders when they revert, so check there is a contract at all first
+    let is_contract = client
+        .is_contract(address)
+        .await
+        .map_err(|e| TokenLookupError::NetworkError(e.to_string()))?;
+    if !is_contract {
+        return Err(TokenLookupError::NotAContract(token_address));
+    }
+
+    let metadata = {
+        let _permit = cache.fetch_permit().await;
+        client
+            .get_token_metadata(&token_address)
+            .await
+            .map_err(|e| TokenLookupError::NetworkError(e.to_string()))?
+    };
+    cache.insert(address, metadata.clone());
+
+    Ok(Json(SuccessResponse::new(TokenMetadataResponse::new(token_address, metadata))))
+}
+
+/// A V2 pool holding the token against one of the chain's quote tokens; reserves are raw
+/// integer amounts as decimal strings
+#[derive(Debug, Serialize)]
+pub struct LiquidityPair {
+    pub quote_symbol: String,
+    pub quote_address: String,
+    pub pair_address: String,
+    pub token_reserve: String,
+    pub quote_reserve: String,
+}
+
+/// Liquidity pairs of a token against the chain's quote tokens (BUSD/USDT/WBNB on BSC), empty when
+/// it has none. The native coin is looked up through its wrapped token
+/// Path: /dex/{chain}/{token_address}/pairs
+pub async fn get_token_pairs(
+    State(state): State<AppState>,
+    Path((chain_id, token_address)): Path<(String, String)>,
+) -> Result<Json<SuccessResponse<Vec<LiquidityPair>>>, TokenLookupError> {
+    let (chain, token_address) = resolve(&state.blockchain, &chain_id, &token_address)?;
+    let token_address = if is_native_token(&token_address) {
+        chain.wrapped_native_address.clone()
+    } else {
+        token_address
+    };
+
+    let client = connect(chain).await?;
+    let lookups = chain
+        .quote_tokens
+        .iter()
+        .filter(|quote| !quote.address.eq_ignore_ascii_case(&token_address))
+        .map(|quote| find_liquidity_pair(&client, chain, &token_address, quote));
+    let pairs = join_all(lookups)
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| TokenLookupError::NetworkError(e.to_string()))?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(Json(SuccessResponse::new(pairs)))
+}
+
+async fn find_liquidity_pair(
+    client: &BlockchainClient,
+    chain: &ChainConfig,
+    token_address: &str,
+    quote: &QuoteToken,
+) -> Result<Option<LiquidityPair>, Box<dyn std::error::Error + Send + Sync>> {
+    let pair_address = match client
+        .find_pair(token_address, &quote.address, &chain.dex_contracts.v2_factory)
+        .await?
+    {
+        Some(pair_address) => pair_address,
+        None => return Ok(None),
+    };
+    let pair_data = client.get_pair_data(pair_address, token_address).await?;
+
+    Ok(Some(LiquidityPair {
+        quote_symbol: quote.symbol.clone(),
+        quote_address: quote.address.clone(),
+        pair_address: to_checksum(&pair_data.pair_address, None),
+        token_reserve: pair_data.token_reserve.to_string(),
+        quote_reserve: pair_data.quote_reserve.to_string(),
+    }))
+}
+
+/// Configured chain and checksummed token address from the path
+fn resolve<'a>(
+    config: &'a BlockchainConfig,
+    chain_id: &str,
+    token_address: &str,
+) -> Result<(&'a ChainConfig, String), TokenLookupError> {
+    let chain_id = chain_id.to_lowercase();
+    let chain = config
+        .get_chain(&chain_id)
+        .ok_or(TokenLookupError::UnsupportedChain(chain_id))?;
+    let token_address = normalize_token_address(token_address)
+        .ok_or_else(|| TokenLookupError::InvalidAddress(token_address.to_string()))?;
+    Ok((chain, token_address))
+}
+
+async fn connect(chain: &ChainConfig) -> Result<BlockchainClient, TokenLookupError> {
+    BlockchainClient::new(&chain.rpc_url)
+        .await
+        .map_err(|e| TokenLookupError::NetworkError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shared::config::OriginAllowlist;
+    use crate::shared::data::state::AppState;
+    use crate::test_util;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use ethers::abi::Token;
+    use ethers::types::{Address, U256};
+    use ethers::utils::to_checksum;
+    use tower::ServiceExt;
+
+    const CAKE: &str = "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82";
+
+    async fn get(state: AppState, path: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::get(path).body(Body::empty()).unwrap();
+        let response = crate::build_router(state).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn metadata_of_an_address_without_code_is_not_found() {
+        let rpc = test_util::FakeRpc::start(Vec::new()).await;
+        let state = test_util::state_with_rpc(OriginAllowlist::default(), &rpc.url);
+
+        let (status, body) = get(state, &format!("/api/dex/bsc/{}/metadata", CAKE)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "TOKEN_NOT_FOUND");
+        // Placeholder ERC20 reads are never attempted
+        assert_eq!(rpc.calls(), ["eth_getCode"]);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs LIVE_BSC_RPC_URL"]
+    async fn metadata_of_a_known_token_has_its_symbol_and_decimals() {
+        let rpc_url = std::env::var("LIVE_BSC_RPC_URL").expect("LIVE_BSC_RPC_URL is required for live tests");
+        let state = test_util::state_with_rpc(OriginAllowlist::default(), &rpc_url);
+
+        let (status, body) = get(state, &format!("/api/dex/bsc/{}/metadata", CAKE)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["symbol"], "Cake");
+        assert_eq!(body["data"]["decimals"], 18);
+        assert!(body["data"]["total_supply"].as_str().unwrap().parse::<u128>().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn pairs_list_each_quote_pool_with_its_reserves() {
+        let cake: Address = CAKE.parse().unwrap();
+        let pair = Address::repeat_byte(0x11);
+        // The stub answers every quote token with the same pool: 5 CAKE against 7 of the quote
+        let rpc = test_util::FakeRpc::start(vec![
+            ("getPair(address,address)", vec![Token::Address(pair)]),
+            ("getReserves()", vec![Token::Uint(U256::from(5)), Token::Uint(U256::from(7)), Token::Uint(U256::zero())]),
+            ("token0()", vec![Token::Address(cake)]),
+            ("token1()", vec![Token::Address(Address::repeat_byte(0x22))]),
+        ])
+        .await;
+        let state = test_util::state_with_rpc(OriginAllowlist::default(), &rpc.url);
+
+        let (status, body) = get(state, &format!("/api/dex/bsc/{}/pairs", CAKE)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let pairs = body["data"].as_array().unwrap();
+        let quotes: Vec<_> = pairs.iter().map(|p| p["quote_symbol"].as_str().unwrap()).collect();
+        assert_eq!(quotes, ["BUSD", "USDT", "WBNB"]);
+        for p in pairs {
+            assert_eq!(p["pair_address"], to_checksum(&pair, None));
+            assert_eq!((p["token_reserve"].as_str(), p["quote_reserve"].as_str()), (Some("5"), Some("7")));
+        }
+    }
+
+    #[tokio::test]
+    async fn a_token_without_pools_has_an_empty_pair_list() {
+        let rpc = test_util::FakeRpc::start(vec![("getPair(address,address)", vec![Token::Address(Address::zero())])]).await;
+        let state = test_util::state_with_rpc(OriginAllowlist::default(), &rpc.url);
+
+        let (status, body) = get(state, &format!("/api/dex/bsc/{}/pairs", CAKE)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"], serde_json::json!([]));
+    }
+}
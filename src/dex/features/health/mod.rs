@@ -0,0 +1,97 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use futures::future::join_all;
+use repository::repositories::crypto::BlockchainClient;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio::time::{timeout, Duration};
+
+use crate::shared::config::ChainConfig;
+use crate::shared::data::state::AppState;
+
+/// How long a chain's RPC gets to answer before it's reported unreachable
+const RPC_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What `/ready` says about one dependency. The endpoint is unauthenticated and RPC errors often
+/// echo the endpoint URL, API key included, so the reason only goes to the logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyStatus {
+    Ok,
+    Unavailable,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadyResponse {
+    pub ready: bool,
+    pub unreachable: Vec<String>,
+    pub chains: BTreeMap<String, DependencyStatus>,
+}
+
+/// Readiness: every configured chain's RPC must answer `eth_blockNumber`, otherwise 503
+/// listing the chains that didn't
+pub async fn ready(State(state): State<AppState>) -> impl IntoResponse {
+    let checks = state.blockchain.chains.values().map(|chain| async move {
+        (chain.chain_id.clone(), check_chain(chain).await)
+    });
+    let chains: BTreeMap<String, DependencyStatus> = join_all(checks).await.into_iter().collect();
+
+    let unreachable: Vec<String> = chains
+        .iter()
+        .filter(|(_, status)| **status == DependencyStatus::Unavailable)
+        .map(|(chain_id, _)| chain_id.clone())
+        .collect();
+    if !unreachable.is_empty() {
+        tracing::warn!(chains = %unreachable.join(","), "dex not ready: RPC unreachable");
+    }
+
+    let ready = unreachable.is_empty();
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(ReadyResponse { ready, unreachable, chains }))
+}
+
+async fn check_chain(chain: &ChainConfig) -> DependencyStatus {
+    let result = async {
+        let client = BlockchainClient::new(&chain.rpc_url).await?;
+        client.get_block_number().await
+    };
+
+    match timeout(RPC_CHECK_TIMEOUT, result).await {
+        Ok(Ok(_)) => DependencyStatus::Ok,
+        Ok(Err(e)) => {
+            tracing::warn!(chain = %chain.chain_id, error = %e, "RPC readiness check failed");
+            DependencyStatus::Unavailable
+        }
+        Err(_) => {
+            tracing::warn!(chain = %chain.chain_id, "RPC readiness check timed out");
+            DependencyStatus::Unavailable
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::config::{BlockchainConfig, OriginAllowlist};
+    use axum::body::to_bytes;
+    use repository::repositories::encryption::EncryptionRepository;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn unreachable_rpc_is_503_without_leaking_the_url() {
+        let mut blockchain = BlockchainConfig::new();
+        for chain in blockchain.chains.values_mut() {
+            chain.rpc_url = "http://127.0.0.1:9/v1/secret-api-key".to_string();
+        }
+        let state = AppState::new(Arc::new(EncryptionRepository::from_env()), blockchain, OriginAllowlist::default());
+
+        let response = ready(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(!body.contains("secret-api-key") && !body.contains("127.0.0.1"), "{}", body);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["chains"]["bsc"], "unavailable");
+        assert_eq!(json["unreachable"], serde_json::json!(["bsc"]));
+    }
+}
@@ -1,6 +1,10 @@
 use axum::Router;
+
+use crate::shared::data::state::AppState;
+
 pub mod dex;
+pub mod health;
 
-pub fn router() -> Router {
+pub fn router() -> Router<AppState> {
     Router::new().nest("/dex", dex::router())
 }
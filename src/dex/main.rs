@@ -1,27 +1,24 @@
 use axum::http::{Method, header};
 use axum::Router;
 use dotenvy::dotenv;
+use repository::repositories::encryption::EncryptionRepository;
+use shared::config::{BlockchainConfig, OriginAllowlist};
+use shared::data::state::AppState;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower_http::cors::CorsLayer;
-use tracing_subscriber;
 
 pub mod features;
 pub mod shared;
+#[cfg(test)]
+mod test_util;
 
 async fn health_check() -> &'static str {
     "OK - Dex WebSocket Proxy"
 }
 
-#[tokio::main]
-async fn main() {
-    let _ = dotenv();
-    
-    // Initialize simple logger for dex binary
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .compact()
-        .init();
-
+/// The dex router with its state and middleware, ready to serve
+fn build_router(state: AppState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(tower_http::cors::Any)
         .allow_methods([
@@ -34,10 +31,27 @@ async fn main() {
         ])
         .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]);
 
-    let app = Router::new()
+    Router::new()
         .route("/health", axum::routing::get(health_check))
+        .route("/ready", axum::routing::get(features::health::ready))
         .nest("/api", features::router())
-        .layer(cors);
+        .with_state(state)
+        .layer(cors)
+}
+
+#[tokio::main]
+async fn main() {
+    let _ = dotenv();
+    
+    // Initialize global logger
+    logger::init();
+
+    let state = AppState::new(
+        Arc::new(EncryptionRepository::from_env()),
+        BlockchainConfig::new(),
+        OriginAllowlist::from_env(),
+    );
+    let app = build_router(state);
 
     let address = SocketAddr::from(([127, 0, 0, 1], 8001));
 
@@ -52,3 +66,33 @@ async fn main() {
         .await
         .expect("Failed to start server");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    async fn get(router: Router, path: &str) -> (StatusCode, String) {
+        let response = router.oneshot(Request::get(path).body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn router_serves_with_the_injected_state() {
+        let mut blockchain = BlockchainConfig::new();
+        blockchain.chains.get_mut("bsc").unwrap().native_symbol = "TESTBNB".to_string();
+        let state = AppState::new(Arc::new(EncryptionRepository::from_env()), blockchain, OriginAllowlist::default());
+        let router = build_router(state);
+
+        assert_eq!(get(router.clone(), "/health").await, (StatusCode::OK, "OK - Dex WebSocket Proxy".to_string()));
+        // Handlers read the chain from the injected state, not from the environment
+        let (status, body) = get(router, "/api/dex/bsc/native/metadata").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["data"]["symbol"], "TESTBNB");
+    }
+}
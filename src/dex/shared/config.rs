@@ -1,73 +1,224 @@
 use std::collections::HashMap;
 
-/// Configuration for blockchain RPC connections
+/// Configuration for blockchain RPC connections, keyed by chain id (e.g. "bsc")
 pub struct BlockchainConfig {
-    pub rpc_urls: HashMap<String, String>,
+    pub chains: HashMap<String, ChainConfig>,
+}
+
+/// RPC endpoint and DEX contracts for a single EVM chain
+#[derive(Clone, Debug)]
+pub struct ChainConfig {
+    pub chain_id: String,
+    pub rpc_url: String,
     pub dex_contracts: DexContracts,
-    pub stable_tokens: HashMap<String, String>,
+    /// Wrapped native coin (e.g. WBNB), used as the fallback quote token
+    pub wrapped_native_address: String,
+    /// USD stable coin used to price tokens directly
+    pub stable_token_address: String,
+    /// Decimals of the USD stable coin (BUSD is 18, USDC on Ethereum is 6)
+    pub stable_token_decimals: u8,
+    /// Symbol reported for native-coin queries (e.g. "BNB")
+    pub native_symbol: String,
+    /// Tokens searched for liquidity pairs against a token (e.g. BUSD, USDT, WBNB)
+    pub quote_tokens: Vec<QuoteToken>,
+}
+
+#[derive(Clone, Debug)]
+pub struct QuoteToken {
+    pub symbol: String,
+    pub address: String,
+}
+
+impl QuoteToken {
+    fn new(symbol: &str, address: &str) -> Self {
+        Self { symbol: symbol.to_string(), address: address.to_string() }
+    }
+
+    /// Parse "SYMBOL:0xaddress,..." entries, skipping malformed ones
+    fn parse_list(raw: &str) -> Vec<Self> {
+        raw.split(',')
+            .filter_map(|entry| {
+                let (symbol, address) = entry.trim().split_once(':')?;
+                Some(Self::new(symbol.trim(), address.trim()))
+            })
+            .collect()
+    }
 }
 
+#[derive(Clone, Debug)]
 pub struct DexContracts {
-    pub pancakeswap_v2_factory: String,
-    pub pancakeswap_v2_router: String,
+    pub v2_factory: String,
+    pub v2_router: String,
 }
 
 impl BlockchainConfig {
+    /// Load BSC defaults plus any extra chains listed in `DEX_CHAINS`.
+    ///
+    /// Each chain is read from `<CHAIN>_RPC_URL`, `<CHAIN>_V2_FACTORY`, `<CHAIN>_V2_ROUTER`,
+    /// `<CHAIN>_WRAPPED_NATIVE`, `<CHAIN>_STABLE_TOKEN`, `<CHAIN>_STABLE_DECIMALS`, `<CHAIN>_NATIVE_SYMBOL`
+    /// and `<CHAIN>_PAIR_QUOTES` ("SYMBOL:0xaddress,...").
     pub fn new() -> Self {
-        let mut rpc_urls = HashMap::new();
-        let mut stable_tokens = HashMap::new();
-
-        // BSC RPC endpoints (public)
-        let bsc_rpc_url = std::env::var("BSC_RPC_URL")
-            .unwrap_or_else(|_| "https://bsc-dataseed.binance.org/".to_string());
-
-        rpc_urls.insert("bsc".to_string(), bsc_rpc_url);
-
-        // Solana RPC (for future use)
-        rpc_urls.insert(
-            "solana".to_string(),
-            "https://api.mainnet-beta.solana.com".to_string(),
-        );
-
-        // BSC stable tokens for price calculation
-        stable_tokens.insert(
-            "bsc_wbnb".to_string(),
-            "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".to_string(),
-        );
-        stable_tokens.insert(
-            "bsc_busd".to_string(),
-            "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56".to_string(),
-        );
-        stable_tokens.insert(
-            "bsc_usdt".to_string(),
-            "0x55d398326f99059fF775485246999027B3197955".to_string(),
-        );
-
-        Self {
-            rpc_urls,
+        let mut chains = HashMap::new();
+
+        // BSC (PancakeSwap V2), overridable through the same env vars as any other chain
+        let bsc = ChainConfig {
+            chain_id: "bsc".to_string(),
+            rpc_url: "https://bsc-dataseed.binance.org/".to_string(),
             dex_contracts: DexContracts {
-                pancakeswap_v2_factory: "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73".to_string(),
-                pancakeswap_v2_router: "0x10ED43C718714eb63d5aA57B78B54704E256024E".to_string(),
+                v2_factory: "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73".to_string(),
+                v2_router: "0x10ED43C718714eb63d5aA57B78B54704E256024E".to_string(),
             },
-            stable_tokens,
+            wrapped_native_address: "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".to_string(),
+            stable_token_address: "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56".to_string(),
+            stable_token_decimals: 18,
+            native_symbol: "BNB".to_string(),
+            quote_tokens: vec![
+                QuoteToken::new("BUSD", "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56"),
+                QuoteToken::new("USDT", "0x55d398326f99059fF775485246999027B3197955"),
+                QuoteToken::new("WBNB", "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"),
+            ],
+        };
+        let bsc = ChainConfig::from_env("bsc", Some(bsc.clone())).unwrap_or(bsc);
+        chains.insert(bsc.chain_id.clone(), bsc);
+
+        let extra_chains = std::env::var("DEX_CHAINS").unwrap_or_default();
+        for chain_id in extra_chains.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let chain_id = chain_id.to_lowercase();
+            if chains.contains_key(&chain_id) {
+                continue;
+            }
+            match ChainConfig::from_env(&chain_id, None) {
+                Some(chain) => {
+                    chains.insert(chain_id, chain);
+                }
+                None => tracing::warn!("Skipping chain {}: incomplete configuration", chain_id),
+            }
         }
+
+        Self { chains }
+    }
+
+    pub fn get_chain(&self, chain_id: &str) -> Option<&ChainConfig> {
+        self.chains.get(chain_id)
+    }
+}
+
+impl ChainConfig {
+    /// Read a chain from `<CHAIN>_*` env vars, falling back to `defaults` field by field
+    fn from_env(chain_id: &str, defaults: Option<ChainConfig>) -> Option<Self> {
+        let prefix = chain_id.to_uppercase();
+        let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok();
+
+        let rpc_url = var("RPC_URL").or_else(|| defaults.as_ref().map(|d| d.rpc_url.clone()))?;
+        let v2_factory = var("V2_FACTORY")
+            .or_else(|| defaults.as_ref().map(|d| d.dex_contracts.v2_factory.clone()))?;
+        let v2_router = var("V2_ROUTER")
+            .or_else(|| defaults.as_ref().map(|d| d.dex_contracts.v2_router.clone()))?;
+        let wrapped_native_address = var("WRAPPED_NATIVE")
+            .or_else(|| defaults.as_ref().map(|d| d.wrapped_native_address.clone()))?;
+        let stable_token_address = var("STABLE_TOKEN")
+            .or_else(|| defaults.as_ref().map(|d| d.stable_token_address.clone()))?;
+        let stable_token_decimals = var("STABLE_DECIMALS")
+            .and_then(|s| s.parse().ok())
+            .or_else(|| defaults.as_ref().map(|d| d.stable_token_decimals))
+            .unwrap_or(18);
+        let native_symbol = var("NATIVE_SYMBOL")
+            .or_else(|| defaults.as_ref().map(|d| d.native_symbol.clone()))
+            .unwrap_or_else(|| "ETH".to_string());
+        // Without an explicit list, search the stable coin and the wrapped native coin
+        let quote_tokens = var("PAIR_QUOTES")
+            .map(|raw| QuoteToken::parse_list(&raw))
+            .or_else(|| defaults.as_ref().map(|d| d.quote_tokens.clone()))
+            .unwrap_or_else(|| {
+                vec![
+                    QuoteToken::new("USD", &stable_token_address),
+                    QuoteToken::new(&format!("W{}", native_symbol), &wrapped_native_address),
+                ]
+            });
+
+        Some(Self {
+            chain_id: chain_id.to_string(),
+            rpc_url,
+            dex_contracts: DexContracts { v2_factory, v2_router },
+            wrapped_native_address,
+            stable_token_address,
+            stable_token_decimals,
+            native_symbol,
+            quote_tokens,
+        })
     }
+}
+
+/// Origins allowed to open websocket streams, from `DEX_WS_ALLOWED_ORIGINS` (comma-separated,
+/// e.g. "https://app.example.com"). Unset or empty allows every origin, for local development
+#[derive(Clone, Debug, Default)]
+pub struct OriginAllowlist {
+    origins: Option<Vec<String>>,
+}
 
-    pub fn get_rpc_url(&self, chain_id: &str) -> Option<&String> {
-        self.rpc_urls.get(chain_id)
+impl OriginAllowlist {
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("DEX_WS_ALLOWED_ORIGINS").unwrap_or_default().split(','))
     }
 
-    pub fn get_wbnb_address(&self) -> &str {
-        &self.stable_tokens["bsc_wbnb"]
+    /// Allow exactly `origins`; an empty list allows every origin
+    pub fn new<'a>(origins: impl IntoIterator<Item = &'a str>) -> Self {
+        let origins: Vec<String> = origins.into_iter().map(normalize_origin).filter(|o| !o.is_empty()).collect();
+        Self { origins: (!origins.is_empty()).then_some(origins) }
     }
 
-    pub fn get_busd_address(&self) -> &str {
-        &self.stable_tokens["bsc_busd"]
+    /// Whether an upgrade carrying `origin` may proceed. Requests without an `Origin` header don't
+    /// come from a browser page, so there is no cross-site page to hijack the socket and they pass
+    pub fn allows(&self, origin: Option<&str>) -> bool {
+        match (&self.origins, origin) {
+            (None, _) | (_, None) => true,
+            (Some(origins), Some(origin)) => origins.contains(&normalize_origin(origin)),
+        }
     }
 }
 
+fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_lowercase()
+}
+
 impl Default for BlockchainConfig {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_chain_resolves_its_own_contracts() {
+        // A prefix no other test or deployment uses, since the environment is process-wide
+        for (name, value) in [
+            ("RPC_URL", "http://127.0.0.1:8545"),
+            ("V2_FACTORY", "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f"),
+            ("V2_ROUTER", "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D"),
+            ("WRAPPED_NATIVE", "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+            ("STABLE_TOKEN", "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            ("STABLE_DECIMALS", "6"),
+        ] {
+            std::env::set_var(format!("CFGTEST_ETH_{}", name), value);
+        }
+
+        let mut config = BlockchainConfig::new();
+        let eth = ChainConfig::from_env("cfgtest_eth", None).expect("complete chain config");
+        config.chains.insert(eth.chain_id.clone(), eth);
+
+        let bsc = config.get_chain("bsc").unwrap();
+        assert_eq!(bsc.dex_contracts.v2_factory, "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73");
+
+        let eth = config.get_chain("cfgtest_eth").unwrap();
+        assert_eq!(eth.dex_contracts.v2_factory, "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f");
+        assert_eq!(eth.stable_token_decimals, 6);
+        assert_eq!(eth.native_symbol, "ETH");
+        assert_eq!(eth.quote_tokens.len(), 2);
+
+        // Without the bsc defaults to fall back on, a chain missing a required var is skipped
+        assert!(ChainConfig::from_env("cfgtest_missing", None).is_none());
+    }
+}
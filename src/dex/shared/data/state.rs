@@ -1,17 +1,31 @@
-use model::models;
-use repository::repositories;
+use repository::repositories::encryption::EncryptionRepository;
+use std::sync::Arc;
+
+use crate::shared::config::{BlockchainConfig, OriginAllowlist};
+use crate::shared::metadata_cache::MetadataCaches;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub repository: repositories::Repositories,
-    pub model: models::Models,
+    pub encryption: Arc<EncryptionRepository>,
+    /// Chains loaded once at startup instead of re-reading the environment per request
+    pub blockchain: Arc<BlockchainConfig>,
+    /// Origins allowed to open websocket streams
+    pub ws_origins: Arc<OriginAllowlist>,
+    /// Token metadata cached per chain across requests and connections
+    pub metadata: Arc<MetadataCaches>,
 }
 
 impl AppState {
     pub fn new(
-        repository: repositories::Repositories,
-        model: models::Models,
+        encryption: Arc<EncryptionRepository>,
+        blockchain: BlockchainConfig,
+        ws_origins: OriginAllowlist,
     ) -> Self {
-        Self { repository, model }
+        Self {
+            encryption,
+            blockchain: Arc::new(blockchain),
+            ws_origins: Arc::new(ws_origins),
+            metadata: Arc::new(MetadataCaches::new()),
+        }
     }
-}
\ No newline at end of file
+}
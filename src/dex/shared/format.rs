@@ -0,0 +1,54 @@
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::sync::OnceLock;
+
+/// Default significant figures kept when formatting prices and USD amounts
+const DEFAULT_SIGNIFICANT_DIGITS: u32 = 8;
+
+/// Significant figures for price output, from `DEX_PRICE_SIGNIFICANT_DIGITS` (read once)
+pub fn significant_digits() -> u32 {
+    static DIGITS: OnceLock<u32> = OnceLock::new();
+    *DIGITS.get_or_init(|| {
+        std::env::var("DEX_PRICE_SIGNIFICANT_DIGITS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|n| (1..=28).contains(n))
+            .unwrap_or(DEFAULT_SIGNIFICANT_DIGITS)
+    })
+}
+
+/// Plain decimal string of `value` rounded to `digits` significant figures, without exponent
+/// notation or trailing zeros (e.g. 0.000000000001234000001 -> "0.000000000001234")
+pub fn format_significant(value: f64, digits: u32) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+    match Decimal::from_f64(value).and_then(|d| d.round_sf(digits)) {
+        Some(rounded) => rounded.normalize().to_string(),
+        // Beyond Decimal's ~7.9e28 range, so always a whole number: expand the rounded mantissa with zeros
+        None => {
+            let scientific = format!("{:.*e}", digits.saturating_sub(1) as usize, value);
+            let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+            let exponent: usize = exponent.parse().unwrap_or(0);
+            let (sign, mantissa) = mantissa.strip_prefix('-').map_or(("", mantissa), |m| ("-", m));
+            let mantissa = mantissa.replace('.', "");
+            format!("{}{}{}", sign, mantissa, "0".repeat((exponent + 1).saturating_sub(mantissa.len())))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_are_rounded_to_significant_figures_without_exponents() {
+        assert_eq!(format_significant(0.000000000001234000001, 8), "0.000000000001234");
+        assert_eq!(format_significant(1234.56789012345, 8), "1234.5679");
+        assert_eq!(format_significant(600.0, 8), "600");
+        assert_eq!(format_significant(-0.5, 8), "-0.5");
+        assert_eq!(format_significant(1e30, 8), format!("1{}", "0".repeat(30)));
+        assert_eq!(format_significant(f64::NAN, 8), "0");
+    }
+}
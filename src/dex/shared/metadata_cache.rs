@@ -0,0 +1,153 @@
+use dashmap::DashMap;
+use ethers::types::Address;
+use repository::repositories::crypto::blockchain_client::TokenMetadata;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Default lifetime of a cached entry; name, symbol and decimals never change, supply rarely does
+const DEFAULT_TTL_SECONDS: u64 = 3600;
+
+/// Default number of metadata fetches allowed against a chain's RPC at once
+const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 16;
+
+/// Per-chain caches keyed by chain id, held in `AppState` and shared by every connection
+pub type MetadataCaches = DashMap<String, Arc<MetadataCache>>;
+
+/// Token metadata cached across requests, so a token watched by many clients is read from the chain once per TTL
+pub struct MetadataCache {
+    entries: DashMap<Address, (TokenMetadata, Instant)>,
+    ttl: Duration,
+    fetches: Semaphore,
+}
+
+impl MetadataCache {
+    /// Build a cache from `DEX_METADATA_TTL_SECONDS` and `DEX_METADATA_MAX_CONCURRENT_FETCHES`
+    pub fn from_env() -> Self {
+        let ttl = std::env::var("DEX_METADATA_TTL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TTL_SECONDS);
+        let max_fetches = std::env::var("DEX_METADATA_MAX_CONCURRENT_FETCHES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_FETCHES);
+
+        Self::new(Duration::from_secs(ttl), max_fetches)
+    }
+
+    pub fn new(ttl: Duration, max_fetches: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            fetches: Semaphore::new(max_fetches),
+        }
+    }
+
+    /// `chain_id`'s cache in `caches`, created on first use; the same address on two chains is two different tokens
+    pub fn for_chain(caches: &MetadataCaches, chain_id: &str) -> Arc<MetadataCache> {
+        caches
+            .entry(chain_id.to_string())
+            .or_insert_with(|| Arc::new(MetadataCache::from_env()))
+            .clone()
+    }
+
+    /// Cached metadata for `token`, if it was stored less than a TTL ago
+    pub fn get(&self, token: &Address) -> Option<TokenMetadata> {
+        let entry = self.entries.get(token)?;
+        let (metadata, stored_at) = entry.value();
+        if stored_at.elapsed() < self.ttl {
+            return Some(metadata.clone());
+        }
+        drop(entry);
+        self.entries.remove(token);
+        None
+    }
+
+    pub fn insert(&self, token: Address, metadata: TokenMetadata) {
+        self.entries.insert(token, (metadata, Instant::now()));
+    }
+
+    /// Wait for a fetch slot, bounding how many cache misses hit the RPC at the same time
+    pub async fn fetch_permit(&self) -> Option<SemaphorePermit<'_>> {
+        self.fetches.acquire().await.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::config::OriginAllowlist;
+    use crate::test_util;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use ethers::abi::Token;
+    use ethers::types::U256;
+    use tower::ServiceExt;
+
+    fn metadata(symbol: &str) -> TokenMetadata {
+        TokenMetadata {
+            name: symbol.to_string(),
+            symbol: symbol.to_string(),
+            decimals: 18,
+            total_supply: Default::default(),
+        }
+    }
+
+    #[test]
+    fn entries_are_served_until_the_ttl_runs_out() {
+        let cache = MetadataCache::new(Duration::from_millis(50), 1);
+        let token = Address::repeat_byte(1);
+        assert!(cache.get(&token).is_none());
+
+        cache.insert(token, metadata("CAKE"));
+        assert_eq!(cache.get(&token).unwrap().symbol, "CAKE");
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(cache.get(&token).is_none());
+    }
+
+    #[test]
+    fn each_chain_gets_its_own_cache() {
+        let caches = MetadataCaches::new();
+        let token = Address::repeat_byte(1);
+
+        MetadataCache::for_chain(&caches, "bsc").insert(token, metadata("CAKE"));
+        assert!(MetadataCache::for_chain(&caches, "bsc").get(&token).is_some());
+        assert!(MetadataCache::for_chain(&caches, "ethereum").get(&token).is_none());
+
+        // A second set of caches, as a second AppState would hold, starts empty
+        assert!(MetadataCache::for_chain(&MetadataCaches::new(), "bsc").get(&token).is_none());
+    }
+
+    #[tokio::test]
+    async fn a_second_lookup_within_the_ttl_is_served_without_the_rpc() {
+        let rpc = test_util::FakeRpc::start_with_code(
+            vec![
+                ("name()", vec![Token::String("PancakeSwap Token".to_string())]),
+                ("symbol()", vec![Token::String("Cake".to_string())]),
+                ("decimals()", vec![Token::Uint(U256::from(18))]),
+                ("totalSupply()", vec![Token::Uint(U256::exp10(24))]),
+            ],
+            "0x00",
+        )
+        .await;
+        let state = test_util::state_with_rpc(OriginAllowlist::default(), &rpc.url);
+        let lookup = || async {
+            let request = Request::get("/api/dex/bsc/0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82/metadata")
+                .body(Body::empty())
+                .unwrap();
+            let response = crate::build_router(state.clone()).oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            to_bytes(response.into_body(), usize::MAX).await.unwrap()
+        };
+
+        let first = lookup().await;
+        let calls = rpc.calls();
+        assert_eq!(calls, ["eth_getCode", "name()", "symbol()", "decimals()", "totalSupply()"]);
+
+        assert_eq!(lookup().await, first);
+        assert_eq!(rpc.calls(), calls);
+    }
+}
@@ -1 +1,4 @@
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod data;
+pub mod format;
+pub mod metadata_cache;
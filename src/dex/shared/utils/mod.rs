@@ -1,2 +0,0 @@
-pub mod config;
-pub mod logger;
\ No newline at end of file
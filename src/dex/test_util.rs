@@ -0,0 +1,146 @@
+//! Shared setup for the dex unit tests: state with unreachable RPCs, a router served on a local
+//! port and a canned JSON-RPC endpoint
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::Json;
+use ethers::abi::Token;
+use repository::repositories::encryption::EncryptionRepository;
+
+use crate::shared::config::{BlockchainConfig, OriginAllowlist};
+use crate::shared::data::state::AppState;
+use tracing::subscriber::DefaultGuard;
+
+/// Discard port: connections are refused at once, so RPC failures are immediate and deterministic
+pub const UNREACHABLE_RPC: &str = "http://127.0.0.1:9";
+
+/// Only bsc is configured, pointed at `UNREACHABLE_RPC`
+pub fn state(ws_origins: OriginAllowlist) -> AppState {
+    state_with_rpc(ws_origins, UNREACHABLE_RPC)
+}
+
+/// Only bsc is configured, pointed at `rpc_url`
+pub fn state_with_rpc(ws_origins: OriginAllowlist, rpc_url: &str) -> AppState {
+    let mut blockchain = BlockchainConfig::new();
+    blockchain.chains.retain(|chain_id, _| chain_id == "bsc");
+    for chain in blockchain.chains.values_mut() {
+        chain.rpc_url = rpc_url.to_string();
+    }
+    AppState::new(Arc::new(EncryptionRepository::from_env()), blockchain, ws_origins)
+}
+
+/// Serve the full dex router on an ephemeral port; websocket upgrades need a real connection
+pub async fn serve(state: AppState) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, crate::build_router(state)).await.unwrap() });
+    address
+}
+
+/// JSON-RPC endpoint answering `eth_call` from results keyed by function signature, e.g.
+/// `("getReserves()", vec![...])`. `eth_getCode` finds no contract anywhere and any other call
+/// reverts. Every call is recorded by signature or method
+pub struct FakeRpc {
+    pub url: String,
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeRpc {
+    pub async fn start(results: Vec<(&str, Vec<Token>)>) -> Self {
+        Self::start_with_code(results, "0x").await
+    }
+
+    /// Like `start`, but `eth_getCode` answers `code` for every address, e.g. `"0x00"` for a deployed contract
+    pub async fn start_with_code(results: Vec<(&str, Vec<Token>)>, code: &'static str) -> Self {
+        let results: Arc<HashMap<[u8; 4], (String, String)>> = Arc::new(
+            results
+                .into_iter()
+                .map(|(signature, tokens)| {
+                    let selector = ethers::utils::id(signature);
+                    let encoded = format!("0x{}", ethers::utils::hex::encode(ethers::abi::encode(&tokens)));
+                    (selector, (signature.to_string(), encoded))
+                })
+                .collect(),
+        );
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let handler = move |Json(request): Json<serde_json::Value>| {
+            let (results, calls) = (results.clone(), recorded.clone());
+            async move {
+                let call = &request["params"][0];
+                let data = call["input"].as_str().or_else(|| call["data"].as_str()).unwrap_or_default();
+                let selector = ethers::utils::hex::decode(data.trim_start_matches("0x"))
+                    .ok()
+                    .and_then(|bytes| bytes.get(..4).and_then(|s| <[u8; 4]>::try_from(s).ok()));
+                let answer = match (request["method"].as_str(), selector.and_then(|s| results.get(&s))) {
+                    (Some("eth_call"), Some((signature, encoded))) => {
+                        calls.lock().unwrap().push(signature.clone());
+                        serde_json::json!({ "result": encoded })
+                    }
+                    (Some("eth_getCode"), _) => {
+                        calls.lock().unwrap().push("eth_getCode".to_string());
+                        serde_json::json!({ "result": code })
+                    }
+                    (method, _) => {
+                        calls.lock().unwrap().push(format!("{} {}", method.unwrap_or("?"), data));
+                        serde_json::json!({ "error": { "code": -32000, "message": "execution reverted" } })
+                    }
+                };
+                let mut response = serde_json::json!({ "jsonrpc": "2.0", "id": request["id"] });
+                response.as_object_mut().unwrap().extend(answer.as_object().unwrap().clone());
+                Json(response)
+            }
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let router = axum::Router::new().route("/", axum::routing::post(handler));
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        Self { url: format!("http://{}", address), calls }
+    }
+
+    /// Signatures of the calls made so far, in order; unanswered ones as `<method> <calldata>`
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+/// Plain-text logs emitted on the current thread while it lives; needs the current-thread
+/// runtime so the served router's tasks log on the test's thread
+pub struct CapturedLogs {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    _guard: DefaultGuard,
+}
+
+struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    pub fn start() -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || CaptureWriter(writer.clone()))
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        Self { buffer, _guard }
+    }
+
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.lock().unwrap()).into_owned()
+    }
+}
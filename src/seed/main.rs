@@ -0,0 +1,109 @@
+use chrono::Utc;
+use dotenvy::dotenv;
+use model::migration::{Migrator, MigratorTrait};
+use model::models::admin::{entity as admin_entity, repo::{AdminRepository, AdminRepositoryError, AdminRepositoryTrait}};
+use model::models::Models;
+use node_server::shared::utils::config::AppConfig;
+use repository::repositories::encryption::{EncryptionRepository, EncryptionRepositoryTrait};
+use uuid::Uuid;
+
+/// Create the initial admin from `ADMIN_EMAIL`/`ADMIN_PASSWORD`.
+///
+/// Safe to run repeatedly: an existing admin with the same email is left untouched.
+#[tokio::main]
+async fn main() {
+    let _ = dotenv();
+
+    logger::init();
+
+    if let Err(e) = run().await {
+        tracing::error!("Failed to seed admin: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), String> {
+    let email = std::env::var("ADMIN_EMAIL")
+        .map_err(|_| "ADMIN_EMAIL is not set".to_string())?
+        .trim()
+        .to_lowercase();
+    let password = std::env::var("ADMIN_PASSWORD").map_err(|_| "ADMIN_PASSWORD is not set".to_string())?;
+    if email.is_empty() || password.is_empty() {
+        return Err("ADMIN_EMAIL and ADMIN_PASSWORD must not be empty".to_string());
+    }
+
+    // Same config as the server, so a missing DATABASE_URL fails here too instead of seeding another database
+    let cfg = AppConfig::try_from_env().map_err(|e| e.to_string())?;
+    let models = Models::new(&cfg.database_url)
+        .await
+        .map_err(|e| format!("Failed to connect to the database: {}", e))?;
+    Migrator::up(&models.db, None)
+        .await
+        .map_err(|e| format!("Failed to run migrations: {}", e))?;
+
+    let created = seed_admin(&models.admin, &EncryptionRepository::from_env(), &email, &password).await?;
+    if created {
+        tracing::info!("Admin {} created", email);
+    } else {
+        tracing::info!("Admin {} already exists, nothing to do", email);
+    }
+
+    Ok(())
+}
+
+/// Insert the admin unless one with `email` already exists; returns whether a row was created
+async fn seed_admin(
+    admin_repo: &AdminRepository,
+    encryption: &EncryptionRepository,
+    email: &str,
+    password: &str,
+) -> Result<bool, String> {
+    match admin_repo.get_by_email(email).await {
+        Ok(_) => return Ok(false),
+        Err(AdminRepositoryError::NotFound(_)) => {}
+        Err(e) => return Err(e.to_string()),
+    }
+
+    let password = encryption
+        .hash_password(password)
+        .map_err(|e| format!("Failed to hash password: {:?}", e))?;
+
+    let now = Utc::now();
+    let admin = admin_entity::Model {
+        id: Uuid::new_v4(),
+        email_address: email.to_string(),
+        password,
+        created_at: now.into(),
+        updated_at: now.into(),
+        deleted_at: None,
+    };
+
+    admin_repo.create(admin).await.map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repository::repositories::encryption::data::{ArgonConfig, JwtConfig};
+    use sea_orm::{Database, EntityTrait, PaginatorTrait};
+
+    #[tokio::test]
+    async fn seeding_twice_creates_one_admin() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        let models = Models::from_connection(db);
+        let encryption = EncryptionRepository::new(
+            ArgonConfig { t_cost: 1, m_cost_kib: 8, p_cost: 1, pepper: None },
+            JwtConfig::from_env(),
+        );
+
+        assert!(seed_admin(&models.admin, &encryption, "root@example.com", "first").await.unwrap());
+        assert!(!seed_admin(&models.admin, &encryption, "root@example.com", "second").await.unwrap());
+        assert_eq!(admin_entity::Entity::find().count(&models.db).await.unwrap(), 1);
+
+        // The second run left the original password in place
+        let admin = models.admin.get_by_email("root@example.com").await.unwrap();
+        assert!(encryption.verify_password(&admin.password, "first").unwrap());
+    }
+}
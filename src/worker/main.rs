@@ -0,0 +1,142 @@
+use dotenvy::dotenv;
+use repository::repositories::mailer::data::{EmailMessage, MailError};
+use repository::repositories::mailer::Mailer;
+use model::jobs::{EmailJob, JobEnvelope};
+use repository::repositories::queue::{data::QueueError, QueueRepositoryTrait};
+use repository::repositories::Repositories;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::time::{sleep, Duration};
+use tokio_util::sync::CancellationToken;
+
+/// Consume `EmailJob` envelopes from the emails queue and deliver them.
+///
+/// Transient failures are requeued; malformed or unknown kind/version envelopes and permanent
+/// mail errors go to the dead-letter queue.
+/// Ctrl-C stops consuming after the in-flight email is finished.
+#[tokio::main]
+async fn main() {
+    let _ = dotenv();
+    logger::init();
+
+    let repositories = Repositories::new();
+    let mailer = repositories.mailer.clone();
+    let email_queue = repositories.queue_names.emails.clone();
+
+    let shutdown = CancellationToken::new();
+    let signal = shutdown.clone();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        tracing::info!("shutdown requested, finishing in-flight email");
+        signal.cancel();
+    });
+
+    while !shutdown.is_cancelled() {
+        tracing::info!("consuming queue: {}", email_queue);
+        let result = repositories
+            .queue
+            .consume(&email_queue, email_handler(mailer.clone(), Handle::current()), shutdown.clone())
+            .await;
+
+        if shutdown.is_cancelled() {
+            break;
+        }
+        match result {
+            Ok(()) => tracing::warn!("consumer stream ended, reconnecting"),
+            Err(e) => tracing::error!("consumer failed: {}, reconnecting", e),
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = sleep(Duration::from_secs(5)) => {}
+        }
+    }
+
+    tracing::info!("worker stopped");
+}
+
+/// Queue handler delivering each `EmailJob` envelope through `mailer`
+fn email_handler(mailer: Arc<dyn Mailer>, runtime: Handle) -> impl Fn(Vec<u8>) -> Result<(), QueueError> + Send + Sync {
+    move |data| {
+        let to_queue_error = |e: MailError| match e {
+            MailError::Transient(msg) => QueueError::QueueError(msg),
+            MailError::Permanent(msg) => QueueError::Rejected(msg),
+        };
+
+        let envelope = JobEnvelope::<EmailJob>::from_bytes(&data)
+            .map_err(|e| QueueError::Rejected(e.to_string()))?;
+        let job = envelope.payload;
+        let to = job.to.clone();
+        let message = EmailMessage::from_template(job.to, &job.template, &job.context).map_err(to_queue_error)?;
+
+        // The queue handler is synchronous; park this worker thread while the send completes
+        tokio::task::block_in_place(|| runtime.block_on(mailer.send(message))).map_err(to_queue_error)?;
+
+        tracing::info!(to = %to, "email sent");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use repository::repositories::mailer::templates::{CodeContext, EmailTemplate};
+    use repository::repositories::queue::mock::InMemoryQueue;
+    use std::sync::Mutex;
+
+    /// Records every message instead of sending it
+    #[derive(Default)]
+    struct RecordingMailer(Mutex<Vec<EmailMessage>>);
+
+    #[async_trait]
+    impl Mailer for RecordingMailer {
+        async fn send(&self, msg: EmailMessage) -> Result<(), MailError> {
+            self.0.lock().unwrap().push(msg);
+            Ok(())
+        }
+    }
+
+    fn reset_code_job(to: &str, code: &str) -> Vec<u8> {
+        let job = EmailJob {
+            to: to.to_string(),
+            template: EmailTemplate::ResetCode.as_str().to_string(),
+            context: CodeContext { first_name: "Ada".to_string(), code: code.to_string(), expires_in: "7 days".to_string() }.into(),
+        };
+        JobEnvelope::new(job, None).to_bytes().unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_consumed_job_is_sent_exactly_once_and_acked() {
+        let queue = InMemoryQueue::new();
+        queue.publish("emails", &reset_code_job("ada@example.com", "482913")).await.unwrap();
+        let mailer = Arc::new(RecordingMailer::default());
+
+        queue
+            .consume("emails", email_handler(mailer.clone(), Handle::current()), CancellationToken::new())
+            .await
+            .unwrap();
+
+        let sent = mailer.0.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "ada@example.com");
+        assert!(sent[0].body.contains("482913"));
+        assert_eq!(queue.acked().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_malformed_job_is_dead_lettered_without_sending() {
+        let queue = InMemoryQueue::new();
+        queue.publish("emails", b"{\"not\":\"an envelope\"}").await.unwrap();
+        let mailer = Arc::new(RecordingMailer::default());
+
+        queue
+            .consume("emails", email_handler(mailer.clone(), Handle::current()), CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert!(mailer.0.lock().unwrap().is_empty());
+        assert_eq!(queue.dead_lettered().len(), 1);
+        assert!(queue.acked().is_empty());
+    }
+}
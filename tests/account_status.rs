@@ -0,0 +1,39 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::Utc;
+use common::{tokens, TestApp};
+use model::models::user::repo::UserRepositoryTrait;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn soft_deleted_user_access_token_is_rejected() {
+    let app = TestApp::new().await;
+    let signed_up = app.sign_up("ada@example.com").await;
+    let user_id: Uuid = signed_up.body["data"]["id"].as_str().unwrap().parse().unwrap();
+    let (access, _) = tokens(&signed_up);
+
+    let mut user = app.models.user.get_by_id(user_id).await.unwrap();
+    user.deleted_at = Some(Utc::now().into());
+    app.models.user.update(user).await.unwrap();
+
+    // The token itself is still unexpired and correctly signed
+    let response = app.get("/api/v1/user/profile", Some(&access)).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED, "{}", response.body);
+}
+
+#[tokio::test]
+async fn banned_user_access_token_is_forbidden() {
+    let app = TestApp::new().await;
+    let signed_up = app.sign_up("mallory@example.com").await;
+    let user_id: Uuid = signed_up.body["data"]["id"].as_str().unwrap().parse().unwrap();
+    let (access, _) = tokens(&signed_up);
+
+    let mut user = app.models.user.get_by_id(user_id).await.unwrap();
+    user.peripheral_is_banned = true;
+    app.models.user.update(user).await.unwrap();
+
+    let response = app.get("/api/v1/user/profile", Some(&access)).await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN, "{}", response.body);
+    assert_eq!(response.body["code"], "ACCOUNT_BANNED");
+}
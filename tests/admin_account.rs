@@ -0,0 +1,64 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+const NEW_PASSWORD: &str = "Battery-Staple-77";
+
+#[tokio::test]
+async fn change_password_rejects_a_wrong_current_password() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token("root@example.com").await;
+
+    let response = app
+        .post(
+            "/api/v1/admin/change-password",
+            json!({ "current_password": "nope", "new_password": NEW_PASSWORD, "confirm_password": NEW_PASSWORD }),
+            Some(&admin),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED, "{}", response.body);
+    assert_eq!(response.body["code"], "INVALID_CURRENT_PASSWORD");
+}
+
+#[tokio::test]
+async fn change_password_rejects_a_mismatched_confirmation() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token("root@example.com").await;
+
+    let response = app
+        .post(
+            "/api/v1/admin/change-password",
+            json!({ "current_password": PASSWORD, "new_password": NEW_PASSWORD, "confirm_password": "Other-Staple-77" }),
+            Some(&admin),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST, "{}", response.body);
+    assert_eq!(response.body["code"], "PASSWORD_MISMATCH");
+}
+
+#[tokio::test]
+async fn changed_password_is_the_one_sign_in_accepts() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token("root@example.com").await;
+
+    let response = app
+        .post(
+            "/api/v1/admin/change-password",
+            json!({ "current_password": PASSWORD, "new_password": NEW_PASSWORD, "confirm_password": NEW_PASSWORD }),
+            Some(&admin),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+
+    let old = app
+        .post("/api/v1/admin/auth/sign-in", json!({ "email_address": "root@example.com", "password": PASSWORD }), None)
+        .await;
+    assert_eq!(old.status, StatusCode::UNAUTHORIZED);
+
+    let new = app
+        .post("/api/v1/admin/auth/sign-in", json!({ "email_address": "root@example.com", "password": NEW_PASSWORD }), None)
+        .await;
+    assert_eq!(new.status, StatusCode::OK, "{}", new.body);
+}
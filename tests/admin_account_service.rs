@@ -0,0 +1,69 @@
+//! `AdminAccountService` driven directly with `MockEncryption` instead of through the router
+
+use chrono::Utc;
+use model::migration::{Migrator, MigratorTrait};
+use model::models::admin::entity::Model as AdminModel;
+use model::models::admin::repo::AdminRepositoryTrait;
+use model::models::admin::AdminChangePasswordRequest;
+use model::models::Models;
+use node_server::features::admin::account::service::{AdminAccountError, AdminAccountService};
+use repository::repositories::encryption::mock::MockEncryption;
+use sea_orm::Database;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const PASSWORD: &str = "Correct-Horse-42";
+const NEW_PASSWORD: &str = "Battery-Staple-77";
+
+/// A service over SQLite holding one admin whose password is `PASSWORD`, hashed by the mock
+async fn service_with_admin() -> (AdminAccountService, Models, Uuid) {
+    let db = Database::connect("sqlite::memory:").await.expect("open sqlite");
+    Migrator::up(&db, None).await.expect("migrate sqlite");
+    let models = Models::from_connection(db);
+
+    let now = Utc::now().into();
+    let admin = models
+        .admin
+        .create(AdminModel {
+            id: Uuid::new_v4(),
+            email_address: "root@example.com".to_string(),
+            password: format!("mock-hash:{}", PASSWORD),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        })
+        .await
+        .expect("create admin");
+
+    let service = AdminAccountService::new(models.admin.clone(), Arc::new(MockEncryption::new()));
+    (service, models, admin.id)
+}
+
+fn change(current: &str) -> AdminChangePasswordRequest {
+    AdminChangePasswordRequest {
+        current_password: current.to_string(),
+        new_password: NEW_PASSWORD.to_string(),
+        confirm_password: NEW_PASSWORD.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn change_password_stores_the_hash_from_the_injected_encryption() {
+    let (service, models, id) = service_with_admin().await;
+
+    service.change_password(id, change(PASSWORD)).await.unwrap();
+
+    let stored = models.admin.get_by_id(id).await.unwrap();
+    assert_eq!(stored.password, format!("mock-hash:{}", NEW_PASSWORD));
+}
+
+#[tokio::test]
+async fn change_password_checks_the_current_password_with_the_injected_encryption() {
+    let (service, models, id) = service_with_admin().await;
+
+    let err = service.change_password(id, change("Wrong-Horse-42")).await.unwrap_err();
+    assert!(matches!(err, AdminAccountError::InvalidCurrentPassword), "{:?}", err);
+
+    let stored = models.admin.get_by_id(id).await.unwrap();
+    assert_eq!(stored.password, format!("mock-hash:{}", PASSWORD));
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn wrong_password_and_unknown_email_are_the_same_error() {
+    let app = TestApp::new().await;
+    app.admin_token("root@example.com").await;
+
+    let wrong = app
+        .post("/api/v1/admin/auth/sign-in", json!({ "email_address": "root@example.com", "password": "nope" }), None)
+        .await;
+    let unknown = app
+        .post("/api/v1/admin/auth/sign-in", json!({ "email_address": "who@example.com", "password": PASSWORD }), None)
+        .await;
+
+    for response in [wrong, unknown] {
+        assert_eq!(response.status, StatusCode::UNAUTHORIZED, "{}", response.body);
+        assert_eq!(response.body["status"], false);
+        assert_eq!(response.body["code"], "INVALID_CREDENTIALS");
+    }
+}
+
+#[tokio::test]
+async fn sign_in_normalizes_the_email() {
+    let app = TestApp::new().await;
+    app.admin_token("root@example.com").await;
+
+    let response = app
+        .post("/api/v1/admin/auth/sign-in", json!({ "email_address": " Root@Example.com ", "password": PASSWORD }), None)
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert!(response.body["data"]["access_token"].is_string());
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+
+#[tokio::test]
+async fn has_next_flips_on_the_last_page() {
+    let app = TestApp::new().await;
+    for n in 0..5 {
+        app.signed_up_user(&format!("user{}@example.com", n)).await;
+    }
+    let admin = app.admin_token("root@example.com").await;
+
+    let mut seen = Vec::new();
+    for (page, has_next, count) in [(1, true, 2), (2, true, 2), (3, false, 1)] {
+        let response = app.get(&format!("/api/v1/admin/users?limit=2&page={}", page), Some(&admin)).await;
+        assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+
+        let data = &response.body["data"];
+        assert_eq!(data["total"], 5);
+        assert_eq!(data["has_next"], has_next, "page {}", page);
+        let items = data["items"].as_array().unwrap();
+        assert_eq!(items.len(), count, "page {}", page);
+        seen.extend(items.iter().map(|u| u["id"].as_str().unwrap().to_string()));
+    }
+
+    // Pages don't overlap
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen.len(), 5);
+}
+
+#[tokio::test]
+async fn exact_multiple_has_no_next_page() {
+    let app = TestApp::new().await;
+    for n in 0..4 {
+        app.signed_up_user(&format!("user{}@example.com", n)).await;
+    }
+    let admin = app.admin_token("root@example.com").await;
+
+    let response = app.get("/api/v1/admin/users?limit=2&page=2", Some(&admin)).await;
+    assert_eq!(response.body["data"]["has_next"], false, "{}", response.body);
+    assert_eq!(response.body["data"]["items"].as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{tokens, TestApp, PASSWORD};
+use model::models::session::repo::SessionRepositoryTrait;
+use model::models::user::repo::UserRepositoryTrait;
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn admin_token_passes_admin_auth() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token("root@example.com").await;
+
+    let users = app.get("/api/v1/admin/users", Some(&admin)).await;
+    assert_eq!(users.status, StatusCode::OK, "{}", users.body);
+
+    let response = app.post(&format!("/api/v1/admin/users/{}/ban", Uuid::new_v4()), json!({}), Some(&admin)).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND, "{}", response.body);
+    assert_eq!(response.body["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn admin_routes_reject_user_tokens() {
+    let app = TestApp::new().await;
+    let (access, _) = app.signed_up_user("eve@example.com").await;
+
+    let response = app.post(&format!("/api/v1/admin/users/{}/ban", Uuid::new_v4()), json!({}), Some(&access)).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    let response = app.get("/api/v1/admin/users", Some("Token abc")).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn banning_a_user_revokes_sessions_and_refuses_refresh() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token("root@example.com").await;
+
+    let signed_up = app.sign_up("mallory@example.com").await;
+    assert_eq!(signed_up.status, StatusCode::CREATED, "{}", signed_up.body);
+    let user_id: Uuid = signed_up.body["data"]["id"].as_str().unwrap().parse().unwrap();
+    let (_, refresh) = tokens(&signed_up);
+    assert!(!app.models.session.list_active_by_user(user_id).await.unwrap().is_empty());
+
+    let banned = app.post(&format!("/api/v1/admin/users/{}/ban", user_id), json!({}), Some(&admin)).await;
+    assert_eq!(banned.status, StatusCode::OK, "{}", banned.body);
+    assert!(app.models.session.list_active_by_user(user_id).await.unwrap().is_empty());
+
+    let refreshed = app.post("/api/v1/user/auth/refresh-token", json!({ "refresh_token": refresh }), None).await;
+    assert_eq!(refreshed.status, StatusCode::UNAUTHORIZED, "{}", refreshed.body);
+}
+
+#[tokio::test]
+async fn refresh_is_refused_for_a_banned_user_with_a_live_session() {
+    let app = TestApp::new().await;
+    let signed_up = app.sign_up("mallory@example.com").await;
+    let user_id: Uuid = signed_up.body["data"]["id"].as_str().unwrap().parse().unwrap();
+    let (_, refresh) = tokens(&signed_up);
+
+    // Banned behind the service's back, so the session is still active
+    let mut user = app.models.user.get_by_id(user_id).await.unwrap();
+    user.peripheral_is_banned = true;
+    app.models.user.update(user).await.unwrap();
+
+    let refreshed = app.post("/api/v1/user/auth/refresh-token", json!({ "refresh_token": refresh }), None).await;
+    assert_eq!(refreshed.status, StatusCode::FORBIDDEN, "{}", refreshed.body);
+    assert_eq!(refreshed.body["code"], "ACCOUNT_BANNED");
+}
+
+#[tokio::test]
+async fn banned_user_cannot_sign_in_until_unbanned() {
+    let app = TestApp::new().await;
+    let admin = app.admin_token("root@example.com").await;
+    let signed_up = app.sign_up("mallory@example.com").await;
+    let user_id = signed_up.body["data"]["id"].as_str().unwrap().to_string();
+
+    let banned = app.post(&format!("/api/v1/admin/users/{}/ban", user_id), json!({}), Some(&admin)).await;
+    assert_eq!(banned.status, StatusCode::OK, "{}", banned.body);
+
+    let refused = app.sign_in("mallory@example.com", PASSWORD).await;
+    assert_eq!(refused.status, StatusCode::FORBIDDEN, "{}", refused.body);
+    assert_eq!(refused.body["code"], "ACCOUNT_BANNED");
+
+    let unbanned = app.post(&format!("/api/v1/admin/users/{}/unban", user_id), json!({}), Some(&admin)).await;
+    assert_eq!(unbanned.status, StatusCode::OK, "{}", unbanned.body);
+    assert_eq!(app.sign_in("mallory@example.com", PASSWORD).await.status, StatusCode::OK);
+}
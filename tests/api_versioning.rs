@@ -0,0 +1,24 @@
+mod common;
+
+use axum::http::{header, StatusCode};
+use common::TestApp;
+
+#[tokio::test]
+async fn unversioned_alias_works_but_is_marked_deprecated() {
+    let app = TestApp::new().await;
+
+    let response = app.get("/api/health/details", None).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert_eq!(response.headers["deprecation"], "true");
+    assert_eq!(response.headers[header::LINK], "</api/v1/health/details>; rel=\"successor-version\"");
+}
+
+#[tokio::test]
+async fn versioned_routes_carry_no_deprecation() {
+    let app = TestApp::new().await;
+
+    let response = app.get("/api/v1/health/details", None).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert!(response.headers.get("deprecation").is_none());
+    assert!(response.headers.get(header::LINK).is_none());
+}
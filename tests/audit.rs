@@ -0,0 +1,35 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{CapturedLogs, TestApp, PASSWORD};
+use node_server::shared::utils::audit::AUDIT_TARGET;
+
+#[tokio::test]
+async fn failed_sign_in_is_audited_as_a_failure() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    let logs = CapturedLogs::start();
+    let response = app.sign_in("ada@example.com", "wrong-password").await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    let audit = logs.lines_for(AUDIT_TARGET);
+    assert_eq!(audit.len(), 1, "{:?}", audit);
+    assert!(audit[0].contains("event=\"sign_in\""), "{}", audit[0]);
+    assert!(audit[0].contains("success=false"), "{}", audit[0]);
+    assert!(audit[0].contains("reason=invalid password"), "{}", audit[0]);
+}
+
+#[tokio::test]
+async fn successful_sign_in_is_audited_as_a_success() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    let logs = CapturedLogs::start();
+    let response = app.sign_in("ada@example.com", PASSWORD).await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let audit = logs.lines_for(AUDIT_TARGET);
+    assert_eq!(audit.len(), 1, "{:?}", audit);
+    assert!(audit[0].contains("success=true"), "{}", audit[0]);
+}
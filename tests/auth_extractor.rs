@@ -0,0 +1,109 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::{routing::get, Extension, Router};
+use common::TestApp;
+use node_server::shared::data::{state::AppState, AuthUser};
+use node_server::shared::middlewares::auth::require_user_auth;
+use repository::repositories::encryption::data::{EncryptionError, TokenOptions, TokenParams};
+use repository::repositories::encryption::EncryptionRepositoryTrait;
+use repository::repositories::Repositories;
+use uuid::Uuid;
+
+/// The app's real encryption, counting every `decode_token` call
+struct CountingEncryption {
+    inner: Arc<dyn EncryptionRepositoryTrait>,
+    decodes: AtomicUsize,
+}
+
+impl CountingEncryption {
+    fn decodes(&self) -> usize {
+        self.decodes.load(Ordering::SeqCst)
+    }
+}
+
+impl EncryptionRepositoryTrait for CountingEncryption {
+    fn hash_password(&self, plain: &str) -> Result<String, EncryptionError> {
+        self.inner.hash_password(plain)
+    }
+
+    fn verify_password(&self, hash: &str, plain: &str) -> Result<bool, EncryptionError> {
+        self.inner.verify_password(hash, plain)
+    }
+
+    fn encrypt_data(&self, data: &str) -> Result<String, EncryptionError> {
+        self.inner.encrypt_data(data)
+    }
+
+    fn decrypt_data(&self, encrypted_data: &str) -> Result<String, EncryptionError> {
+        self.inner.decrypt_data(encrypted_data)
+    }
+
+    fn create_token_value(&self, payload: serde_json::Value, token_type: TokenParams, options: TokenOptions) -> Result<String, EncryptionError> {
+        self.inner.create_token_value(payload, token_type, options)
+    }
+
+    fn decode_token(&self, token_string: &str, token_type: TokenParams) -> Result<serde_json::Value, EncryptionError> {
+        self.decodes.fetch_add(1, Ordering::SeqCst);
+        self.inner.decode_token(token_string, token_type)
+    }
+
+    fn create_code(&self, length: usize) -> String {
+        self.inner.create_code(length)
+    }
+
+    fn create_signed_link(&self, user_id: Uuid, purpose: &str, ttl_seconds: i64) -> Result<String, EncryptionError> {
+        self.inner.create_signed_link(user_id, purpose, ttl_seconds)
+    }
+
+    fn verify_signed_link(&self, token: &str, purpose: &str) -> Result<Uuid, EncryptionError> {
+        self.inner.verify_signed_link(token, purpose)
+    }
+}
+
+/// `/me` takes `AuthUser` as an extractor; with `behind_middleware` it also sits behind
+/// `require_user_auth`. Both read the counting encryption
+fn router(app: &TestApp, behind_middleware: bool) -> (Router, Arc<CountingEncryption>) {
+    let counting = Arc::new(CountingEncryption { inner: app.repositories.encryption.clone(), decodes: AtomicUsize::new(0) });
+    let encryption: Arc<dyn EncryptionRepositoryTrait> = counting.clone();
+    let repositories = Repositories { encryption: encryption.clone(), ..app.repositories.clone() };
+
+    let mut router = Router::new().route("/me", get(|user: AuthUser| async move { user.email_address }));
+    if behind_middleware {
+        router = router.layer(middleware::from_fn(require_user_auth));
+    }
+    let router = router
+        .layer(Extension(encryption))
+        .with_state(AppState::new(repositories, app.models.clone()));
+    (router, counting)
+}
+
+#[tokio::test]
+async fn middleware_and_extractor_decode_the_token_once() {
+    let app = TestApp::new().await;
+    let (token, _) = app.signed_up_user("ada@example.com").await;
+    let (router, counting) = router(&app, true);
+    let app = TestApp { router, ..app };
+
+    let response = app.get("/me", Some(&token)).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert_eq!(response.bytes, "ada@example.com");
+    assert_eq!(counting.decodes(), 1);
+}
+
+#[tokio::test]
+async fn extractor_decodes_on_its_own_without_the_middleware() {
+    let app = TestApp::new().await;
+    let (token, _) = app.signed_up_user("ada@example.com").await;
+    let (router, counting) = router(&app, false);
+    let app = TestApp { router, ..app };
+
+    let response = app.get("/me", Some(&token)).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert_eq!(response.bytes, "ada@example.com");
+    assert_eq!(counting.decodes(), 1);
+}
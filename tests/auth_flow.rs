@@ -0,0 +1,70 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{tokens, TestApp, PASSWORD};
+
+#[tokio::test]
+async fn sign_up_sign_in_then_read_profile() {
+    let app = TestApp::new().await;
+
+    let signed_up = app.sign_up("ada@example.com").await;
+    assert_eq!(signed_up.status, StatusCode::CREATED, "{}", signed_up.body);
+
+    let signed_in = app.sign_in("ada@example.com", PASSWORD).await;
+    assert_eq!(signed_in.status, StatusCode::OK, "{}", signed_in.body);
+    let (access, _) = tokens(&signed_in);
+
+    let profile = app.get("/api/v1/user/profile", Some(&access)).await;
+    assert_eq!(profile.status, StatusCode::OK, "{}", profile.body);
+    assert_eq!(profile.body["data"]["id"], signed_up.body["data"]["id"]);
+    assert!(profile.headers.contains_key("etag"));
+}
+
+#[tokio::test]
+async fn profile_requires_a_token() {
+    let app = TestApp::new().await;
+
+    let profile = app.get("/api/v1/user/profile", None).await;
+    assert_eq!(profile.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(profile.body["status"], false);
+}
+
+#[tokio::test]
+async fn signing_up_twice_is_email_exists() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    let again = app.sign_up("ada@example.com").await;
+    assert_eq!(again.status, StatusCode::CONFLICT, "{}", again.body);
+    assert_eq!(again.body["status"], false);
+    assert_eq!(again.body["code"], "EMAIL_EXISTS");
+}
+
+#[tokio::test]
+async fn padded_mixed_case_email_is_the_same_account_on_sign_up_and_sign_in() {
+    let app = TestApp::new().await;
+
+    let created = app.sign_up(" Alice@X.com ").await;
+    assert_eq!(created.status, StatusCode::CREATED, "{}", created.body);
+
+    let signed_in = app.sign_in("alice@x.com", PASSWORD).await;
+    assert_eq!(signed_in.status, StatusCode::OK, "{}", signed_in.body);
+
+    let again = app.sign_up("ALICE@x.com").await;
+    assert_eq!(again.status, StatusCode::CONFLICT, "{}", again.body);
+    assert_eq!(again.body["code"], "EMAIL_EXISTS");
+}
+
+#[tokio::test]
+async fn unknown_email_and_wrong_password_are_indistinguishable() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    let unknown = app.sign_in("nobody@example.com", PASSWORD).await;
+    let wrong = app.sign_in("ada@example.com", "Wrong-Horse-42").await;
+
+    assert_eq!(unknown.status, StatusCode::UNAUTHORIZED, "{}", unknown.body);
+    assert_eq!(unknown.body["code"], "INVALID_CREDENTIALS");
+    assert_eq!(wrong.status, unknown.status);
+    assert_eq!(wrong.body, unknown.body);
+}
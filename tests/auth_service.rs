@@ -0,0 +1,74 @@
+//! `AuthService` driven directly with test doubles instead of through the router
+
+use model::migration::{Migrator, MigratorTrait};
+use model::models::user::entity::Model as User;
+use model::models::user::mock::InMemoryUserRepository;
+use model::models::user::model::{LoginRequest, RegisterRequest};
+use model::models::user::repo::UserRepositoryTrait;
+use model::models::Models;
+use node_server::features::user::auth::service::{AuthError, AuthService};
+use node_server::shared::extractors::client_context::ClientContext;
+use repository::repositories::encryption::mock::MockEncryption;
+use repository::repositories::encryption::EncryptionRepositoryTrait;
+use sea_orm::Database;
+use std::sync::Arc;
+
+async fn models() -> Models {
+    let db = Database::connect("sqlite::memory:").await.expect("open sqlite");
+    Migrator::up(&db, None).await.expect("migrate sqlite");
+    Models::from_connection(db)
+}
+
+#[tokio::test]
+async fn sign_up_creates_exactly_an_access_and_a_refresh_token() {
+    let models = models().await;
+    let encryption = Arc::new(MockEncryption::new());
+    let service = AuthService::new(Arc::new(models.user.clone()), models.session.clone(), encryption.clone());
+
+    let response = service
+        .sign_up(RegisterRequest::new("Ada", "Lovelace", "ada@example.com", "Correct-Horse-42"), &ClientContext::default())
+        .await
+        .unwrap();
+
+    assert_eq!(encryption.tokens_created(), 2);
+    assert!(response.access_token.starts_with("mock-token-1:"), "{}", response.access_token);
+    assert!(response.refresh_token.starts_with("mock-token-2:"), "{}", response.refresh_token);
+}
+
+/// A service whose only user is ada@example.com with password "Correct-Horse-42"
+async fn service_with_ada() -> (AuthService, Arc<MockEncryption>) {
+    let models = models().await;
+    let encryption = Arc::new(MockEncryption::new());
+    let hash = encryption.hash_password("Correct-Horse-42").unwrap();
+    let ada = User::new_registration("Ada", "Lovelace", "ada@example.com", hash);
+    // Sign-in reads users from the in-memory repository, but sessions reference the SQLite users table
+    models.user.create(ada.clone()).await.unwrap();
+    let users = InMemoryUserRepository::with_users([ada]);
+    (AuthService::new(Arc::new(users), models.session.clone(), encryption.clone()), encryption)
+}
+
+#[tokio::test]
+async fn sign_in_with_the_right_password_issues_tokens() {
+    let (service, encryption) = service_with_ada().await;
+
+    let response = service
+        .sign_in(LoginRequest::new(" Ada@Example.com ", "Correct-Horse-42"), &ClientContext::default())
+        .await
+        .unwrap();
+    assert!(!response.access_token.is_empty());
+    assert_eq!(encryption.tokens_created(), 2);
+}
+
+#[tokio::test]
+async fn sign_in_refuses_unknown_emails_and_wrong_passwords_alike() {
+    let (service, encryption) = service_with_ada().await;
+    let client = ClientContext::default();
+
+    let unknown = service.sign_in(LoginRequest::new("nobody@example.com", "Correct-Horse-42"), &client).await;
+    assert!(matches!(unknown, Err(AuthError::InvalidCredentials)), "{:?}", unknown);
+
+    let wrong = service.sign_in(LoginRequest::new("ada@example.com", "Wrong-Horse-42"), &client).await;
+    assert!(matches!(wrong, Err(AuthError::InvalidCredentials)), "{:?}", wrong);
+
+    assert_eq!(encryption.tokens_created(), 0);
+}
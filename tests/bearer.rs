@@ -0,0 +1,50 @@
+mod common;
+
+use axum::body::Body;
+use axum::extract::Extension;
+use axum::http::{header, Request, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use common::TestApp;
+use node_server::shared::data::state::AppState;
+use node_server::shared::data::AuthUser;
+use node_server::shared::middlewares::auth::require_user_auth;
+use tower::ServiceExt;
+
+/// One route authenticated by the `AuthUser` extractor alone, one by `require_user_auth` alone
+fn router(app: &TestApp) -> Router {
+    let via_middleware = Router::new()
+        .route("/middleware", get(|Extension(user): Extension<AuthUser>| async move { user.id.to_string() }))
+        .layer(axum::middleware::from_fn(require_user_auth));
+
+    Router::new()
+        .route("/extractor", get(|user: AuthUser| async move { user.id.to_string() }))
+        .merge(via_middleware)
+        .layer(Extension(app.repositories.encryption.clone()))
+        .with_state(AppState::new(app.repositories.clone(), app.models.clone()))
+}
+
+async fn status(router: &Router, uri: &str, authorization: &str) -> StatusCode {
+    let request = Request::get(uri).header(header::AUTHORIZATION, authorization).body(Body::empty()).unwrap();
+    router.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn extractor_normalizes_tokens_like_the_middleware() {
+    let app = TestApp::new().await;
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+    let router = router(&app);
+
+    let cases = [
+        (format!("Bearer {}", access), StatusCode::OK),
+        (format!("Bearer \"{}\"", access), StatusCode::OK),
+        (format!("Bearer   {}  ", access), StatusCode::OK),
+        (format!("Bearer  \"{}\" ", access), StatusCode::OK),
+        (access.clone(), StatusCode::UNAUTHORIZED),
+        ("Bearer \"not-a-token\"".to_string(), StatusCode::UNAUTHORIZED),
+    ];
+    for (authorization, expected) in cases {
+        assert_eq!(status(&router, "/middleware", &authorization).await, expected, "middleware: {}", authorization);
+        assert_eq!(status(&router, "/extractor", &authorization).await, expected, "extractor: {}", authorization);
+    }
+}
@@ -0,0 +1,269 @@
+//! In-process harness: the real router on a fresh in-memory SQLite database, driven with
+//! `tower::ServiceExt::oneshot`, so tests exercise the same middleware stack as production
+
+#![allow(dead_code)]
+
+use axum::body::{to_bytes, Body, Bytes};
+use axum::http::{header, HeaderMap, Method, Request, StatusCode};
+use axum::Router;
+use chrono::Utc;
+use model::migration::{Migrator, MigratorTrait};
+use model::models::admin::entity::Model as AdminModel;
+use model::models::admin::repo::AdminRepositoryTrait;
+use model::models::user::model::{LoginRequest, RegisterRequest};
+use model::models::Models;
+use node_server::build_router;
+use node_server::shared::utils::config::AppConfig;
+use repository::repositories::crypto::data::CryptoConfig;
+use repository::repositories::crypto::CryptoRepository;
+use repository::repositories::encryption::data::{ArgonConfig, JwtConfig};
+use repository::repositories::encryption::EncryptionRepository;
+use repository::repositories::Repositories;
+use sea_orm::Database;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+use tracing::subscriber::DefaultGuard;
+use uuid::Uuid;
+
+pub const PASSWORD: &str = "Correct-Horse-42";
+
+pub struct TestApp {
+    pub router: Router,
+    pub models: Models,
+    pub repositories: Repositories,
+}
+
+/// Response status, headers and body parsed as JSON (`Value::Null` when empty or not JSON)
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Value,
+    /// The body as sent, for responses that aren't JSON
+    pub bytes: Bytes,
+}
+
+impl TestApp {
+    pub async fn new() -> Self {
+        Self::with_config(Self::config()).await
+    }
+
+    /// Config `new` builds the app with; tests that need other settings tweak it for `with_config`
+    pub fn config() -> AppConfig {
+        AppConfig {
+            database_url: "sqlite::memory:".to_string(),
+            production: false,
+            max_in_flight_requests: 64,
+            migrate_on_start: false,
+            cleanup_interval_seconds: 0,
+        }
+    }
+
+    pub async fn with_config(cfg: AppConfig) -> Self {
+        let db = Database::connect(&cfg.database_url).await.expect("open sqlite");
+        Migrator::up(&db, None).await.expect("migrate sqlite");
+        let models = Models::from_connection(db);
+        // Cheapest argon2 parameters: production costs make every sign-up and sign-in take seconds
+        let encryption = EncryptionRepository::new(
+            ArgonConfig { t_cost: 1, m_cost_kib: 8, p_cost: 1, pepper: None },
+            JwtConfig::from_env(),
+        );
+        let repositories = Repositories { encryption: Arc::new(encryption), ..Repositories::new() };
+
+        let router = build_router(&cfg, repositories.clone(), models.clone());
+
+        Self { router, models, repositories }
+    }
+
+    /// The same app and database with chain access configured by `config`, e.g. pointed at `rpc_stub`s
+    pub fn with_crypto(self, config: CryptoConfig) -> Self {
+        self.with_crypto_repository(CryptoRepository::new(config))
+    }
+
+    /// The same app and database with `crypto` behind the wallet and swap endpoints, e.g. one using a fake router
+    pub fn with_crypto_repository(self, crypto: CryptoRepository) -> Self {
+        let repositories = Repositories { crypto: Arc::new(crypto), ..self.repositories };
+        let router = build_router(&Self::config(), repositories.clone(), self.models.clone());
+        Self { router, models: self.models, repositories }
+    }
+
+    /// Send one request; `body` is sent as JSON and `token` as a Bearer token
+    pub async fn request(&self, method: Method, uri: &str, body: Option<Value>, token: Option<&str>) -> TestResponse {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("build request");
+
+        self.send(request).await
+    }
+
+    /// Send a request built by the test, for headers the helpers don't cover
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.expect("router is infallible");
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.expect("read body");
+        let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+
+        TestResponse { status, headers, body, bytes }
+    }
+
+    pub async fn get(&self, uri: &str, token: Option<&str>) -> TestResponse {
+        self.request(Method::GET, uri, None, token).await
+    }
+
+    pub async fn post(&self, uri: &str, body: Value, token: Option<&str>) -> TestResponse {
+        self.request(Method::POST, uri, Some(body), token).await
+    }
+
+    pub async fn sign_up(&self, email: &str) -> TestResponse {
+        self.post(
+            "/api/v1/user/auth/sign-up",
+            json!(RegisterRequest::new("Ada", "Lovelace", email, PASSWORD)),
+            None,
+        )
+        .await
+    }
+
+    pub async fn sign_in(&self, email: &str, password: &str) -> TestResponse {
+        self.post("/api/v1/user/auth/sign-in", json!(LoginRequest::new(email, password)), None)
+            .await
+    }
+
+    /// Sign up a new user and return its (access, refresh) token pair
+    pub async fn signed_up_user(&self, email: &str) -> (String, String) {
+        let response = self.sign_up(email).await;
+        assert_eq!(response.status, StatusCode::CREATED, "sign-up failed: {}", response.body);
+        tokens(&response)
+    }
+
+    /// Insert an admin with `PASSWORD` and return its access token
+    pub async fn admin_token(&self, email: &str) -> String {
+        let now = Utc::now().into();
+        let password = self.repositories.encryption.hash_password(PASSWORD).expect("hash password");
+        self.models
+            .admin
+            .create(AdminModel {
+                id: Uuid::new_v4(),
+                email_address: email.to_string(),
+                password,
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            })
+            .await
+            .expect("create admin");
+
+        let response = self
+            .post("/api/v1/admin/auth/sign-in", json!({ "email_address": email, "password": PASSWORD }), None)
+            .await;
+        assert_eq!(response.status, StatusCode::OK, "admin sign-in failed: {}", response.body);
+        response.body["data"]["access_token"].as_str().expect("admin access token").to_string()
+    }
+}
+
+/// Local JSON-RPC endpoint answering each method with a canned result and recording every
+/// method it's asked for; methods without a result get a JSON-RPC error
+pub struct RpcStub {
+    pub url: String,
+    methods: Arc<Mutex<Vec<String>>>,
+}
+
+impl RpcStub {
+    pub async fn start(results: &[(&str, &str)]) -> Self {
+        let results: Arc<std::collections::HashMap<String, String>> =
+            Arc::new(results.iter().map(|(m, r)| (m.to_string(), r.to_string())).collect());
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let recorded = methods.clone();
+        let handler = move |axum::Json(request): axum::Json<Value>| {
+            let (results, recorded) = (results.clone(), recorded.clone());
+            async move {
+                let method = request["method"].as_str().unwrap_or_default().to_string();
+                recorded.lock().unwrap().push(method.clone());
+                axum::Json(match results.get(&method) {
+                    Some(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+                    None => json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32601, "message": "method not found" } }),
+                })
+            }
+        };
+        Self::serve(Router::new().route("/", axum::routing::post(handler)), methods).await
+    }
+
+    /// Accepts requests but never answers them
+    pub async fn unresponsive() -> Self {
+        let handler = |_: axum::Json<Value>| std::future::pending::<()>();
+        Self::serve(Router::new().route("/", axum::routing::post(handler)), Arc::default()).await
+    }
+
+    async fn serve(router: Router, methods: Arc<Mutex<Vec<String>>>) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind rpc stub");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        Self { url, methods }
+    }
+
+    /// JSON-RPC methods called so far, in order
+    pub fn methods(&self) -> Vec<String> {
+        self.methods.lock().unwrap().clone()
+    }
+}
+
+/// (access, refresh) tokens of a sign-up, sign-in or refresh response
+pub fn tokens(response: &TestResponse) -> (String, String) {
+    let data = &response.body["data"];
+    (
+        data["access_token"].as_str().expect("access token").to_string(),
+        data["refresh_token"].as_str().expect("refresh token").to_string(),
+    )
+}
+
+/// Plain-text logs emitted on the current thread while it lives. Tests using it must run on the
+/// current-thread runtime (the `#[tokio::test]` default) so the app's tasks log on the same thread
+pub struct CapturedLogs {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    _guard: DefaultGuard,
+}
+
+struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    pub fn start() -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || CaptureWriter(writer.clone()))
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        Self { buffer, _guard }
+    }
+
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.lock().unwrap()).into_owned()
+    }
+
+    /// Lines logged on `target`
+    pub fn lines_for(&self, target: &str) -> Vec<String> {
+        let marker = format!(" {}:", target);
+        self.contents().lines().filter(|line| line.contains(&marker)).map(str::to_string).collect()
+    }
+}
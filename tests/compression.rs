@@ -0,0 +1,62 @@
+mod common;
+
+use std::io::Read;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use common::TestApp;
+use flate2::read::GzDecoder;
+use model::models::user::entity::Model as User;
+use model::models::user::repo::UserRepositoryTrait;
+use serde_json::Value;
+
+/// An admin token on an app with enough users that the list is worth compressing
+async fn app_with_users() -> (TestApp, String) {
+    let app = TestApp::new().await;
+    for i in 0..10 {
+        let email = format!("user{}@example.com", i);
+        app.models.user.create(User::new_registration("Ada", "Lovelace", &email, "hash")).await.unwrap();
+    }
+    let admin = app.admin_token("root@example.com").await;
+    (app, admin)
+}
+
+fn list_users(admin: &str, accept_encoding: Option<&str>) -> Request<Body> {
+    let mut builder = Request::get("/api/v1/admin/users").header(header::AUTHORIZATION, format!("Bearer {}", admin));
+    if let Some(accept_encoding) = accept_encoding {
+        builder = builder.header(header::ACCEPT_ENCODING, accept_encoding);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn gzips_large_responses_when_accepted() {
+    let (app, admin) = app_with_users().await;
+
+    let response = app.send(list_users(&admin, Some("br;q=1, gzip;q=0.8"))).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.headers[header::CONTENT_ENCODING], "gzip");
+    assert!(response.headers[header::VARY].to_str().unwrap().contains("accept-encoding"));
+
+    let mut json = String::new();
+    GzDecoder::new(&response.bytes[..]).read_to_string(&mut json).unwrap();
+    let body: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(body["status"], true);
+}
+
+#[tokio::test]
+async fn leaves_responses_alone_otherwise() {
+    let (app, admin) = app_with_users().await;
+
+    for accept_encoding in [None, Some("br"), Some("gzip;q=0")] {
+        let response = app.send(list_users(&admin, accept_encoding)).await;
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(!response.headers.contains_key(header::CONTENT_ENCODING), "{:?}", accept_encoding);
+        assert_eq!(response.body["status"], true);
+    }
+
+    // Below the size threshold
+    let response = app.send(Request::get("/health").header(header::ACCEPT_ENCODING, "gzip").body(Body::empty()).unwrap()).await;
+    assert!(!response.headers.contains_key(header::CONTENT_ENCODING));
+    assert_eq!(&response.bytes[..], b"OK");
+}
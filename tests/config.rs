@@ -0,0 +1,28 @@
+//! Flips the process environment, so it gets its own test binary and runs as a single test
+
+use node_server::shared::utils::config::{AppConfig, ConfigError, DEFAULT_MAX_IN_FLIGHT_REQUESTS};
+
+#[test]
+fn missing_and_malformed_variables_are_reported() {
+    std::env::remove_var("DATABASE_URL");
+    std::env::remove_var("MAX_IN_FLIGHT_REQUESTS");
+    std::env::remove_var("CLEANUP_INTERVAL_SECONDS");
+    let err = AppConfig::try_from_env().unwrap_err();
+    assert!(matches!(err, ConfigError::Missing("DATABASE_URL")), "{:?}", err);
+    assert_eq!(err.to_string(), "DATABASE_URL must be set");
+
+    std::env::set_var("DATABASE_URL", "postgres://localhost/trade");
+    let config = AppConfig::try_from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://localhost/trade");
+    assert_eq!(config.max_in_flight_requests, DEFAULT_MAX_IN_FLIGHT_REQUESTS);
+
+    std::env::set_var("MAX_IN_FLIGHT_REQUESTS", "abc");
+    let err = AppConfig::try_from_env().unwrap_err();
+    assert!(matches!(err, ConfigError::Invalid { var: "MAX_IN_FLIGHT_REQUESTS", .. }), "{:?}", err);
+    assert_eq!(err.to_string(), "MAX_IN_FLIGHT_REQUESTS=\"abc\" is invalid: expected a positive integer");
+
+    std::env::remove_var("MAX_IN_FLIGHT_REQUESTS");
+    std::env::set_var("CLEANUP_INTERVAL_SECONDS", "1h");
+    let err = AppConfig::try_from_env().unwrap_err();
+    assert!(matches!(err, ConfigError::Invalid { var: "CLEANUP_INTERVAL_SECONDS", .. }), "{:?}", err);
+}
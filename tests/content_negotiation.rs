@@ -0,0 +1,45 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use common::TestApp;
+
+fn get_accepting(uri: &str, accept: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder().method(Method::GET).uri(uri);
+    if let Some(accept) = accept {
+        builder = builder.header(header::ACCEPT, accept);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn health_is_plain_ok_unless_json_is_asked_for() {
+    let app = TestApp::new().await;
+
+    let plain = app.send(get_accepting("/health", None)).await;
+    assert_eq!(plain.status, StatusCode::OK);
+    assert_eq!(plain.bytes, "OK");
+
+    let json = app.send(get_accepting("/health", Some("application/json"))).await;
+    assert_eq!(json.status, StatusCode::OK);
+    assert_eq!(json.headers[header::CONTENT_TYPE], "application/json");
+    assert_eq!(json.body, serde_json::json!({ "status": true, "data": "OK" }));
+
+    // q-values pick the preferred type
+    let preferred = app.send(get_accepting("/health", Some("text/plain;q=0.5, application/json"))).await;
+    assert_eq!(preferred.body["data"], "OK");
+}
+
+#[tokio::test]
+async fn errors_are_plain_text_for_clients_that_prefer_it() {
+    let app = TestApp::new().await;
+
+    let json = app.send(get_accepting("/api/v1/user/profile", None)).await;
+    assert_eq!(json.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json.body["code"], "UNAUTHORIZED");
+
+    let plain = app.send(get_accepting("/api/v1/user/profile", Some("text/plain"))).await;
+    assert_eq!(plain.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(plain.headers[header::CONTENT_TYPE], "text/plain; charset=utf-8");
+    assert_eq!(plain.bytes, json.body["message"].as_str().unwrap());
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+
+#[tokio::test]
+async fn details_list_the_applied_migrations() {
+    let app = TestApp::new().await;
+
+    let response = app.get("/api/v1/health/details", None).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+
+    let data = &response.body["data"];
+    assert_eq!(data["database"]["reachable"], true);
+    let applied: Vec<&str> = data["migrations"]["applied_names"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|name| name.as_str().unwrap())
+        .collect();
+    assert!(applied.contains(&"m20251105_000001_init_schema"), "{:?}", applied);
+    assert_eq!(data["migrations"]["applied"], applied.len());
+    assert_eq!(data["migrations"]["pending_names"], serde_json::json!([]));
+    assert!(data["uptime_seconds"].is_u64());
+}
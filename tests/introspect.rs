@@ -0,0 +1,44 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, TestResponse};
+use repository::repositories::encryption::data::Token;
+use repository::repositories::encryption::EncryptionRepositoryExt;
+use serde_json::json;
+
+async fn introspect(app: &TestApp, token: &str, token_type: &str) -> TestResponse {
+    let response = app.post("/api/v1/auth/introspect", json!({ "token": token, "type": token_type }), None).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    response
+}
+
+#[tokio::test]
+async fn active_tokens_report_their_subject_and_lifetime() {
+    let app = TestApp::new().await;
+    let (access, refresh) = app.signed_up_user("ada@example.com").await;
+
+    let data = introspect(&app, &access, "access").await.body["data"].clone();
+    assert_eq!(data["active"], true);
+    assert!(data["sub"].is_string());
+    assert!(data["exp"].as_i64().unwrap() > chrono::Utc::now().timestamp());
+    assert!(data["remaining_seconds"].as_i64().unwrap() > 0);
+
+    assert_eq!(introspect(&app, &refresh, "refresh").await.body["data"]["active"], true);
+    // The type must match, an access token is not an active refresh token
+    assert_eq!(introspect(&app, &access, "refresh").await.body["data"]["active"], false);
+}
+
+#[tokio::test]
+async fn expired_and_garbage_tokens_are_inactive_not_errors() {
+    let app = TestApp::new().await;
+    let expired = app
+        .repositories
+        .encryption
+        .create_token_with("user-1", Token::user_access_token(), Default::default(), Some(-120))
+        .unwrap();
+
+    for token in [expired.as_str(), "not-a-token"] {
+        let body = introspect(&app, token, "access").await.body;
+        assert_eq!(body["data"], json!({ "active": false }), "{}", token);
+    }
+}
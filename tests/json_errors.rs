@@ -0,0 +1,51 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use common::TestApp;
+use serde_json::json;
+
+fn raw_post(uri: &str, content_type: &str, body: &'static str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn malformed_json_gets_a_json_error_body() {
+    let app = TestApp::new().await;
+
+    let response = app.send(raw_post("/api/v1/user/auth/sign-up", "application/json", "{\"email_address\": ")).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers[header::CONTENT_TYPE], "application/json");
+    assert_eq!(response.body["status"], false);
+    assert_eq!(response.body["code"], "INVALID_JSON");
+}
+
+#[tokio::test]
+async fn wrong_shape_and_content_type_also_get_json_error_bodies() {
+    let app = TestApp::new().await;
+
+    let response = app.post("/api/v1/user/auth/sign-in", json!({ "email_address": 42 }), None).await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(response.body["code"], "INVALID_JSON");
+
+    let response = app.send(raw_post("/api/v1/user/auth/sign-in", "text/plain", "hello")).await;
+    assert_eq!(response.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(response.body["code"], "INVALID_JSON");
+}
+
+#[tokio::test]
+async fn failed_validation_is_a_422_with_the_reason() {
+    let app = TestApp::new().await;
+
+    let response = app
+        .post("/api/v1/user/auth/sign-in", json!({ "email_address": "", "password": "" }), None)
+        .await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(response.body["code"], "VALIDATION_ERROR");
+    assert!(response.body["message"].as_str().is_some_and(|m| !m.is_empty()));
+}
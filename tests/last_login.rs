@@ -0,0 +1,27 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, PASSWORD};
+use model::models::user::repo::UserRepositoryTrait;
+
+#[tokio::test]
+async fn only_a_successful_sign_in_advances_last_login() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+    assert!(app.models.user.get_by_email("ada@example.com").await.unwrap().last_login_at.is_none());
+
+    assert_eq!(app.sign_in("ada@example.com", "Wrong-Horse-42").await.status, StatusCode::UNAUTHORIZED);
+    assert!(app.models.user.get_by_email("ada@example.com").await.unwrap().last_login_at.is_none());
+
+    assert_eq!(app.sign_in("ada@example.com", PASSWORD).await.status, StatusCode::OK);
+    let first = app.models.user.get_by_email("ada@example.com").await.unwrap().last_login_at.unwrap();
+
+    assert_eq!(app.sign_in("ada@example.com", "Wrong-Horse-42").await.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(app.models.user.get_by_email("ada@example.com").await.unwrap().last_login_at, Some(first));
+
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    assert_eq!(app.sign_in("ada@example.com", PASSWORD).await.status, StatusCode::OK);
+    let second = app.models.user.get_by_email("ada@example.com").await.unwrap().last_login_at.unwrap();
+    assert!(second > first);
+}
+
@@ -0,0 +1,35 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::http::{header, Method, Request, StatusCode};
+use common::TestApp;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn saturated_server_sheds_requests_on_other_routes() {
+    let mut cfg = TestApp::config();
+    cfg.max_in_flight_requests = 1;
+    let app = TestApp::with_config(cfg).await;
+
+    // A sign-in whose body never arrives holds the only slot until it's dropped
+    let body = futures::stream::pending::<Result<Bytes, std::io::Error>>();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/user/auth/sign-in")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from_stream(body))
+        .unwrap();
+    let blocked = tokio::spawn(app.router.clone().oneshot(request));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let response = app.get("/health", None).await;
+    assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.body["code"], "OVERLOADED");
+
+    blocked.abort();
+    let _ = blocked.await;
+    let response = app.get("/health", None).await;
+    assert_eq!(response.status, StatusCode::OK);
+}
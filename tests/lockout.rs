@@ -0,0 +1,79 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::{TestApp, PASSWORD};
+use futures::future::join_all;
+use model::models::user::repo::UserRepositoryTrait;
+
+const WRONG: &str = "Wrong-Horse-42";
+
+#[tokio::test]
+async fn wrong_passwords_are_counted_and_reset_on_success() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    for _ in 0..2 {
+        assert_eq!(app.sign_in("ada@example.com", WRONG).await.status, StatusCode::UNAUTHORIZED);
+    }
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    assert_eq!(user.failed_login_attempts, 2);
+    assert!(user.locked_until.is_none());
+
+    assert_eq!(app.sign_in("ada@example.com", PASSWORD).await.status, StatusCode::OK);
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    assert_eq!(user.failed_login_attempts, 0);
+}
+
+#[tokio::test]
+async fn concurrent_wrong_passwords_are_all_counted() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    let attempts = join_all((0..4).map(|_| app.sign_in("ada@example.com", WRONG))).await;
+    assert!(attempts.iter().all(|a| a.status == StatusCode::UNAUTHORIZED));
+
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    assert_eq!(user.failed_login_attempts, 4);
+}
+
+#[tokio::test]
+async fn reaching_the_threshold_locks_the_account() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    for _ in 0..4 {
+        assert_eq!(app.sign_in("ada@example.com", WRONG).await.status, StatusCode::UNAUTHORIZED);
+    }
+    // The fifth wrong password locks the account for the first window
+    let fifth = app.sign_in("ada@example.com", WRONG).await;
+    assert_eq!(fifth.status, StatusCode::LOCKED);
+    assert_eq!(fifth.body["code"], "ACCOUNT_LOCKED");
+    assert_eq!(fifth.headers["retry-after"], "60");
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    assert!(user.locked_until.is_some_and(|t| t > Utc::now()));
+
+    // Even the right password is refused until the lock runs out
+    let locked = app.sign_in("ada@example.com", PASSWORD).await;
+    assert_eq!(locked.status, StatusCode::LOCKED);
+    assert_eq!(locked.body["code"], "ACCOUNT_LOCKED");
+    let retry_after: i64 = locked.headers["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after), "{}", retry_after);
+}
+
+#[tokio::test]
+async fn the_lock_lifts_once_it_expires() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+    for _ in 0..5 {
+        app.sign_in("ada@example.com", WRONG).await;
+    }
+
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    app.models.user.set_locked_until(user.id, Some(Utc::now() - Duration::seconds(1))).await.unwrap();
+
+    assert_eq!(app.sign_in("ada@example.com", PASSWORD).await.status, StatusCode::OK);
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    assert_eq!(user.failed_login_attempts, 0);
+    assert!(user.locked_until.is_none());
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use common::TestApp;
+use model::migration::{Migrator, MigratorTrait};
+use node_server::shared::utils::config::AppConfig;
+use node_server::shared::utils::migrations;
+use sea_orm::Database;
+
+#[tokio::test]
+async fn boot_skips_migrations_when_the_toggle_is_off() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let cfg = AppConfig { migrate_on_start: false, ..TestApp::config() };
+
+    assert!(!migrations::run_on_start(&cfg, &db).await.unwrap());
+    // Nothing was applied, so every migration is still pending
+    assert!(!Migrator::get_pending_migrations(&db).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn boot_applies_migrations_by_default() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let cfg = AppConfig { migrate_on_start: true, ..TestApp::config() };
+
+    assert!(migrations::run_on_start(&cfg, &db).await.unwrap());
+    assert!(Migrator::get_pending_migrations(&db).await.unwrap().is_empty());
+}
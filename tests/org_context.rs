@@ -0,0 +1,125 @@
+mod common;
+
+use axum::http::{header, Method, Request, StatusCode};
+use axum::{body::Body, middleware, routing::get, Extension, Json, Router};
+use chrono::Utc;
+use common::TestApp;
+use model::models::organization::entity::Model as Organization;
+use model::models::organization::repo::OrganizationRepositoryTrait;
+use model::models::organization_user::entity::Model as Membership;
+use model::models::organization_user::repo::OrganizationUserRepositoryTrait;
+use model::models::user::repo::UserRepositoryTrait;
+use node_server::shared::data::state::AppState;
+use node_server::shared::extractors::org_context::{OrgContext, ORGANIZATION_HEADER};
+use node_server::shared::middlewares::auth::require_user_auth;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// No endpoint is scoped yet, so serve one that echoes the context behind the user auth middleware
+fn scoped_router(app: &TestApp) -> Router {
+    Router::new()
+        .route(
+            "/scoped",
+            get(|ctx: OrgContext| async move {
+                Json(json!({ "organization_id": ctx.organization_id, "user_id": ctx.user_id, "role": ctx.role }))
+            }),
+        )
+        .layer(middleware::from_fn(require_user_auth))
+        .layer(Extension(app.repositories.encryption.clone()))
+        .with_state(AppState::new(app.repositories.clone(), app.models.clone()))
+}
+
+/// An organization created by `creator_id`, with `member_id` as its admin
+async fn organization_with_member(app: &TestApp, creator_id: Uuid, member_id: Uuid) -> Uuid {
+    let now = Utc::now();
+    let organization = app
+        .models
+        .organization
+        .create(Organization {
+            id: Uuid::new_v4(),
+            name: "Acme".to_string(),
+            description: String::new(),
+            template: json!({ "version": 1 }),
+            project_template: json!({ "version": 1 }),
+            stage: "draft".to_string(),
+            status: "active".to_string(),
+            members: 1,
+            creator_id,
+            settings: json!({}),
+            created_at: now.into(),
+            updated_at: now.into(),
+            deleted_at: None,
+        })
+        .await
+        .expect("create organization");
+    app.models
+        .organization_user
+        .create(Membership {
+            id: Uuid::new_v4(),
+            user_id: member_id,
+            organization_id: organization.id,
+            dashboards: json!([]),
+            role: "admin".to_string(),
+            created_at: now.into(),
+            updated_at: now.into(),
+            deleted_at: None,
+        })
+        .await
+        .expect("create membership");
+    organization.id
+}
+
+async fn scoped_get(app: &TestApp, token: &str, organization: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(Method::GET)
+        .uri("/scoped")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token));
+    if let Some(organization) = organization {
+        request = request.header(ORGANIZATION_HEADER, organization);
+    }
+    let response = app.send(request.body(Body::empty()).unwrap()).await;
+    (response.status, response.body)
+}
+
+/// Signs up ada (a member) and grace (not one), then swaps in the scoped router
+async fn setup() -> (TestApp, Uuid, String, String) {
+    let app = TestApp::new().await;
+    let (ada_token, _) = app.signed_up_user("ada@example.com").await;
+    let (grace_token, _) = app.signed_up_user("grace@example.com").await;
+    let ada = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    let organization_id = organization_with_member(&app, ada.id, ada.id).await;
+    let app = TestApp { router: scoped_router(&app), ..app };
+    (app, organization_id, ada_token, grace_token)
+}
+
+#[tokio::test]
+async fn members_get_the_organization_context() {
+    let (app, organization_id, ada_token, _) = setup().await;
+
+    let (status, body) = scoped_get(&app, &ada_token, Some(&organization_id.to_string())).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["organization_id"], organization_id.to_string());
+    assert_eq!(body["role"], "admin");
+}
+
+#[tokio::test]
+async fn non_members_are_forbidden() {
+    let (app, organization_id, _, grace_token) = setup().await;
+
+    let (status, body) = scoped_get(&app, &grace_token, Some(&organization_id.to_string())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "NOT_ORGANIZATION_MEMBER");
+}
+
+#[tokio::test]
+async fn missing_or_malformed_header_is_a_bad_request() {
+    let (app, _, ada_token, _) = setup().await;
+
+    let (status, body) = scoped_get(&app, &ada_token, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "MISSING_ORGANIZATION");
+
+    let (status, body) = scoped_get(&app, &ada_token, Some("acme")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_ORGANIZATION");
+}
@@ -0,0 +1,78 @@
+//! `PasswordService` driven directly with test doubles instead of through the router
+
+mod common;
+
+use model::jobs::{EmailJob, JobEnvelope};
+use model::models::user::entity::Model as User;
+use model::models::user::mock::InMemoryUserRepository;
+use model::models::user::model::SendResetCodeRequest;
+use model::models::user::repo::UserRepositoryTrait;
+use node_server::features::user::auth::password::service::PasswordService;
+use node_server::shared::extractors::client_context::ClientContext;
+use repository::repositories::encryption::mock::MockEncryption;
+use common::CapturedLogs;
+use repository::repositories::queue::data::QueueNames;
+use repository::repositories::queue::mock::InMemoryQueue;
+use repository::repositories::queue::rabbitmq::RabbitMQRepository;
+use std::sync::Arc;
+
+const EMAIL_QUEUE: &str = "emails";
+
+fn ada() -> InMemoryUserRepository {
+    let ada = User::new_registration("Ada", "Lovelace", "ada@example.com", "mock-hash:Correct-Horse-42".to_string());
+    InMemoryUserRepository::with_users([ada])
+}
+
+/// A service whose only user is ada@example.com, publishing to an in-memory queue
+fn service_with_ada() -> (PasswordService, Arc<InMemoryQueue>) {
+    let queue = Arc::new(InMemoryQueue::new());
+    let service = PasswordService::new(Arc::new(ada()), Arc::new(MockEncryption::new()), queue.clone(), EMAIL_QUEUE.to_string());
+    (service, queue)
+}
+
+#[tokio::test]
+async fn queued_reset_email_carries_the_request_id() {
+    let (service, queue) = service_with_ada();
+    let client = ClientContext { request_id: Some("req-42".to_string()), ..Default::default() };
+
+    service.send_reset_code(SendResetCodeRequest::new("ada@example.com"), &client).await.unwrap();
+
+    let published = queue.published();
+    assert_eq!(published.len(), 1);
+    let (queue_name, payload) = &published[0];
+    assert_eq!(queue_name, EMAIL_QUEUE);
+    let envelope = JobEnvelope::<EmailJob>::from_bytes(payload).unwrap();
+    assert_eq!(envelope.correlation_id.as_deref(), Some("req-42"));
+    assert_eq!(envelope.payload.to, "ada@example.com");
+}
+
+#[tokio::test]
+async fn unreachable_broker_still_sends_the_code_and_warns() {
+    let logs = CapturedLogs::start();
+    let users = Arc::new(ada());
+    // Nothing listens on the discard port, so the connection is refused at once
+    let broker = Arc::new(RabbitMQRepository::new("amqp://127.0.0.1:9/%2f".to_string()));
+    let service = PasswordService::new(users.clone(), Arc::new(MockEncryption::new()), broker, EMAIL_QUEUE.to_string());
+
+    let response = service
+        .send_reset_code(SendResetCodeRequest::new("ada@example.com"), &ClientContext::default())
+        .await
+        .unwrap();
+
+    assert_eq!(response.email_address, "ada@example.com");
+    let stored = users.get_by_email("ada@example.com").await.unwrap();
+    assert!(stored.peripheral_authentication_code.is_some());
+    assert!(logs.contents().contains("reset code email not queued"), "{}", logs.contents());
+}
+
+#[tokio::test]
+async fn reset_email_is_published_to_the_prefixed_queue() {
+    let queue = Arc::new(InMemoryQueue::new());
+    let names = QueueNames::new(Some("prod"), "emails");
+    let service = PasswordService::new(Arc::new(ada()), Arc::new(MockEncryption::new()), queue.clone(), names.emails);
+
+    service.send_reset_code(SendResetCodeRequest::new("ada@example.com"), &ClientContext::default()).await.unwrap();
+
+    let queues: Vec<String> = queue.published().into_iter().map(|(name, _)| name).collect();
+    assert_eq!(queues, ["prod.emails"]);
+}
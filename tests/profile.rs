@@ -0,0 +1,84 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use common::{TestApp, TestResponse};
+use model::models::user::repo::UserRepositoryTrait;
+use serde_json::{json, Value};
+
+async fn get_profile(app: &TestApp, access: &str, if_none_match: Option<&str>) -> TestResponse {
+    let mut request = Request::builder()
+        .method(Method::GET)
+        .uri("/api/v1/user/profile")
+        .header(header::AUTHORIZATION, format!("Bearer {}", access));
+    if let Some(etag) = if_none_match {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    app.send(request.body(Body::empty()).unwrap()).await
+}
+
+fn etag(response: &TestResponse) -> String {
+    response.headers.get(header::ETAG).expect("ETag header").to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn profile_etag_answers_304_until_the_profile_changes() {
+    let app = TestApp::new().await;
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+
+    let first = get_profile(&app, &access, None).await;
+    assert_eq!(first.status, StatusCode::OK, "{}", first.body);
+    let first_etag = etag(&first);
+
+    let unchanged = get_profile(&app, &access, Some(&first_etag)).await;
+    assert_eq!(unchanged.status, StatusCode::NOT_MODIFIED);
+    assert!(unchanged.bytes.is_empty());
+
+    let updated = app
+        .request(
+            Method::PUT,
+            "/api/v1/user/profile",
+            Some(json!({ "first_name": "Augusta", "second_name": "King", "email_address": "ada@example.com" })),
+            Some(&access),
+        )
+        .await;
+    assert_eq!(updated.status, StatusCode::OK, "{}", updated.body);
+
+    let after_update = get_profile(&app, &access, Some(&first_etag)).await;
+    assert_eq!(after_update.status, StatusCode::OK, "{}", after_update.body);
+    assert_ne!(etag(&after_update), first_etag);
+    assert_eq!(after_update.body["data"]["personal"]["first_name"], "Augusta");
+}
+
+/// Every object key anywhere in `value`
+fn keys(value: &Value) -> Vec<String> {
+    match value {
+        Value::Object(map) => map.iter().flat_map(|(k, v)| std::iter::once(k.clone()).chain(keys(v))).collect(),
+        Value::Array(items) => items.iter().flat_map(keys).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn profile_never_includes_verification_or_reset_codes() {
+    let app = TestApp::new().await;
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+
+    // Give the user both kinds of stored code
+    let mut user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    user.verification_code = "424242".to_string();
+    app.models.user.update(user).await.unwrap();
+    let sent = app.post("/api/v1/user/auth/password/send-reset-code", json!({ "email_address": "ada@example.com" }), None).await;
+    assert_eq!(sent.status, StatusCode::OK, "{}", sent.body);
+    let reset_code = app.models.user.get_by_email("ada@example.com").await.unwrap().peripheral_authentication_code;
+    assert!(reset_code.is_some());
+
+    let profile = get_profile(&app, &access, None).await;
+    assert_eq!(profile.status, StatusCode::OK, "{}", profile.body);
+    let keys = keys(&profile.body);
+    assert!(!keys.iter().any(|k| k.contains("code")), "{:?}", keys);
+    assert!(keys.contains(&"is_verified".to_string()), "{:?}", keys);
+
+    let raw = String::from_utf8_lossy(&profile.bytes);
+    assert!(!raw.contains("424242") && !raw.contains(reset_code.unwrap().as_str()), "{}", raw);
+}
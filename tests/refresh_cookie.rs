@@ -0,0 +1,53 @@
+//! Runs with `REFRESH_TOKEN_COOKIE=true`, so it gets its own test binary
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use common::TestApp;
+
+fn cookie_mode() {
+    std::env::set_var("REFRESH_TOKEN_COOKIE", "true");
+}
+
+/// The refresh token from a `Set-Cookie` header, after checking its attributes
+fn refresh_cookie(response: &common::TestResponse) -> String {
+    let cookie = response.headers[header::SET_COOKIE].to_str().unwrap();
+    for attribute in ["HttpOnly", "Secure", "SameSite=Strict", "Path=/api"] {
+        assert!(cookie.split("; ").any(|part| part == attribute), "{} missing from {}", attribute, cookie);
+    }
+    let (pair, _) = cookie.split_once(';').unwrap();
+    pair.strip_prefix("refresh_token=").expect("the refresh_token cookie").to_string()
+}
+
+#[tokio::test]
+async fn sign_up_and_sign_in_set_the_refresh_cookie_instead_of_the_body_field() {
+    cookie_mode();
+    let app = TestApp::new().await;
+
+    for response in [app.sign_up("ada@example.com").await, app.sign_in("ada@example.com", common::PASSWORD).await] {
+        assert!(response.status.is_success(), "{}", response.body);
+        assert!(!refresh_cookie(&response).is_empty());
+        assert!(response.body["data"]["access_token"].is_string());
+        assert!(response.body["data"].get("refresh_token").is_none(), "{}", response.body);
+    }
+}
+
+#[tokio::test]
+async fn refresh_accepts_the_cookie_and_rotates_it() {
+    cookie_mode();
+    let app = TestApp::new().await;
+    let token = refresh_cookie(&app.sign_up("ada@example.com").await);
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/user/auth/refresh-token")
+        .header(header::COOKIE, format!("theme=dark; refresh_token={}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.send(request).await;
+
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert!(response.body["data"].get("refresh_token").is_none());
+    assert!(!refresh_cookie(&response).is_empty());
+}
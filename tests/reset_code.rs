@@ -0,0 +1,109 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use futures::future::join_all;
+use model::models::user::repo::UserRepositoryTrait;
+use serde_json::json;
+
+const MAX_CODE_ATTEMPTS: i32 = 5;
+
+/// Sign up `email`, have a reset code sent to it and return the stored code
+async fn code_sent_to(app: &TestApp, email: &str) -> String {
+    app.signed_up_user(email).await;
+    let sent = app.post("/api/v1/user/auth/password/send-reset-code", json!({ "email_address": email }), None).await;
+    assert_eq!(sent.status, StatusCode::OK, "{}", sent.body);
+    app.models.user.get_by_email(email).await.unwrap().peripheral_authentication_code.expect("stored code")
+}
+
+async fn verify(app: &TestApp, email: &str, code: &str) -> common::TestResponse {
+    app.post(
+        "/api/v1/user/auth/password/verify-reset-code",
+        json!({ "email_address": email, "auth_code": code }),
+        None,
+    )
+    .await
+}
+
+fn wrong(code: &str) -> String {
+    code.chars().map(|c| if c == '0' { '1' } else { '0' }).collect()
+}
+
+#[tokio::test]
+async fn the_right_code_verifies_and_resets_the_counter() {
+    let app = TestApp::new().await;
+    let code = code_sent_to(&app, "ada@example.com").await;
+
+    assert_eq!(verify(&app, "ada@example.com", &wrong(&code)).await.body["code"], "INVALID_CODE");
+
+    let verified = verify(&app, "ada@example.com", &code).await;
+    assert_eq!(verified.status, StatusCode::OK, "{}", verified.body);
+    assert!(verified.body["data"]["token"].is_string());
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    assert_eq!(user.peripheral_failed_code_attempts, 0);
+}
+
+#[tokio::test]
+async fn one_attempt_past_the_limit_is_locked_even_with_the_right_code() {
+    let app = TestApp::new().await;
+    let code = code_sent_to(&app, "ada@example.com").await;
+
+    for attempt in 1..=MAX_CODE_ATTEMPTS {
+        let response = verify(&app, "ada@example.com", &wrong(&code)).await;
+        let expected = if attempt < MAX_CODE_ATTEMPTS { "INVALID_CODE" } else { "CODE_LOCKED" };
+        assert_eq!(response.body["code"], expected, "attempt {}", attempt);
+    }
+
+    let locked = verify(&app, "ada@example.com", &code).await;
+    assert_eq!(locked.status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(locked.body["code"], "CODE_LOCKED");
+}
+
+#[tokio::test]
+async fn parallel_guesses_cannot_exceed_the_limit() {
+    let app = TestApp::new().await;
+    let code = code_sent_to(&app, "ada@example.com").await;
+    let guess = wrong(&code);
+
+    let responses = join_all((0..3 * MAX_CODE_ATTEMPTS).map(|_| verify(&app, "ada@example.com", &guess))).await;
+    let compared = responses.iter().filter(|r| r.body["code"] == "INVALID_CODE").count();
+    assert!(compared < MAX_CODE_ATTEMPTS as usize, "{} guesses were compared", compared);
+
+    let user = app.models.user.get_by_email("ada@example.com").await.unwrap();
+    assert_eq!(user.peripheral_failed_code_attempts, MAX_CODE_ATTEMPTS);
+    assert_eq!(verify(&app, "ada@example.com", &code).await.body["code"], "CODE_LOCKED");
+}
+
+#[tokio::test]
+async fn a_new_code_unlocks_verification() {
+    let app = TestApp::new().await;
+    let code = code_sent_to(&app, "ada@example.com").await;
+    for _ in 0..MAX_CODE_ATTEMPTS {
+        verify(&app, "ada@example.com", &wrong(&code)).await;
+    }
+
+    let resent = app.post("/api/v1/user/auth/password/send-reset-code", json!({ "email_address": "ada@example.com" }), None).await;
+    assert_eq!(resent.status, StatusCode::OK);
+    let code = app.models.user.get_by_email("ada@example.com").await.unwrap().peripheral_authentication_code.unwrap();
+
+    assert_eq!(verify(&app, "ada@example.com", &code).await.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn known_and_unknown_emails_get_the_same_response() {
+    let app = TestApp::new().await;
+    app.signed_up_user("ada@example.com").await;
+
+    let known = app.post("/api/v1/user/auth/password/send-reset-code", json!({ "email_address": "ada@example.com" }), None).await;
+    let unknown = app.post("/api/v1/user/auth/password/send-reset-code", json!({ "email_address": "eve@example.com" }), None).await;
+
+    assert_eq!(known.status, StatusCode::OK, "{}", known.body);
+    assert_eq!(unknown.status, known.status);
+    assert_eq!(known.body["data"]["email_address"], "ada@example.com");
+    // Only the echoed address may differ
+    let mut unknown_body = unknown.body.clone();
+    unknown_body["data"]["email_address"] = known.body["data"]["email_address"].clone();
+    assert_eq!(unknown_body, known.body);
+
+    assert!(app.models.user.get_by_email("eve@example.com").await.is_err());
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{tokens, TestApp, PASSWORD};
+use futures::future::join_all;
+use node_server::shared::middlewares::auth::decode_refresh_token;
+use serde_json::json;
+
+async fn refresh(app: &TestApp, refresh_token: &str) -> common::TestResponse {
+    app.post("/api/v1/user/auth/refresh-token", json!({ "refresh_token": refresh_token }), None).await
+}
+
+fn jti(app: &TestApp, refresh_token: &str) -> String {
+    decode_refresh_token(app.repositories.encryption.as_ref(), refresh_token).unwrap().jti.unwrap()
+}
+
+#[tokio::test]
+async fn a_replayed_refresh_token_is_refused() {
+    let app = TestApp::new().await;
+    let (_, refresh_token) = app.signed_up_user("ada@example.com").await;
+
+    let first = refresh(&app, &refresh_token).await;
+    assert_eq!(first.status, StatusCode::OK, "{}", first.body);
+
+    let replay = refresh(&app, &refresh_token).await;
+    assert_eq!(replay.status, StatusCode::UNAUTHORIZED, "{}", replay.body);
+    assert_eq!(replay.body["code"], "INVALID_TOKEN");
+
+    // The rotated token is unaffected by the replay
+    let (_, rotated) = tokens(&first);
+    assert_eq!(refresh(&app, &rotated).await.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn concurrent_refreshes_with_one_token_mint_one_pair() {
+    let app = TestApp::new().await;
+    let (_, refresh_token) = app.signed_up_user("ada@example.com").await;
+
+    let responses = join_all((0..4).map(|_| refresh(&app, &refresh_token))).await;
+    let succeeded = responses.iter().filter(|r| r.status == StatusCode::OK).count();
+    assert_eq!(succeeded, 1, "{:?}", responses.iter().map(|r| r.status).collect::<Vec<_>>());
+    assert!(responses
+        .iter()
+        .all(|r| r.status == StatusCode::OK || r.status == StatusCode::UNAUTHORIZED));
+}
+
+#[tokio::test]
+async fn revoking_one_session_leaves_the_others_valid() {
+    let app = TestApp::new().await;
+    let (access, laptop) = app.signed_up_user("ada@example.com").await;
+    let (_, phone) = tokens(&app.sign_in("ada@example.com", PASSWORD).await);
+
+    let listed = app.get("/api/v1/user/auth/sessions", Some(&access)).await;
+    assert_eq!(listed.status, StatusCode::OK, "{}", listed.body);
+    assert_eq!(listed.body["data"].as_array().unwrap().len(), 2);
+
+    let uri = format!("/api/v1/user/auth/sessions/{}", jti(&app, &phone));
+    let revoked = app.request(Method::DELETE, &uri, None, Some(&access)).await;
+    assert_eq!(revoked.status, StatusCode::OK, "{}", revoked.body);
+
+    assert_eq!(refresh(&app, &phone).await.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(refresh(&app, &laptop).await.status, StatusCode::OK);
+
+    // Revoking it again is a not-found, not a second success
+    let again = app.request(Method::DELETE, &uri, None, Some(&access)).await;
+    assert_eq!(again.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn refresh_accepts_the_token_in_the_authorization_header() {
+    let app = TestApp::new().await;
+    let (_, refresh_token) = app.signed_up_user("ada@example.com").await;
+
+    let response = app.request(Method::POST, "/api/v1/user/auth/refresh-token", None, Some(&refresh_token)).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    let (access, rotated) = tokens(&response);
+    assert!(!access.is_empty());
+    assert_ne!(rotated, refresh_token);
+}
+
+#[tokio::test]
+async fn refresh_accepts_the_token_in_the_body() {
+    let app = TestApp::new().await;
+    let (_, refresh_token) = app.signed_up_user("ada@example.com").await;
+
+    let response = refresh(&app, &refresh_token).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    let (access, rotated) = tokens(&response);
+    assert!(!access.is_empty());
+    assert_ne!(rotated, refresh_token);
+}
+
+#[tokio::test]
+async fn refresh_without_any_token_is_invalid_token() {
+    let app = TestApp::new().await;
+
+    let response = app.request(Method::POST, "/api/v1/user/auth/refresh-token", None, None).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED, "{}", response.body);
+    assert_eq!(response.body["code"], "INVALID_TOKEN");
+}
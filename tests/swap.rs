@@ -0,0 +1,176 @@
+mod common;
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use common::{RpcStub, TestApp};
+use ethers::types::{Address, Bytes, U256};
+use ethers::utils::to_checksum;
+use repository::repositories::crypto::blockchain_client::{BlockchainClient, RouteQuote};
+use repository::repositories::crypto::data::{
+    ChainDex, CryptoConfig, CryptoError, SingleChainSwap, SwapStatus, SwapType, TokenInfo, Wallet, DRY_RUN_TX_HASH,
+};
+use repository::repositories::crypto::router::{SwapCall, SwapRouter};
+use repository::repositories::crypto::CryptoRepository;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn status_rejects_a_malformed_hash_without_touching_the_chain() {
+    let app = TestApp::new().await;
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+
+    for hash in ["nothex", "0x1234", &format!("0x{}", "zz".repeat(32))] {
+        let response = app.get(&format!("/api/v1/user/swap/status/{}", hash), Some(&access)).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST, "{}: {}", hash, response.body);
+        assert_eq!(response.body["code"], "SWAP_ERROR");
+    }
+}
+
+fn quote_request(slippage: &str) -> serde_json::Value {
+    json!({
+        "SingleChain": {
+            "chain": "bsc",
+            "from_token": { "symbol": "BNB", "address": null, "decimals": 18 },
+            "to_token": { "symbol": "CAKE", "address": "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82", "decimals": 18 },
+            "amount": "1",
+            "slippage": slippage,
+            "dex": null,
+        }
+    })
+}
+
+#[tokio::test]
+async fn quote_rejects_out_of_range_slippage_before_any_rpc_work() {
+    let app = TestApp::new().await;
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+
+    for slippage in ["75", "-1", "lots"] {
+        let response = app.post("/api/v1/user/swap/quote", quote_request(slippage), Some(&access)).await;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST, "{}: {}", slippage, response.body);
+        assert_eq!(response.body["code"], "SWAP_ERROR");
+    }
+}
+
+const WBNB: &str = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c";
+const CAKE: &str = "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82";
+
+/// Quotes a fixed output on the direct path without touching the chain, and records the calls it gets
+struct FakeRouter {
+    amount_out: U256,
+    calls: Mutex<Vec<&'static str>>,
+}
+
+impl FakeRouter {
+    fn quoting(amount_out: U256) -> Self {
+        Self { amount_out, calls: Mutex::new(Vec::new()) }
+    }
+}
+
+#[async_trait]
+impl SwapRouter for FakeRouter {
+    async fn quote(
+        &self,
+        _client: &BlockchainClient,
+        _chain: &ChainDex<'_>,
+        token_in: Address,
+        token_out: Address,
+        _amount_in: U256,
+    ) -> Result<RouteQuote, CryptoError> {
+        self.calls.lock().unwrap().push("quote");
+        Ok(RouteQuote { path: vec![token_in, token_out], amount_out: self.amount_out, price_impact: 0.0 })
+    }
+
+    async fn build_tx(
+        &self,
+        _chain: &ChainDex<'_>,
+        _quote: &RouteQuote,
+        _amount_in: U256,
+        _amount_out_min: U256,
+        _recipient: Address,
+    ) -> Result<SwapCall, CryptoError> {
+        self.calls.lock().unwrap().push("build_tx");
+        Ok(SwapCall { to: Address::repeat_byte(0x42), data: Bytes::new(), value: U256::zero() })
+    }
+}
+
+/// A chain whose balance and allowance checks pass and whose gas costs 200k at 5 gwei
+async fn funded_chain() -> RpcStub {
+    RpcStub::start(&[
+        ("eth_call", "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+        ("eth_estimateGas", "0x30d40"),
+        ("eth_gasPrice", "0x12a05f200"),
+    ])
+    .await
+}
+
+fn wbnb_to_cake(dry_run: bool) -> SwapType {
+    SwapType::SingleChain(SingleChainSwap {
+        chain: "bsc".to_string(),
+        from_token: TokenInfo { symbol: "WBNB".to_string(), address: Some(WBNB.to_string()), decimals: 18 },
+        to_token: TokenInfo { symbol: "CAKE".to_string(), address: Some(CAKE.to_string()), decimals: 18 },
+        amount: "1".to_string(),
+        slippage: "1".to_string(),
+        dex: None,
+        wait_for_receipt: false,
+        dry_run,
+    })
+}
+
+fn wallet() -> Wallet {
+    let address = to_checksum(&Address::repeat_byte(0x11), None);
+    Wallet::new(address, "11".repeat(32), String::new())
+}
+
+#[tokio::test]
+async fn dry_run_returns_the_quote_without_broadcasting() {
+    let chain = funded_chain().await;
+    let mut config = CryptoConfig::from_env();
+    config.rpc_endpoints.insert("bsc".to_string(), chain.url.clone());
+    let router = FakeRouter::quoting(U256::exp10(18) * 100);
+
+    let result = wallet().swap_tokens(&config, &router, wbnb_to_cake(true)).await.unwrap();
+
+    assert_eq!(result.tx_hash, DRY_RUN_TX_HASH);
+    assert!(matches!(result.status, SwapStatus::Pending), "{:?}", result.status);
+    assert_eq!(result.amount_out.parse::<f64>().unwrap(), 100.0);
+    assert_eq!(result.min_amount_out.parse::<f64>().unwrap(), 99.0);
+    assert_eq!(result.fee.parse::<f64>().unwrap(), 0.001);
+    assert_eq!(result.route, [WBNB, CAKE]);
+    // Balance and allowance reads plus the estimate; nothing is signed or sent
+    assert_eq!(chain.methods(), ["eth_call", "eth_call", "eth_estimateGas", "eth_gasPrice"]);
+
+    // Without the flag the same swap goes on to sign, which starts by asking for the chain id
+    let live = funded_chain().await;
+    config.rpc_endpoints.insert("bsc".to_string(), live.url.clone());
+    assert!(wallet().swap_tokens(&config, &router, wbnb_to_cake(false)).await.is_err());
+    assert!(live.methods().contains(&"eth_chainId".to_string()), "{:?}", live.methods());
+}
+
+#[tokio::test]
+async fn swaps_are_priced_and_built_by_the_router_they_are_given() {
+    let chain = funded_chain().await;
+    let mut config = CryptoConfig::from_env();
+    config.rpc_endpoints.insert("bsc".to_string(), chain.url.clone());
+    let router = FakeRouter::quoting(U256::exp10(18) * 42);
+
+    let result = wallet().swap_tokens(&config, &router, wbnb_to_cake(true)).await.unwrap();
+
+    assert_eq!(*router.calls.lock().unwrap(), ["quote", "build_tx"]);
+    assert_eq!(result.amount_out.parse::<f64>().unwrap(), 42.0);
+    assert_eq!(result.min_amount_out.parse::<f64>().unwrap(), 41.58);
+    assert_eq!(result.route, [WBNB, CAKE]);
+}
+
+#[tokio::test]
+async fn quotes_are_priced_by_the_repositorys_router() {
+    let router = Arc::new(FakeRouter::quoting(U256::exp10(18) * 42));
+    let crypto = CryptoRepository::new(CryptoConfig::from_env()).with_router(router.clone());
+    let app = TestApp::new().await.with_crypto_repository(crypto);
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+
+    let response = app.post("/api/v1/user/swap/quote", quote_request("1"), Some(&access)).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert_eq!(response.body["data"]["amount_out"].as_str().unwrap().parse::<f64>().unwrap(), 42.0);
+    assert_eq!(response.body["data"]["route"], json!([WBNB, CAKE]));
+    assert_eq!(*router.calls.lock().unwrap(), ["quote"]);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use repository::repositories::crypto::data::CryptoConfig;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn create_returns_an_address_and_a_seed_phrase() {
+    let app = TestApp::new().await;
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+
+    let response = app.post("/api/v1/user/wallet/create", json!({}), Some(&access)).await;
+    assert_eq!(response.status, StatusCode::CREATED, "{}", response.body);
+
+    let address = response.body["data"]["address"].as_str().unwrap();
+    assert!(address.starts_with("0x") && address.len() == 42, "{}", address);
+    assert!(address[2..].chars().all(|c| c.is_ascii_hexdigit()));
+
+    let words = response.body["data"]["seed_phrase"].as_str().unwrap().split_whitespace().count();
+    assert!(words == 12 || words == 24, "{} words", words);
+}
+
+#[tokio::test]
+async fn create_requires_a_token() {
+    let app = TestApp::new().await;
+
+    let response = app.post("/api/v1/user/wallet/create", json!({}), None).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn balances_of_someone_elses_wallet_are_not_found() {
+    let app = TestApp::new().await;
+    let (owner, _) = app.signed_up_user("ada@example.com").await;
+    let (other, _) = app.signed_up_user("bob@example.com").await;
+
+    let created = app.post("/api/v1/user/wallet/create", json!({}), Some(&owner)).await;
+    let address = created.body["data"]["address"].as_str().unwrap().to_string();
+
+    let response = app.get(&format!("/api/v1/user/wallet/{}/balances", address), Some(&other)).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND, "{}", response.body);
+    assert_eq!(response.body["code"], "WALLET_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn a_slow_chain_times_out_without_holding_back_the_others() {
+    let mut config = CryptoConfig::default();
+    // 1 BNB on the responsive chain; the other never answers
+    config.rpc_endpoints.insert("bsc".to_string(), common::RpcStub::start(&[("eth_getBalance", "0xde0b6b3a7640000")]).await.url);
+    config.rpc_endpoints.insert("eth".to_string(), common::RpcStub::unresponsive().await.url);
+    config.native_symbols.insert("bsc".to_string(), "BNB".to_string());
+    config.balance_timeout = Duration::from_millis(300);
+    let app = TestApp::new().await.with_crypto(config);
+
+    let (access, _) = app.signed_up_user("ada@example.com").await;
+    let created = app.post("/api/v1/user/wallet/create", json!({}), Some(&access)).await;
+    let address = created.body["data"]["address"].as_str().unwrap().to_string();
+
+    let started = Instant::now();
+    let response = app.get(&format!("/api/v1/user/wallet/{}/balances", address), Some(&access)).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert!(started.elapsed() < Duration::from_secs(2), "took {:?}", started.elapsed());
+
+    let chains = &response.body["data"]["chains"];
+    assert_eq!(chains[0]["chain"], "bsc");
+    assert_eq!(chains[0]["error"], Value::Null);
+    assert_eq!(chains[0]["balances"][0]["symbol"], "BNB");
+    assert_eq!(chains[0]["balances"][0]["amount"].as_str().unwrap().parse::<f64>().unwrap(), 1.0);
+    assert_eq!(chains[1], json!({ "chain": "eth", "balances": [], "error": "TIMEOUT" }));
+}